@@ -0,0 +1,176 @@
+//! IMA-ADPCM audio decoding.
+//!
+//! IMA-ADPCM stores each sample in 4 bits, a quarter the size of 16-bit PCM
+//! (or half of the 8-bit PCM that DirectSound plays), with decoding cheap
+//! enough to do while streaming. Samples are packed two to a byte, low nibble
+//! first, which is how WAV files and most tools store them.
+//!
+//! On the GBA the decode loop is placed in IWRAM and compiled as ARM code,
+//! unless the `iwram_adpcm` feature is turned off to save IWRAM.
+//! Feeding the output to the DirectSound FIFO is up to the caller.
+
+/// How much the step size changes after each nibble.
+const INDEX_TABLE: [i8; 8] = [-1, -1, -1, -1, 2, 4, 6, 8];
+
+/// The step sizes.
+const STEP_TABLE: [u16; 89] = [
+    7, 8, 9, 10, 11, 12, 13, 14, 16, 17, 19, 21, 23, 25, 28, 31, 34, 37, 41, 45, 50, 55, 60, 66,
+    73, 80, 88, 97, 107, 118, 130, 143, 157, 173, 190, 209, 230, 253, 279, 307, 337, 371, 408,
+    449, 494, 544, 598, 658, 724, 796, 876, 963, 1060, 1166, 1282, 1411, 1552, 1707, 1878, 2066,
+    2272, 2499, 2749, 3024, 3327, 3660, 4026, 4428, 4871, 5358, 5894, 6484, 7132, 7845, 8630,
+    9493, 10442, 11487, 12635, 13899, 15289, 16818, 18500, 20350, 22385, 24623, 27086, 29794,
+    32767,
+];
+
+/// The largest step index.
+const MAX_INDEX: u8 = STEP_TABLE.len() as u8 - 1;
+
+/// The state of an ADPCM stream: the last sample, and the step size index.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct AdpcmDecoder {
+    predictor: i16,
+    index: u8,
+}
+impl AdpcmDecoder {
+    /// Starts decoding from the given sample and step index.
+    ///
+    /// Streams that were encoded in blocks (like WAV files) store these at
+    /// the start of each block. Otherwise, start from `(0, 0)`.
+    ///
+    /// ## Panics
+    /// * If `index` is more than 88.
+    #[inline]
+    #[must_use]
+    #[cfg_attr(feature = "track_caller", track_caller)]
+    pub const fn new(predictor: i16, index: u8) -> Self {
+        assert!(index <= MAX_INDEX, "ADPCM step index out of range.");
+        Self { predictor, index }
+    }
+
+    /// The last decoded sample.
+    #[inline]
+    #[must_use]
+    pub const fn predictor(&self) -> i16 {
+        self.predictor
+    }
+
+    /// The current step size index.
+    #[inline]
+    #[must_use]
+    pub const fn index(&self) -> u8 {
+        self.index
+    }
+
+    /// Decodes one 4-bit code (the upper bits are ignored) into a sample.
+    #[inline]
+    pub fn decode_nibble(&mut self, nibble: u8) -> i16 {
+        self.decode_one(nibble)
+    }
+
+    /// The body of [`decode_nibble`](Self::decode_nibble), built as ARM code
+    /// like the decode loops so that it can be inlined into them, rather than
+    /// each sample being a call out to Thumb code in ROM.
+    #[inline(always)]
+    #[cfg_attr(
+        all(feature = "on_gba", feature = "iwram_adpcm", target_arch = "arm"),
+        link_section = ".iwram.adpcm_decode_one",
+        instruction_set(arm::a32)
+    )]
+    fn decode_one(&mut self, nibble: u8) -> i16 {
+        let step = i32::from(STEP_TABLE[usize::from(self.index)]);
+        let mut diff = step >> 3;
+        if nibble & 4 != 0 {
+            diff += step;
+        }
+        if nibble & 2 != 0 {
+            diff += step >> 1;
+        }
+        if nibble & 1 != 0 {
+            diff += step >> 2;
+        }
+        let p = i32::from(self.predictor);
+        let p = if nibble & 8 != 0 { p - diff } else { p + diff };
+        self.predictor = p.clamp(i16::MIN.into(), i16::MAX.into()) as i16;
+        let index = self.index as i8 + INDEX_TABLE[usize::from(nibble & 7)];
+        self.index = index.clamp(0, MAX_INDEX as i8) as u8;
+        self.predictor
+    }
+
+    /// Decodes bytes into 8-bit samples, the format DirectSound plays.
+    ///
+    /// Each byte gives two samples. Decodes as many bytes as there's room for
+    /// in `out`, and returns how many bytes were used.
+    #[cfg_attr(
+        all(feature = "on_gba", feature = "iwram_adpcm", target_arch = "arm"),
+        link_section = ".iwram.adpcm_decode",
+        instruction_set(arm::a32)
+    )]
+    pub fn decode(&mut self, input: &[u8], out: &mut [i8]) -> usize {
+        let mut used = 0;
+        for (&byte, pair) in input.iter().zip(out.chunks_exact_mut(2)) {
+            pair[0] = (self.decode_one(byte) >> 8) as i8;
+            pair[1] = (self.decode_one(byte >> 4) >> 8) as i8;
+            used += 1;
+        }
+        used
+    }
+
+    /// Decodes bytes into 16-bit samples, such as for a software mixer.
+    ///
+    /// Each byte gives two samples. Decodes as many bytes as there's room for
+    /// in `out`, and returns how many bytes were used.
+    #[cfg_attr(
+        all(feature = "on_gba", feature = "iwram_adpcm", target_arch = "arm"),
+        link_section = ".iwram.adpcm_decode_i16",
+        instruction_set(arm::a32)
+    )]
+    pub fn decode_i16(&mut self, input: &[u8], out: &mut [i16]) -> usize {
+        let mut used = 0;
+        for (&byte, pair) in input.iter().zip(out.chunks_exact_mut(2)) {
+            pair[0] = self.decode_one(byte);
+            pair[1] = self.decode_one(byte >> 4);
+            used += 1;
+        }
+        used
+    }
+}
+
+/// Encodes 16-bit samples as IMA-ADPCM, starting from `AdpcmDecoder::new(0, 0)`.
+///
+/// An odd number of samples is padded with a repeat of the last sample.
+#[cfg(feature = "std")]
+#[must_use]
+pub fn encode(samples: &[i16]) -> std::vec::Vec<u8> {
+    // Encoding tracks a decoder, so that errors don't accumulate.
+    let mut decoder = AdpcmDecoder::new(0, 0);
+    let mut encode_one = |sample: i16| {
+        let step = i32::from(STEP_TABLE[usize::from(decoder.index)]);
+        let mut diff = i32::from(sample) - i32::from(decoder.predictor);
+        let mut nibble = 0;
+        if diff < 0 {
+            nibble = 8;
+            diff = -diff;
+        }
+        if diff >= step {
+            nibble |= 4;
+            diff -= step;
+        }
+        if diff >= step >> 1 {
+            nibble |= 2;
+            diff -= step >> 1;
+        }
+        if diff >= step >> 2 {
+            nibble |= 1;
+        }
+        decoder.decode_nibble(nibble);
+        nibble
+    };
+    samples
+        .chunks(2)
+        .map(|pair| {
+            let low = encode_one(pair[0]);
+            let high = encode_one(*pair.get(1).unwrap_or(&pair[0]));
+            low | (high << 4)
+        })
+        .collect()
+}
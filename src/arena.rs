@@ -0,0 +1,134 @@
+//! Building strings in a borrowed buffer.
+//!
+//! An [`ArenaString`] writes into a byte buffer the caller provides, such as a
+//! `static mut` array in EWRAM, so occasional string building (assembling
+//! dialog, debug text) needs neither a global allocator nor a large buffer on
+//! the small IWRAM stack. The [`format_in!`](crate::format_in) macro is the
+//! quick way to use one:
+//!
+//! ```
+//! # use gba_cell::format_in;
+//! let mut buffer = [0_u8; 32];
+//! let text = format_in!(&mut buffer, "Found {} coins!", 12).unwrap();
+//! assert_eq!(text, "Found 12 coins!");
+//! ```
+
+use core::fmt::{self, Debug, Display, Write};
+use core::ops::Deref;
+
+/// A string stored in a borrowed buffer, with a fixed capacity.
+///
+/// A piece of text that doesn't fit fails with [`fmt::Error`] and isn't added.
+/// A formatted write is made of several pieces, so when one fails the pieces
+/// before it are still in the string.
+pub struct ArenaString<'a> {
+    buffer: &'a mut [u8],
+    len: usize,
+}
+impl<'a> ArenaString<'a> {
+    /// Makes an empty string that can use all of `buffer`.
+    #[inline]
+    #[must_use]
+    pub fn new(buffer: &'a mut [u8]) -> Self {
+        Self { buffer, len: 0 }
+    }
+
+    /// The string's contents.
+    #[inline]
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        // SAFETY: Only whole `str`s are ever copied in, so the bytes up to
+        // `len` are valid UTF-8.
+        unsafe { core::str::from_utf8_unchecked(&self.buffer[..self.len]) }
+    }
+
+    /// Gives up the buffer, returning the string with the buffer's lifetime.
+    #[inline]
+    #[must_use]
+    pub fn into_str(self) -> &'a str {
+        // SAFETY: as in `as_str`.
+        unsafe { core::str::from_utf8_unchecked(&self.buffer[..self.len]) }
+    }
+
+    /// The length in bytes.
+    #[inline]
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// If the string has no bytes.
+    #[inline]
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The most bytes the string can hold.
+    #[inline]
+    #[must_use]
+    pub const fn capacity(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Empties the string, to reuse the buffer.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+
+    /// Appends a string, or does nothing and returns an error if it doesn't
+    /// fit.
+    #[inline]
+    pub fn push_str(&mut self, s: &str) -> Result<(), fmt::Error> {
+        let end = self.len + s.len();
+        let dest = self.buffer.get_mut(self.len..end).ok_or(fmt::Error)?;
+        dest.copy_from_slice(s.as_bytes());
+        self.len = end;
+        Ok(())
+    }
+}
+
+impl Write for ArenaString<'_> {
+    #[inline]
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.push_str(s)
+    }
+}
+
+impl Deref for ArenaString<'_> {
+    type Target = str;
+    #[inline]
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl Debug for ArenaString<'_> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl Display for ArenaString<'_> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Display::fmt(self.as_str(), f)
+    }
+}
+
+/// Formats into a byte buffer, like `format!`, giving a `Result<&str,
+/// fmt::Error>`.
+///
+/// The error means the text didn't fit in the buffer.
+#[macro_export]
+macro_rules! format_in {
+    ($buffer:expr, $($arg:tt)*) => {{
+        let mut s = $crate::arena::ArenaString::new($buffer);
+        match ::core::fmt::Write::write_fmt(&mut s, ::core::format_args!($($arg)*)) {
+            ::core::result::Result::Ok(()) => ::core::result::Result::Ok(s.into_str()),
+            ::core::result::Result::Err(e) => ::core::result::Result::Err(e),
+        }
+    }};
+}
@@ -0,0 +1,65 @@
+//! The [`arm_fn!`](crate::arm_fn) macro, for hot functions.
+
+/// Places functions in IWRAM, compiled as ARM code.
+///
+/// Code in ROM is read over a 16-bit bus with waitstates, which is why GBA
+/// code is normally compiled as Thumb. For a hot inner loop it's usually
+/// several times faster to instead run ARM code from IWRAM, which has a
+/// 32-bit bus and no waitstates. That takes both the `instruction_set` and
+/// `link_section` attributes, which this macro adds to each function it's
+/// given. Each function gets its own `.iwram.<name>` section.
+///
+/// ```
+/// gba_cell::arm_fn! {
+///     /// Adds up some samples.
+///     pub fn sum(samples: &[i16]) -> i32 {
+///         samples.iter().map(|&s| i32::from(s)).sum()
+///     }
+/// }
+/// ```
+///
+/// IWRAM is only 32 KiB, shared with the stack, so keep to the functions that
+/// profiling shows are hot.
+///
+/// Only the function's own body becomes ARM code. Calls to other functions
+/// (including closures) that aren't inlined still run as whatever they were
+/// compiled as, and a Thumb function can't be inlined into an ARM one. Put
+/// the whole inner loop in the function rather than passing it a closure.
+///
+/// When not building for the GBA (the `on_gba` feature on an ARM target),
+/// the functions are left unchanged.
+#[cfg(all(feature = "on_gba", target_arch = "arm"))]
+#[macro_export]
+macro_rules! arm_fn {
+    ($($(#[$m:meta])* $vis:vis fn $name:ident $params:tt $(-> $ret:ty)? $body:block)*) => {
+        $(
+            $(#[$m])*
+            #[instruction_set(arm::a32)]
+            #[link_section = concat!(".iwram.", stringify!($name))]
+            $vis fn $name $params $(-> $ret)? $body
+        )*
+    };
+}
+
+/// Places functions in IWRAM, compiled as ARM code.
+///
+/// This isn't building for the GBA, so the functions are left unchanged.
+///
+/// ```
+/// gba_cell::arm_fn! {
+///     /// Adds up some samples.
+///     pub fn sum(samples: &[i16]) -> i32 {
+///         samples.iter().map(|&s| i32::from(s)).sum()
+///     }
+/// }
+/// ```
+#[cfg(not(all(feature = "on_gba", target_arch = "arm")))]
+#[macro_export]
+macro_rules! arm_fn {
+    ($($(#[$m:meta])* $vis:vis fn $name:ident $params:tt $(-> $ret:ty)? $body:block)*) => {
+        $(
+            $(#[$m])*
+            $vis fn $name $params $(-> $ret)? $body
+        )*
+    };
+}
@@ -0,0 +1,294 @@
+//! A packed directory of assets, looked up by name.
+//!
+//! Instead of one `include_bytes!` constant per file, a build script packs
+//! every asset into one archive with [`build`], and the game includes just
+//! that. Assets are found by the hash of their name, and the
+//! [`asset!`](crate::asset) macro does the lookup at compile time, so a
+//! misspelled name is a build error rather than a missing sprite.
+//!
+//! ```
+//! # use gba_cell::{asset, assets::{Aligned, AssetDir}};
+//! # macro_rules! include_bytes { ($f:literal) => {
+//! #     b"ADIR\x01\0\0\0\x86\x52\x5a\x08\x14\0\0\0\x05\0\0\0hero!\0\0\0"
+//! # } }
+//! const BYTES: &Aligned<[u8]> = &Aligned(*include_bytes!("assets.bin"));
+//! const ASSETS: AssetDir<'static> = match AssetDir::new(&BYTES.0) {
+//!     Ok(dir) => dir,
+//!     Err(_) => panic!("bad asset directory"),
+//! };
+//!
+//! let hero = asset!(ASSETS, "sprites/hero");
+//! assert_eq!(hero.data(), b"hero!");
+//! ```
+//!
+//! ## Format
+//!
+//! All numbers are little-endian `u32`s.
+//! * The magic bytes `ADIR`, then the number of entries.
+//! * Each entry: the [`name_hash`] of its name, the offset of its data from
+//!   the start of the directory, and the length of its data. The top bit of
+//!   the length is set if the data is LZ77 compressed. Entries are sorted by
+//!   hash.
+//! * The data of each asset, each starting on a 4 byte boundary.
+//!
+//! That boundary is relative to the start of the directory, so the data is
+//! only 4-aligned in memory if the directory is too. `include_bytes!` makes
+//! no promise about alignment, so wrap it in [`Aligned`] as above.
+
+use crate::lz77::{self, Lz77Error};
+
+/// The magic bytes at the start of a directory.
+pub const MAGIC: [u8; 4] = *b"ADIR";
+
+/// The length bit marking compressed data.
+const COMPRESSED: u32 = 1 << 31;
+
+/// The size of the header, and of each entry.
+const HEADER_LEN: usize = 8;
+const ENTRY_LEN: usize = 12;
+
+/// Bytes placed on a 4 byte boundary, so that the data in an [`AssetDir`]
+/// can be read as halfwords or words, or handed to DMA or the BIOS.
+///
+/// Wrap the array from `include_bytes!` in this, then unsize the reference
+/// to `&Aligned<[u8]>` to avoid naming its length.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(C, align(4))]
+pub struct Aligned<B: ?Sized>(pub B);
+
+/// The ways a directory can be invalid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AssetDirError {
+    /// The magic bytes are missing.
+    BadMagic,
+    /// The entries, or the data of an entry, go past the end.
+    Truncated,
+    /// The entries aren't sorted by hash, or two have the same hash.
+    Unsorted,
+}
+
+/// The 32-bit FNV-1a hash of an asset name.
+#[must_use]
+pub const fn name_hash(name: &str) -> u32 {
+    let bytes = name.as_bytes();
+    let mut hash = 0x811C_9DC5_u32;
+    let mut i = 0;
+    while i < bytes.len() {
+        hash ^= bytes[i] as u32;
+        hash = hash.wrapping_mul(0x0100_0193);
+        i += 1;
+    }
+    hash
+}
+
+#[inline]
+const fn read_u32(bytes: &[u8], at: usize) -> u32 {
+    u32::from_le_bytes([bytes[at], bytes[at + 1], bytes[at + 2], bytes[at + 3]])
+}
+
+/// One asset in an [`AssetDir`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Asset<'a> {
+    data: &'a [u8],
+    compressed: bool,
+}
+impl<'a> Asset<'a> {
+    /// The stored bytes, which are LZ77 data if the asset is compressed.
+    #[inline]
+    #[must_use]
+    pub const fn data(&self) -> &'a [u8] {
+        self.data
+    }
+
+    /// If the stored bytes are LZ77 compressed.
+    #[inline]
+    #[must_use]
+    pub const fn is_compressed(&self) -> bool {
+        self.compressed
+    }
+
+    /// The size of the asset once loaded.
+    #[inline]
+    pub fn loaded_len(&self) -> Result<usize, Lz77Error> {
+        if self.compressed {
+            lz77::decompressed_len(self.data)
+        } else {
+            Ok(self.data.len())
+        }
+    }
+
+    /// Copies or decompresses the asset into `dst`, returning how many bytes
+    /// were written.
+    pub fn load(&self, dst: &mut [u8]) -> Result<usize, Lz77Error> {
+        if self.compressed {
+            lz77::decompress(self.data, dst)
+        } else {
+            let dst = dst
+                .get_mut(..self.data.len())
+                .ok_or(Lz77Error::OutputTooSmall)?;
+            dst.copy_from_slice(self.data);
+            Ok(self.data.len())
+        }
+    }
+}
+
+/// A directory of assets, checked when it's made so lookups can't fail on
+/// bad data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct AssetDir<'a> {
+    bytes: &'a [u8],
+    len: usize,
+}
+impl<'a> AssetDir<'a> {
+    /// Checks and wraps the bytes of a directory.
+    pub const fn new(bytes: &'a [u8]) -> Result<Self, AssetDirError> {
+        if bytes.len() < HEADER_LEN
+            || bytes[0] != MAGIC[0]
+            || bytes[1] != MAGIC[1]
+            || bytes[2] != MAGIC[2]
+            || bytes[3] != MAGIC[3]
+        {
+            return Err(AssetDirError::BadMagic);
+        }
+        let len = read_u32(bytes, 4) as usize;
+        if len > (bytes.len() - HEADER_LEN) / ENTRY_LEN {
+            return Err(AssetDirError::Truncated);
+        }
+        let mut i = 0;
+        while i < len {
+            let entry = HEADER_LEN + i * ENTRY_LEN;
+            let offset = read_u32(bytes, entry + 4) as usize;
+            let size = (read_u32(bytes, entry + 8) & !COMPRESSED) as usize;
+            if offset > bytes.len() || size > bytes.len() - offset {
+                return Err(AssetDirError::Truncated);
+            }
+            if i > 0 && read_u32(bytes, entry - ENTRY_LEN) >= read_u32(bytes, entry) {
+                return Err(AssetDirError::Unsorted);
+            }
+            i += 1;
+        }
+        Ok(Self { bytes, len })
+    }
+
+    /// The bytes of the whole directory, including the assets' data.
+    #[inline]
+    #[must_use]
+    pub const fn bytes(&self) -> &'a [u8] {
+        self.bytes
+    }
+
+    /// The number of assets.
+    #[inline]
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// If there are no assets.
+    #[inline]
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The asset with the given name.
+    #[inline]
+    #[must_use]
+    pub const fn get(&self, name: &str) -> Option<Asset<'a>> {
+        self.get_hash(name_hash(name))
+    }
+
+    /// The asset whose name has the given [`name_hash`].
+    #[must_use]
+    pub const fn get_hash(&self, hash: u32) -> Option<Asset<'a>> {
+        let (mut low, mut high) = (0, self.len);
+        while low < high {
+            let mid = low + (high - low) / 2;
+            let entry = HEADER_LEN + mid * ENTRY_LEN;
+            let found = read_u32(self.bytes, entry);
+            if found < hash {
+                low = mid + 1;
+            } else if found > hash {
+                high = mid;
+            } else {
+                let offset = read_u32(self.bytes, entry + 4) as usize;
+                let size = read_u32(self.bytes, entry + 8);
+                let (_, data) = self.bytes.split_at(offset);
+                let (data, _) = data.split_at((size & !COMPRESSED) as usize);
+                return Some(Asset {
+                    data,
+                    compressed: size & COMPRESSED != 0,
+                });
+            }
+        }
+        None
+    }
+}
+
+/// Looks up an asset by name at compile time, in a directory that's a
+/// `const`.
+///
+/// An asset that isn't in the directory is a compile error. (Only when
+/// building, though: `cargo check` doesn't evaluate `const` blocks inside
+/// functions.)
+#[macro_export]
+macro_rules! asset {
+    ($dir:expr, $name:literal) => {
+        const {
+            match $dir.get($name) {
+                ::core::option::Option::Some(asset) => asset,
+                ::core::option::Option::None => {
+                    ::core::panic!(::core::concat!("No asset named ", $name))
+                }
+            }
+        }
+    };
+}
+
+/// Packs assets into the directory format, from a build script.
+///
+/// Each asset is a name, its data, and if it should be LZ77 compressed. Fails
+/// with [`AssetDirError::Unsorted`] if two names have the same hash.
+///
+/// ## Panics
+/// * If the directory would be 2 GiB or more.
+#[cfg(feature = "std")]
+pub fn build<'a>(
+    assets: impl IntoIterator<Item = (&'a str, &'a [u8], bool)>,
+) -> Result<std::vec::Vec<u8>, AssetDirError> {
+    let mut entries: std::vec::Vec<_> = assets
+        .into_iter()
+        .map(|(name, data, compress)| {
+            let stored = if compress {
+                lz77::compress(data)
+            } else {
+                data.to_vec()
+            };
+            (name_hash(name), stored, compress)
+        })
+        .collect();
+    entries.sort_by_key(|e| e.0);
+    if entries.windows(2).any(|w| w[0].0 == w[1].0) {
+        return Err(AssetDirError::Unsorted);
+    }
+    let mut out = std::vec::Vec::new();
+    out.extend_from_slice(&MAGIC);
+    out.extend_from_slice(&(entries.len() as u32).to_le_bytes());
+    let mut offset = HEADER_LEN + entries.len() * ENTRY_LEN;
+    for (hash, stored, compress) in &entries {
+        assert!(
+            offset + stored.len() < COMPRESSED as usize,
+            "Asset directory too large"
+        );
+        let size = stored.len() as u32 | if *compress { COMPRESSED } else { 0 };
+        out.extend_from_slice(&hash.to_le_bytes());
+        out.extend_from_slice(&(offset as u32).to_le_bytes());
+        out.extend_from_slice(&size.to_le_bytes());
+        offset = (offset + stored.len() + 3) & !3;
+    }
+    for (_, stored, _) in &entries {
+        out.extend_from_slice(stored);
+        out.resize((out.len() + 3) & !3, 0);
+    }
+    Ok(out)
+}
@@ -0,0 +1,76 @@
+//! Powering the sound hardware on and off.
+//!
+//! With the master enable bit of `SOUNDCNT_X` off, the sound circuits draw no
+//! power, which is worth doing in menus without music or while paused. Simply
+//! flipping the bit pops, because the output jumps between the bias level and
+//! zero, so [`enable`] and [`disable`] ramp `SOUNDBIAS` first, like the BIOS
+//! `SoundBias` call.
+//!
+//! Turning sound off resets the PSG registers (`0x0400_0060` to
+//! `0x0400_0081`), so set them up again after [`enable`]. A DirectSound
+//! mixer's timer and DMA keep running, and should be stopped by the caller
+//! before [`disable`].
+
+use crate::mmio;
+
+/// The master enable bit of `SOUNDCNT_X`.
+const MASTER_ENABLE: u16 = 1 << 7;
+/// The bias level bits of `SOUNDBIAS`.
+const BIAS_LEVEL: u16 = 0x3FE;
+/// The normal bias level, half of the range.
+const DEFAULT_BIAS: u16 = 0x200;
+/// How much the bias level changes each scanline while ramping.
+const BIAS_STEP: u16 = 8;
+
+/// Moves the bias level to `target` a step per scanline, which takes about
+/// 4 ms for a full ramp.
+fn ramp_bias(target: u16) {
+    // SAFETY: `SOUNDBIAS` and `VCOUNT` are plain registers, and only the
+    // level bits of `SOUNDBIAS` are changed.
+    unsafe {
+        let bias = mmio::SOUNDBIAS.read_volatile();
+        let mut level = bias & BIAS_LEVEL;
+        while level != target {
+            level = if level < target {
+                (level + BIAS_STEP).min(target)
+            } else {
+                level.saturating_sub(BIAS_STEP).max(target)
+            };
+            mmio::SOUNDBIAS.write_volatile((bias & !BIAS_LEVEL) | level);
+            let line = mmio::VCOUNT.read_volatile();
+            while mmio::VCOUNT.read_volatile() == line {}
+        }
+    }
+}
+
+/// If the sound hardware is powered on.
+#[inline]
+#[must_use]
+pub fn is_enabled() -> bool {
+    // SAFETY: `SOUNDCNT_X` is a plain read/write register.
+    unsafe { mmio::SOUNDCNT_X.read_volatile() & MASTER_ENABLE != 0 }
+}
+
+/// Powers on the sound hardware, then ramps the bias up to its normal level.
+///
+/// Does nothing if sound is already on.
+pub fn enable() {
+    if is_enabled() {
+        return;
+    }
+    // SAFETY: `SOUNDCNT_X` is a plain read/write register.
+    unsafe { mmio::SOUNDCNT_X.write_volatile(MASTER_ENABLE) };
+    ramp_bias(DEFAULT_BIAS);
+}
+
+/// Ramps the bias down to zero, then powers off the sound hardware.
+///
+/// Does nothing if sound is already off.
+pub fn disable() {
+    if !is_enabled() {
+        return;
+    }
+    ramp_bias(0);
+    // SAFETY: `SOUNDCNT_X` is a plain read/write register.
+    unsafe { mmio::SOUNDCNT_X.write_volatile(0) };
+}
@@ -0,0 +1,477 @@
+//! Finding out how much ROM the cartridge actually has, and reading it
+//! quickly.
+//!
+//! Reading past the end of the ROM doesn't fault. On a real cartridge the
+//! chip either mirrors its contents, or nothing drives the bus and the read
+//! gives back the low bits of the address, so the halfword at `0x0800_0000 +
+//! offset` reads as `(offset / 2) as u16`. Flashcarts do one or the other
+//! past the end of the loaded file. [`detect_rom_size`] looks for either one
+//! at each power of two, which catches a ROM that was flashed truncated, and
+//! [`contains`] then checks that some data (such as the
+//! [`bytes`](crate::assets::AssetDir::bytes) of an asset directory) is all
+//! inside it.
+//!
+//! [`read_block`] copies from ROM with DMA, at the fastest [`WaitProfile`]
+//! the cartridge handles.
+//! [`Reader`] builds a byte stream on top of it, for data that's consumed a
+//! little at a time (such as music being played back).
+
+/// The address ROM starts at.
+pub const ROM_BASE: usize = 0x0800_0000;
+
+/// The largest ROM the cartridge bus can address, 32 MiB.
+pub const MAX_ROM_SIZE: u32 = 32 << 20;
+
+/// The smallest size checked for, 64 KiB.
+const MIN_ROM_SIZE: u32 = 64 << 10;
+
+/// The number of halfwords compared at each size.
+const PROBES: u32 = 16;
+
+/// The distance between probes, which puts them all in the cartridge header
+/// (the first `0xC0` bytes). The header has the entry point and the logo, so
+/// it doesn't happen to repeat anywhere else in a real ROM.
+const PROBE_STEP: u32 = 0x0C;
+
+/// Works out the ROM size from a function reading the halfword at a byte
+/// offset into ROM.
+///
+/// This is what [`detect_rom_size`] uses. It's separate so it can be tested
+/// with a fake ROM. The result is the smallest power of two from 64 KiB up
+/// where the ROM mirrors or the bus is empty, or [`MAX_ROM_SIZE`] if there's
+/// no such point.
+pub fn size_from(read: impl Fn(u32) -> u16) -> u32 {
+    let mut size = MIN_ROM_SIZE;
+    while size < MAX_ROM_SIZE {
+        let mut probes = (0..PROBES).map(|i| i * PROBE_STEP);
+        let empty = probes
+            .clone()
+            .all(|offset| read(size + offset) == ((size + offset) >> 1) as u16);
+        let mirrored = probes.all(|offset| read(size + offset) == read(offset));
+        if empty || mirrored {
+            return size;
+        }
+        size *= 2;
+    }
+    MAX_ROM_SIZE
+}
+
+/// Detects how much ROM the cartridge has, rounded up to a power of two.
+///
+/// A ROM file that isn't a power of two long is usually padded to one, but if
+/// it isn't, data past the end of the file still counts as inside until the
+/// next power of two. A ROM whose data (not padding) happens to repeat its
+/// own header at a power of two would be detected as smaller than it is, but
+/// that doesn't happen by accident.
+#[must_use]
+pub fn detect_rom_size() -> u32 {
+    size_from(|offset| {
+        // SAFETY: the whole ROM address range can always be read, and reads
+        // have no side effects.
+        unsafe { ((ROM_BASE + offset as usize) as *const u16).read_volatile() }
+    })
+}
+
+/// If all of `data` is in the first `rom_size` bytes of ROM.
+///
+/// ```no_run
+/// # use gba_cell::cart;
+/// static ASSETS: &[u8] = &[0; 8];
+/// assert!(cart::contains(ASSETS, cart::detect_rom_size()));
+/// ```
+///
+/// Data that isn't in ROM at all (such as a copy in RAM) is never contained.
+#[inline]
+#[must_use]
+pub fn contains(data: &[u8], rom_size: u32) -> bool {
+    let start = data.as_ptr() as usize;
+    start >= ROM_BASE
+        && start - ROM_BASE <= rom_size as usize
+        && data.len() <= rom_size as usize - (start - ROM_BASE)
+}
+
+/// ROM access timings, as set in `WAITCNT`.
+///
+/// Faster timings make every ROM read faster, code included, but not every
+/// cartridge keeps up with them. Commercial games mostly use
+/// [`Fast`](Self::Fast), and some older flashcarts return garbage with
+/// [`Fastest`](Self::Fastest). [`detect`](Self::detect) picks the fastest
+/// one the cartridge handles.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WaitProfile {
+    /// 4 cycles for the first access and 2 for sequential ones, without
+    /// prefetch. This is how the system starts.
+    Standard,
+    /// 3 and 1 cycles, with prefetch.
+    Fast,
+    /// 2 and 1 cycles, with prefetch.
+    Fastest,
+}
+impl WaitProfile {
+    /// The `WAITCNT` bits the profiles set.
+    const MASK: u16 = 0b111 << 2 | 1 << 14;
+
+    /// The profile's `WAITCNT` bits, within [`MASK`](Self::MASK).
+    #[inline]
+    #[must_use]
+    const fn bits(self) -> u16 {
+        match self {
+            Self::Standard => 0,
+            Self::Fast => 1 << 2 | 1 << 4 | 1 << 14,
+            Self::Fastest => 2 << 2 | 1 << 4 | 1 << 14,
+        }
+    }
+
+    /// Sets `WAITCNT` to use this profile, returning the old value of the
+    /// register so it can be put back with [`restore`](Self::restore).
+    ///
+    /// Only the ROM timing and prefetch bits are changed, so the SRAM and
+    /// other wait state settings are kept.
+    #[inline]
+    pub fn apply(self) -> u16 {
+        // SAFETY: `WAITCNT` is a plain read/write register. Changing ROM
+        // timings is fine even while running from ROM.
+        unsafe {
+            let old = crate::mmio::WAITCNT.read_volatile();
+            crate::mmio::WAITCNT.write_volatile((old & !Self::MASK) | self.bits());
+            old
+        }
+    }
+
+    /// Puts back a `WAITCNT` value returned by [`apply`](Self::apply).
+    #[inline]
+    pub fn restore(waitcnt: u16) {
+        // SAFETY: as in `apply`.
+        unsafe { crate::mmio::WAITCNT.write_volatile(waitcnt) };
+    }
+
+    /// Finds the fastest profile the cartridge handles, by reading the
+    /// cartridge header with each one and comparing it to a read with
+    /// [`Standard`](Self::Standard) timings.
+    ///
+    /// `WAITCNT` is put back how it was afterwards. Each profile is tried by
+    /// a small loop in IWRAM, so a cartridge that can't keep up only gives
+    /// wrong header words, never wrong instructions.
+    #[must_use]
+    pub fn detect() -> Self {
+        let old = Self::Standard.apply();
+        let standard = old & !Self::MASK;
+        let mut expected = [0; HEADER_WORDS];
+        for (i, word) in expected.iter_mut().enumerate() {
+            // SAFETY: the header is the first `0xC0` bytes of ROM, which can
+            // always be read.
+            *word = unsafe { (ROM_BASE as *const u32).add(i).read_volatile() };
+        }
+        let mut best = Self::Standard;
+        for profile in [Self::Fast, Self::Fastest] {
+            if !header_matches(standard | profile.bits(), standard, &expected) {
+                break;
+            }
+            best = profile;
+        }
+        Self::restore(old);
+        best
+    }
+}
+
+/// The number of words in the cartridge header.
+const HEADER_WORDS: usize = 0xC0 / 4;
+
+/// Sets `WAITCNT` to `trial`, reads the cartridge header, then sets `WAITCNT`
+/// to `after`. Returns if the header matched `expected`.
+///
+/// This is placed in IWRAM as ARM code, so while `trial` is set no code is
+/// fetched from ROM. It also only uses volatile reads and compares, which
+/// can't be turned into a call to `memcmp` (in ROM).
+#[cfg_attr(
+    target_arch = "arm",
+    link_section = ".iwram.cart_header_matches",
+    instruction_set(arm::a32)
+)]
+#[inline(never)]
+fn header_matches(trial: u16, after: u16, expected: &[u32; HEADER_WORDS]) -> bool {
+    // SAFETY: `WAITCNT` is a plain read/write register, and the header can
+    // always be read.
+    unsafe { crate::mmio::WAITCNT.write_volatile(trial) };
+    let mut matches = true;
+    let mut i = 0;
+    while i < HEADER_WORDS {
+        let word = unsafe { (ROM_BASE as *const u32).add(i).read_volatile() };
+        matches &= word == expected[i];
+        i += 1;
+    }
+    unsafe { crate::mmio::WAITCNT.write_volatile(after) };
+    matches
+}
+
+/// The profile [`read_block`] uses, as a [`WaitProfile`] plus one, or `0`
+/// if it hasn't been detected yet.
+static READ_PROFILE: crate::GbaCell<u8> = crate::GbaCell::new(0);
+
+/// The most words one DMA transfer is given, so the count fits in
+/// `DMA3CNT_L` without using its "0 means `0x10000`" case.
+const DMA_CHUNK: usize = 0x8000;
+
+/// Copies words from ROM into `dst` with DMA 3, starting `offset` bytes into
+/// ROM.
+///
+/// While copying, ROM timings are set to the fastest [`WaitProfile`] the
+/// cartridge handles (found with [`WaitProfile::detect`] on the first call),
+/// then put back. DMA reads ROM with sequential accesses, so the slower
+/// first access is only paid once per transfer, and there are no instruction
+/// fetches competing for the bus like in a CPU copy loop.
+///
+/// The CPU is paused while DMA runs, and so are interrupts. Large blocks are
+/// copied in pieces of 128 KiB, so an interrupt is delayed by at most one
+/// piece, but that's still most of a frame. Keep blocks small when
+/// interrupts need to stay on time.
+///
+/// The gamepak DRQ bit of `DMA3CNT_H` is left off, since it's for cartridges
+/// with their own hardware that requests transfers, and it would hang the
+/// DMA on any other cartridge.
+///
+/// ## Panics
+/// * If `offset` isn't a multiple of 4.
+/// * If the block goes past [`MAX_ROM_SIZE`].
+#[cfg_attr(feature = "track_caller", track_caller)]
+pub fn read_block(offset: u32, dst: &mut [u32]) {
+    assert!(offset & 3 == 0, "ROM reads must be word aligned.");
+    assert!(
+        offset <= MAX_ROM_SIZE && dst.len() <= (MAX_ROM_SIZE - offset) as usize / 4,
+        "ROM reads must be inside the 32 MiB of ROM space."
+    );
+    let profile = match READ_PROFILE.read() {
+        0 => {
+            let detected = WaitProfile::detect();
+            READ_PROFILE.write(detected as u8 + 1);
+            detected
+        }
+        1 => WaitProfile::Standard,
+        2 => WaitProfile::Fast,
+        _ => WaitProfile::Fastest,
+    };
+    let old = profile.apply();
+    let mut src = ROM_BASE + offset as usize;
+    for chunk in dst.chunks_mut(DMA_CHUNK) {
+        // SAFETY: the source is inside ROM, the destination is `chunk`, and
+        // both are word aligned. The transfer is started immediately, with
+        // 32 bit units and both addresses incrementing, and this waits for it
+        // to finish before `chunk` is used again.
+        unsafe {
+            crate::mmio::DMA3SAD.write_volatile(src as u32);
+            crate::mmio::DMA3DAD.write_volatile(chunk.as_mut_ptr() as u32);
+            crate::mmio::DMA3CNT_L.write_volatile(chunk.len() as u16);
+            crate::mmio::DMA3CNT_H.write_volatile(1 << 15 | 1 << 10);
+            while crate::mmio::DMA3CNT_H.read_volatile() & (1 << 15) != 0 {}
+        }
+        src += chunk.len() * 4;
+    }
+    core::sync::atomic::compiler_fence(core::sync::atomic::Ordering::SeqCst);
+    WaitProfile::restore(old);
+}
+
+/// Copies words from ROM into `dst` with the CPU, starting `offset` bytes into
+/// ROM.
+///
+/// This is the non-DMA counterpart of [`read_block`], using whatever ROM
+/// timings are already set. It's slower, but doesn't hold off interrupts.
+///
+/// ## Panics
+/// * If `offset` isn't a multiple of 4.
+/// * If the block goes past [`MAX_ROM_SIZE`].
+#[cfg_attr(feature = "track_caller", track_caller)]
+pub fn read_words(offset: u32, dst: &mut [u32]) {
+    assert!(offset & 3 == 0, "ROM reads must be word aligned.");
+    assert!(
+        offset <= MAX_ROM_SIZE && dst.len() <= (MAX_ROM_SIZE - offset) as usize / 4,
+        "ROM reads must be inside the 32 MiB of ROM space."
+    );
+    let src = (ROM_BASE + offset as usize) as *const u32;
+    for (i, word) in dst.iter_mut().enumerate() {
+        // SAFETY: the word is inside ROM, which can always be read.
+        *word = unsafe { src.add(i).read_volatile() };
+    }
+}
+
+/// The function a [`Reader`] fills its buffer with, given a word aligned ROM
+/// offset and the words to fill.
+pub type Fill = fn(u32, &mut [u32]);
+
+/// Reads a range of ROM as a stream of bytes, `WORDS` words at a time.
+///
+/// Each refill reads a whole aligned burst into the reader's buffer, either
+/// with DMA ([`read_block`]) or the CPU ([`read_words`]), and bytes are then
+/// handed out from the buffer. Most reads never touch ROM at all, and the
+/// ones that do get sequential accesses. The buffer is part of the reader, so
+/// a reader on the stack (or in an IWRAM static) keeps it in IWRAM.
+///
+/// ```no_run
+/// # use gba_cell::cart::Reader;
+/// let mut song = Reader::<64>::new(0x10_0000, 0x2000);
+/// let mut header = [0; 16];
+/// song.read(&mut header);
+/// let first_note = song.next_byte();
+/// ```
+#[derive(Debug, Clone)]
+pub struct Reader<const WORDS: usize, F = Fill> {
+    buf: [u32; WORDS],
+    fill: F,
+    /// The ROM offset of the next refill, always word aligned.
+    next: u32,
+    /// The ROM offset the stream ends at.
+    end: u32,
+    /// The byte in `buf` to read next.
+    pos: usize,
+    /// The number of bytes in `buf` that are part of the stream.
+    filled: usize,
+    /// The bytes to skip after the first refill, for an unaligned start.
+    skip: usize,
+}
+impl<const WORDS: usize> Reader<WORDS> {
+    /// Makes a reader for the `len` bytes starting `offset` bytes into ROM,
+    /// which refills with DMA.
+    ///
+    /// Nothing is read until the first byte is asked for.
+    ///
+    /// ## Panics
+    /// * If `WORDS` is 0.
+    /// * If the range goes past [`MAX_ROM_SIZE`].
+    #[inline]
+    #[must_use]
+    #[cfg_attr(feature = "track_caller", track_caller)]
+    pub fn new(offset: u32, len: u32) -> Self {
+        Self::with_fill(offset, len, read_block)
+    }
+
+    /// Makes a reader like [`new`](Self::new), but which refills with the CPU
+    /// so that interrupts aren't held off.
+    #[inline]
+    #[must_use]
+    #[cfg_attr(feature = "track_caller", track_caller)]
+    pub fn without_dma(offset: u32, len: u32) -> Self {
+        Self::with_fill(offset, len, read_words)
+    }
+}
+impl<const WORDS: usize, F> Reader<WORDS, F>
+where
+    F: FnMut(u32, &mut [u32]),
+{
+    /// Makes a reader that refills its buffer with `fill`, which is given a
+    /// word aligned offset and the words to read from there.
+    ///
+    /// This is what [`new`](Reader::new) uses. It's separate so it can be
+    /// tested with a fake ROM.
+    ///
+    /// ## Panics
+    /// * If `WORDS` is 0.
+    /// * If the range goes past [`MAX_ROM_SIZE`].
+    #[must_use]
+    #[cfg_attr(feature = "track_caller", track_caller)]
+    pub fn with_fill(offset: u32, len: u32, fill: F) -> Self {
+        assert!(WORDS > 0, "A Reader needs a buffer of at least one word.");
+        assert!(
+            offset <= MAX_ROM_SIZE && len <= MAX_ROM_SIZE - offset,
+            "ROM reads must be inside the 32 MiB of ROM space."
+        );
+        Self {
+            buf: [0; WORDS],
+            fill,
+            next: offset & !3,
+            end: offset + len,
+            pos: 0,
+            filled: 0,
+            skip: (offset & 3) as usize,
+        }
+    }
+
+    /// The number of bytes left in the stream.
+    #[inline]
+    #[must_use]
+    pub fn remaining(&self) -> u32 {
+        let unread = self.end.saturating_sub(self.next) - self.skip as u32;
+        (self.filled - self.pos) as u32 + unread
+    }
+
+    /// If every byte of the stream has been read.
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.remaining() == 0
+    }
+
+    /// Reads the next burst into the buffer. Returns `false` at the end of
+    /// the stream.
+    fn refill(&mut self) -> bool {
+        if self.next >= self.end {
+            return false;
+        }
+        let left = (self.end - self.next) as usize;
+        let words = WORDS.min(left.div_ceil(4));
+        (self.fill)(self.next, &mut self.buf[..words]);
+        self.filled = left.min(words * 4);
+        self.pos = core::mem::take(&mut self.skip);
+        self.next += words as u32 * 4;
+        true
+    }
+
+    /// The buffer's bytes. ROM data is little-endian, like the GBA itself.
+    #[inline]
+    fn bytes(&self) -> &[u8] {
+        // SAFETY: any `u32` can be read as 4 `u8`s, and the slice covers
+        // exactly the buffer.
+        unsafe { core::slice::from_raw_parts(self.buf.as_ptr().cast(), WORDS * 4) }
+    }
+
+    /// Reads the next byte, or `None` at the end of the stream.
+    #[inline]
+    pub fn next_byte(&mut self) -> Option<u8> {
+        // A refill can be all skipped bytes, at an unaligned start.
+        while self.pos == self.filled {
+            if !self.refill() {
+                return None;
+            }
+        }
+        let byte = self.bytes()[self.pos];
+        self.pos += 1;
+        Some(byte)
+    }
+
+    /// Fills as much of `out` as the stream has left, returning the number
+    /// of bytes read.
+    pub fn read(&mut self, out: &mut [u8]) -> usize {
+        let mut done = 0;
+        while done < out.len() {
+            if self.pos == self.filled {
+                if !self.refill() {
+                    break;
+                }
+                continue;
+            }
+            let n = (self.filled - self.pos).min(out.len() - done);
+            out[done..done + n].copy_from_slice(&self.bytes()[self.pos..self.pos + n]);
+            self.pos += n;
+            done += n;
+        }
+        done
+    }
+}
+
+impl<const WORDS: usize, F> Iterator for Reader<WORDS, F>
+where
+    F: FnMut(u32, &mut [u32]),
+{
+    type Item = u8;
+
+    #[inline]
+    fn next(&mut self) -> Option<u8> {
+        self.next_byte()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let n = self.remaining() as usize;
+        (n, Some(n))
+    }
+}
+
+impl<const WORDS: usize, F> ExactSizeIterator for Reader<WORDS, F> where F: FnMut(u32, &mut [u32]) {}
@@ -0,0 +1,307 @@
+//! Axis-aligned box collision and a coarse spatial grid.
+//!
+//! Coordinates are plain `i32` values. Games using fixed-point positions can
+//! store the raw fixed-point bits here, as long as every value uses the same
+//! format. Tile and cell sizes are always powers of two, given as a shift
+//! amount, so none of this needs a division (which the GBA has no hardware
+//! for), apart from working out a [`SpatialGrid`]'s rows once when it's
+//! made.
+
+/// An axis-aligned bounding box.
+///
+/// The box covers `x..x+width` and `y..y+height`, so the right and bottom
+/// edges are exclusive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Aabb {
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+}
+impl Aabb {
+    /// Makes a box from the top left corner and size.
+    #[inline]
+    #[must_use]
+    pub const fn new(x: i32, y: i32, width: i32, height: i32) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    /// The exclusive right edge.
+    #[inline]
+    #[must_use]
+    pub const fn right(&self) -> i32 {
+        self.x + self.width
+    }
+
+    /// The exclusive bottom edge.
+    #[inline]
+    #[must_use]
+    pub const fn bottom(&self) -> i32 {
+        self.y + self.height
+    }
+
+    /// If the two boxes share any area.
+    ///
+    /// Boxes that only touch along an edge don't overlap.
+    #[inline]
+    #[must_use]
+    pub const fn overlaps(&self, other: &Self) -> bool {
+        self.x < other.right()
+            && other.x < self.right()
+            && self.y < other.bottom()
+            && other.y < self.bottom()
+    }
+
+    /// If the point is inside the box.
+    #[inline]
+    #[must_use]
+    pub const fn contains(&self, x: i32, y: i32) -> bool {
+        self.x <= x && x < self.right() && self.y <= y && y < self.bottom()
+    }
+
+    /// The area shared by both boxes, if any.
+    #[inline]
+    #[must_use]
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        let x = self.x.max(other.x);
+        let y = self.y.max(other.y);
+        let right = self.right().min(other.right());
+        let bottom = self.bottom().min(other.bottom());
+        (x < right && y < bottom).then(|| Self::new(x, y, right - x, bottom - y))
+    }
+
+    /// This box moved by the given amount.
+    #[inline]
+    #[must_use]
+    pub const fn translated(&self, dx: i32, dy: i32) -> Self {
+        Self::new(self.x + dx, self.y + dy, self.width, self.height)
+    }
+
+    /// Moves the box through a tile map, stopping at solid tiles.
+    ///
+    /// Tiles are `1 << tile_shift` units wide and tall, and `is_solid(tx, ty)`
+    /// is called with tile coordinates to check each tile the box would pass
+    /// into. Movement is resolved along X first and then along Y, which lets
+    /// the box slide along walls and floors.
+    ///
+    /// The box should already be clear of solid tiles, and each step should
+    /// be no more than one tile, otherwise the box can end up inside (or
+    /// tunnel through) solid tiles.
+    pub fn sweep_tiles<F>(&self, dx: i32, dy: i32, tile_shift: u32, mut is_solid: F) -> Sweep
+    where
+        F: FnMut(i32, i32) -> bool,
+    {
+        let mut aabb = *self;
+        let rows = (aabb.y >> tile_shift)..=((aabb.bottom() - 1) >> tile_shift);
+        let hit_x = if dx > 0 {
+            let first = ((aabb.right() - 1) >> tile_shift) + 1;
+            let last = (aabb.right() - 1 + dx) >> tile_shift;
+            match (first..=last).find(|&tx| rows.clone().any(|ty| is_solid(tx, ty))) {
+                Some(tx) => {
+                    aabb.x = (tx << tile_shift) - aabb.width;
+                    true
+                }
+                None => {
+                    aabb.x += dx;
+                    false
+                }
+            }
+        } else if dx < 0 {
+            let first = (aabb.x >> tile_shift) - 1;
+            let last = (aabb.x + dx) >> tile_shift;
+            match (last..=first)
+                .rev()
+                .find(|&tx| rows.clone().any(|ty| is_solid(tx, ty)))
+            {
+                Some(tx) => {
+                    aabb.x = (tx + 1) << tile_shift;
+                    true
+                }
+                None => {
+                    aabb.x += dx;
+                    false
+                }
+            }
+        } else {
+            false
+        };
+        let cols = (aabb.x >> tile_shift)..=((aabb.right() - 1) >> tile_shift);
+        let hit_y = if dy > 0 {
+            let first = ((aabb.bottom() - 1) >> tile_shift) + 1;
+            let last = (aabb.bottom() - 1 + dy) >> tile_shift;
+            match (first..=last).find(|&ty| cols.clone().any(|tx| is_solid(tx, ty))) {
+                Some(ty) => {
+                    aabb.y = (ty << tile_shift) - aabb.height;
+                    true
+                }
+                None => {
+                    aabb.y += dy;
+                    false
+                }
+            }
+        } else if dy < 0 {
+            let first = (aabb.y >> tile_shift) - 1;
+            let last = (aabb.y + dy) >> tile_shift;
+            match (last..=first)
+                .rev()
+                .find(|&ty| cols.clone().any(|tx| is_solid(tx, ty)))
+            {
+                Some(ty) => {
+                    aabb.y = (ty + 1) << tile_shift;
+                    true
+                }
+                None => {
+                    aabb.y += dy;
+                    false
+                }
+            }
+        } else {
+            false
+        };
+        Sweep { aabb, hit_x, hit_y }
+    }
+}
+
+/// The outcome of [`Aabb::sweep_tiles`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Sweep {
+    /// Where the box ended up.
+    pub aabb: Aabb,
+    /// If horizontal movement was stopped by a solid tile.
+    pub hit_x: bool,
+    /// If vertical movement was stopped by a solid tile.
+    pub hit_y: bool,
+}
+
+const NO_ENTRY: u16 = u16::MAX;
+
+/// A uniform grid for finding nearby boxes without testing every pair.
+///
+/// The grid has `CELLS` cells and can hold up to `N` entries, which are
+/// identified by index (`0..N`). Each cell costs 2 bytes, as does each entry,
+/// so even a fine grid over a large area stays small.
+///
+/// Each entry is filed under the cell holding its top left corner, and entries
+/// must be no larger than one cell. Queries then check one extra cell up and to
+/// the left, which guarantees that every overlapping entry is found.
+#[derive(Debug, Clone)]
+pub struct SpatialGrid<const CELLS: usize, const N: usize> {
+    heads: [u16; CELLS],
+    next: [u16; N],
+    columns: i32,
+    rows: i32,
+    cell_shift: u32,
+}
+impl<const CELLS: usize, const N: usize> SpatialGrid<CELLS, N> {
+    /// Makes an empty grid `columns` cells wide.
+    ///
+    /// Cells are `1 << cell_shift` units on each side, and the grid has
+    /// `CELLS / columns` rows. Positions outside the grid are clamped to the
+    /// edge cells.
+    ///
+    /// ## Panics
+    /// * If `columns` is 0 or larger than `CELLS`.
+    /// * If `N` is larger than `u16::MAX`.
+    #[inline]
+    #[must_use]
+    #[cfg_attr(feature = "track_caller", track_caller)]
+    pub const fn new(columns: u16, cell_shift: u32) -> Self {
+        assert!(columns != 0 && columns as usize <= CELLS);
+        assert!(N < NO_ENTRY as usize);
+        Self {
+            heads: [NO_ENTRY; CELLS],
+            next: [NO_ENTRY; N],
+            columns: columns as i32,
+            rows: (CELLS / columns as usize) as i32,
+            cell_shift,
+        }
+    }
+
+    #[inline]
+    fn cell_coords(&self, x: i32, y: i32) -> (i32, i32) {
+        let cx = (x >> self.cell_shift).clamp(0, self.columns - 1);
+        let cy = (y >> self.cell_shift).clamp(0, self.rows - 1);
+        (cx, cy)
+    }
+
+    /// Removes all entries.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.heads = [NO_ENTRY; CELLS];
+    }
+
+    /// Files entry `id` under the cell holding the box's top left corner.
+    ///
+    /// Each id should only be inserted once between calls to
+    /// [`clear`](Self::clear). Returns `false` (and does nothing) if `id` is
+    /// out of range.
+    pub fn insert(&mut self, id: u16, aabb: &Aabb) -> bool {
+        if usize::from(id) >= N {
+            return false;
+        }
+        let (cx, cy) = self.cell_coords(aabb.x, aabb.y);
+        let cell = (cy * self.columns + cx) as usize;
+        self.next[usize::from(id)] = self.heads[cell];
+        self.heads[cell] = id;
+        true
+    }
+
+    /// Iterates the ids of every entry that might overlap `area`.
+    ///
+    /// This can include entries that don't actually overlap, so the caller
+    /// should still test each one.
+    #[must_use]
+    pub fn query(&self, area: &Aabb) -> Candidates<'_, CELLS, N> {
+        let (min_x, min_y) = self.cell_coords(area.x, area.y);
+        let (max_x, max_y) = self.cell_coords(area.right() - 1, area.bottom() - 1);
+        let min_x = (min_x - 1).max(0);
+        let min_y = (min_y - 1).max(0);
+        Candidates {
+            grid: self,
+            min_x,
+            max_x,
+            max_y,
+            cx: min_x,
+            cy: min_y,
+            entry: NO_ENTRY,
+        }
+    }
+}
+
+/// The iterator returned by [`SpatialGrid::query`].
+#[derive(Debug, Clone)]
+pub struct Candidates<'a, const CELLS: usize, const N: usize> {
+    grid: &'a SpatialGrid<CELLS, N>,
+    min_x: i32,
+    max_x: i32,
+    max_y: i32,
+    cx: i32,
+    cy: i32,
+    entry: u16,
+}
+impl<const CELLS: usize, const N: usize> Iterator for Candidates<'_, CELLS, N> {
+    type Item = u16;
+
+    fn next(&mut self) -> Option<u16> {
+        while self.entry == NO_ENTRY {
+            if self.cy > self.max_y {
+                return None;
+            }
+            let cell = (self.cy * self.grid.columns + self.cx) as usize;
+            self.entry = self.grid.heads[cell];
+            self.cx += 1;
+            if self.cx > self.max_x {
+                self.cx = self.min_x;
+                self.cy += 1;
+            }
+        }
+        let id = self.entry;
+        self.entry = self.grid.next[usize::from(id)];
+        Some(id)
+    }
+}
@@ -0,0 +1,297 @@
+//! Palette colors, and color correction for different screens.
+//!
+//! A [`Color`] is one palette entry, with const constructors, per-channel
+//! access, and the fades and blends that palette effects need, so they don't
+//! have to be bit math at each call site.
+//!
+//! The original GBA has a dark, unlit screen, so art that looks right on a
+//! backlit GBA SP (AGS-101) or an emulator looks muddy on it, and art made to
+//! look right on it looks washed out everywhere else. A [`Correction`] is a
+//! brightness curve and a saturation change, applied to palette colors before
+//! they're loaded, so a game can offer a "screen type" option.
+//!
+//! Colors are 15-bit BGR, as stored in palette RAM: red in bits 0 to 4, green
+//! in bits 5 to 9, and blue in bits 10 to 14.
+
+use crate::GbaCellSafe;
+
+/// A color, as stored in palette RAM.
+///
+/// Each channel is 5 bits, from 0 to 31. Bit 15 is unused by the hardware,
+/// and the methods here always leave it clear.
+///
+/// ```
+/// # use gba_cell::color::Color;
+/// const SKY: Color = Color::rgb(12, 20, 31);
+/// assert_eq!(SKY.b(), 31);
+/// // Halfway through a fade to black.
+/// assert_eq!(SKY.lerp(Color::BLACK, 128), Color::rgb(6, 10, 16));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[repr(transparent)]
+pub struct Color(pub u16);
+impl Color {
+    /// Black.
+    pub const BLACK: Self = Self::rgb(0, 0, 0);
+    /// White.
+    pub const WHITE: Self = Self::rgb(31, 31, 31);
+    /// Full red.
+    pub const RED: Self = Self::rgb(31, 0, 0);
+    /// Full green.
+    pub const GREEN: Self = Self::rgb(0, 31, 0);
+    /// Full blue.
+    pub const BLUE: Self = Self::rgb(0, 0, 31);
+
+    /// Makes a color from its channels. Values over 31 are clamped.
+    #[inline]
+    #[must_use]
+    pub const fn rgb(r: u8, g: u8, b: u8) -> Self {
+        Self(channel(r) | channel(g) << 5 | channel(b) << 10)
+    }
+
+    /// The red channel.
+    #[inline]
+    #[must_use]
+    pub const fn r(self) -> u8 {
+        (self.0 & 31) as u8
+    }
+
+    /// The green channel.
+    #[inline]
+    #[must_use]
+    pub const fn g(self) -> u8 {
+        ((self.0 >> 5) & 31) as u8
+    }
+
+    /// The blue channel.
+    #[inline]
+    #[must_use]
+    pub const fn b(self) -> u8 {
+        ((self.0 >> 10) & 31) as u8
+    }
+
+    /// The same color with a different red channel, clamped to 31.
+    #[inline]
+    #[must_use]
+    pub const fn with_r(self, r: u8) -> Self {
+        Self::rgb(r, self.g(), self.b())
+    }
+
+    /// The same color with a different green channel, clamped to 31.
+    #[inline]
+    #[must_use]
+    pub const fn with_g(self, g: u8) -> Self {
+        Self::rgb(self.r(), g, self.b())
+    }
+
+    /// The same color with a different blue channel, clamped to 31.
+    #[inline]
+    #[must_use]
+    pub const fn with_b(self, b: u8) -> Self {
+        Self::rgb(self.r(), self.g(), b)
+    }
+
+    /// Mixes towards `other` by an 8.8 fixed-point fraction: `0` gives this
+    /// color, and `256` (or more) gives `other`. Each channel is rounded to
+    /// the nearest value.
+    ///
+    /// Stepping the fraction each frame gives a fade.
+    #[inline]
+    #[must_use]
+    pub const fn lerp(self, other: Self, t: u16) -> Self {
+        let t = if t > 256 { 256 } else { t as i32 };
+        Self::rgb(
+            mix(self.r(), other.r(), t),
+            mix(self.g(), other.g(), t),
+            mix(self.b(), other.b(), t),
+        )
+    }
+
+    /// The gray with the same brightness, using the Rec. 601 luma weights.
+    #[inline]
+    #[must_use]
+    pub const fn grayscale(self) -> Self {
+        let luma = (self.r() as u16 * 77 + self.g() as u16 * 150 + self.b() as u16 * 29) >> 8;
+        let luma = luma as u8;
+        Self::rgb(luma, luma, luma)
+    }
+
+    /// Multiplies each channel by `other`'s, as fractions of 31, which
+    /// darkens (like tinting with a filter).
+    #[inline]
+    #[must_use]
+    pub const fn blend_multiply(self, other: Self) -> Self {
+        Self::rgb(
+            mul(self.r(), other.r()),
+            mul(self.g(), other.g()),
+            mul(self.b(), other.b()),
+        )
+    }
+
+    /// Adds each channel to `other`'s, clamped to 31, which brightens (like
+    /// a light or a flash).
+    #[inline]
+    #[must_use]
+    pub const fn blend_add(self, other: Self) -> Self {
+        Self::rgb(
+            self.r() + other.r(),
+            self.g() + other.g(),
+            self.b() + other.b(),
+        )
+    }
+}
+
+/// Mixes two channel values by an 8.8 fraction from 0 to 256.
+#[inline]
+const fn mix(a: u8, b: u8, t: i32) -> u8 {
+    (a as i32 + (((b as i32 - a as i32) * t + 128) >> 8)) as u8
+}
+
+/// Multiplies two channel values as fractions of 31, rounded.
+#[inline]
+const fn mul(a: u8, b: u8) -> u8 {
+    ((a as u16 * b as u16 + 15) / 31) as u8
+}
+
+/// A channel value clamped to 5 bits.
+#[inline]
+const fn channel(value: u8) -> u16 {
+    if value > 31 {
+        31
+    } else {
+        value as u16
+    }
+}
+
+impl From<u16> for Color {
+    #[inline]
+    fn from(bits: u16) -> Self {
+        Self(bits)
+    }
+}
+
+impl From<Color> for u16 {
+    #[inline]
+    fn from(color: Color) -> Self {
+        color.0
+    }
+}
+
+// SAFETY: `Color` is a `repr(transparent)` newtype over `u16`.
+unsafe impl GbaCellSafe for Color {}
+
+// SAFETY: `Color` is a `repr(transparent)` newtype over `u16`, and every bit
+// pattern is a color.
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Zeroable for Color {}
+// SAFETY: as above.
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Pod for Color {}
+
+/// A transform applied to each palette color.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Correction {
+    curve: [u8; 32],
+    saturation: u16,
+}
+impl Correction {
+    /// Leaves colors unchanged, for emulators and art made for them.
+    pub const NONE: Self = Self {
+        curve: [
+            0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23,
+            24, 25, 26, 27, 28, 29, 30, 31,
+        ],
+        saturation: 256,
+    };
+
+    /// A rough approximation for the unlit original GBA (AGB): much brighter
+    /// shadows (gamma 0.6) and 20% more saturation.
+    pub const AGB: Self = Self {
+        curve: [
+            0, 4, 6, 8, 9, 10, 12, 13, 14, 15, 16, 17, 18, 18, 19, 20, 21, 22, 22, 23, 24, 25, 25,
+            26, 27, 27, 28, 29, 29, 30, 30, 31,
+        ],
+        saturation: 307,
+    };
+
+    /// A rough approximation for the backlit GBA SP (AGS-101): slightly
+    /// brighter shadows (gamma 0.85) and 10% more saturation.
+    pub const AGS101: Self = Self {
+        curve: [
+            0, 2, 3, 4, 5, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 20, 21, 22, 23, 24,
+            25, 26, 27, 28, 28, 29, 30, 31,
+        ],
+        saturation: 282,
+    };
+
+    /// Makes a correction from a curve, which maps each 5-bit channel value
+    /// to a new one, and an 8.8 fixed-point saturation factor (`256` leaves
+    /// saturation unchanged, `0` makes everything gray).
+    ///
+    /// Curve values over 31 are clamped.
+    #[inline]
+    #[must_use]
+    pub const fn new(curve: [u8; 32], saturation: u16) -> Self {
+        let mut curve = curve;
+        let mut i = 0;
+        while i < 32 {
+            if curve[i] > 31 {
+                curve[i] = 31;
+            }
+            i += 1;
+        }
+        Self { curve, saturation }
+    }
+
+    /// The same curve with a different saturation factor.
+    #[inline]
+    #[must_use]
+    pub const fn with_saturation(self, saturation: u16) -> Self {
+        Self { saturation, ..self }
+    }
+
+    /// Corrects one color.
+    ///
+    /// ```
+    /// # use gba_cell::color::{Color, Correction};
+    /// let shadow = Color::rgb(4, 4, 8);
+    /// assert_eq!(Correction::NONE.apply(shadow), shadow);
+    /// assert!(Correction::AGB.apply(shadow).b() > shadow.b());
+    /// ```
+    #[must_use]
+    pub const fn apply(&self, color: Color) -> Color {
+        let channels = [color.r(), color.g(), color.b()];
+        // Rec. 601 luma weights, out of 256.
+        let luma =
+            (channels[0] as i32 * 77 + channels[1] as i32 * 150 + channels[2] as i32 * 29) >> 8;
+        let mut out = [0; 3];
+        let mut i = 0;
+        while i < 3 {
+            let c = channels[i] as i32;
+            let c = luma + (((c - luma) * self.saturation as i32) >> 8);
+            let c = if c < 0 {
+                0
+            } else if c > 31 {
+                31
+            } else {
+                c
+            };
+            out[i] = self.curve[c as usize];
+            i += 1;
+        }
+        Color::rgb(out[0], out[1], out[2])
+    }
+
+    /// Corrects every color in a palette.
+    #[inline]
+    pub fn apply_all(&self, palette: &mut [Color]) {
+        palette.iter_mut().for_each(|c| *c = self.apply(*c));
+    }
+}
+
+impl Default for Correction {
+    #[inline]
+    fn default() -> Self {
+        Self::NONE
+    }
+}
@@ -0,0 +1,192 @@
+//! Memory tests for the video memory regions, for checking hardware and
+//! flashcarts.
+//!
+//! VRAM, OAM, and palette RAM ignore 8-bit writes (or, for VRAM, write the
+//! byte to both halves of a `u16`), so [`test_words`] only ever uses 16 and
+//! 32 bit volatile accesses. [`memtest`] runs it over all three regions, and
+//! [`show`] turns the whole screen green or red to report the result without
+//! needing any text drawing.
+//!
+//! IWRAM and EWRAM aren't tested, since the program's stack and statics live
+//! there.
+
+use core::fmt;
+
+/// A video memory region.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Region {
+    /// Palette RAM, 1 KiB at `0x0500_0000`.
+    Palram,
+    /// Video RAM, 96 KiB at `0x0600_0000`.
+    Vram,
+    /// Object attribute memory, 1 KiB at `0x0700_0000`.
+    Oam,
+}
+impl Region {
+    /// Every region, in the order [`memtest`] tests them.
+    pub const ALL: [Self; 3] = [Self::Palram, Self::Vram, Self::Oam];
+
+    /// The address the region starts at.
+    #[inline]
+    #[must_use]
+    pub const fn base(self) -> usize {
+        match self {
+            Self::Palram => 0x0500_0000,
+            Self::Vram => 0x0600_0000,
+            Self::Oam => 0x0700_0000,
+        }
+    }
+
+    /// The size of the region in bytes.
+    #[inline]
+    #[must_use]
+    pub const fn size(self) -> usize {
+        match self {
+            Self::Palram | Self::Oam => 1024,
+            Self::Vram => 96 * 1024,
+        }
+    }
+}
+
+impl fmt::Display for Region {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Palram => "PALRAM",
+            Self::Vram => "VRAM",
+            Self::Oam => "OAM",
+        })
+    }
+}
+
+/// A word that didn't read back what was written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Failure {
+    /// The offset of the word, in bytes.
+    pub offset: usize,
+    /// The value written.
+    pub expected: u32,
+    /// The value read back.
+    pub found: u32,
+}
+
+impl fmt::Display for Failure {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "at offset {:#07X}: wrote {:#010X}, read {:#010X}",
+            self.offset, self.expected, self.found
+        )
+    }
+}
+
+/// The fill patterns, which set every bit both ways and put opposite values
+/// in neighbouring bits.
+const PATTERNS: [u32; 4] = [0, u32::MAX, 0xAAAA_AAAA, 0x5555_5555];
+
+/// The value the address test puts in word `i`, which is different for each
+/// word of even the largest region.
+#[inline]
+const fn address_value(i: usize) -> u32 {
+    (i as u32).wrapping_mul(0x9E37_79B9) ^ 0x5A5A_5A5A
+}
+
+/// Checks that every word of `mem` reads back what was written, returning
+/// the first one that doesn't.
+#[inline]
+fn verify(mem: &[u32], expected: impl Fn(usize) -> u32) -> Result<(), Failure> {
+    for (i, word) in mem.iter().enumerate() {
+        // SAFETY: `word` is a valid, aligned `&u32`.
+        let found = unsafe { (word as *const u32).read_volatile() };
+        let expected = expected(i);
+        if found != expected {
+            return Err(Failure {
+                offset: i * 4,
+                expected,
+                found,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Tests a block of memory, returning the first word that failed.
+///
+/// The tests are, in order:
+/// 1. Filling with each of a few bit patterns and reading them back.
+/// 2. Writing each half of each word with a `u16` write, to check the two
+///    halves land in the right places.
+/// 3. Writing a different value to every word before reading any back, which
+///    finds address lines that are stuck or shorted (the word would read back
+///    another word's value).
+///
+/// The memory is left zeroed if every test passes.
+pub fn test_words(mem: &mut [u32]) -> Result<(), Failure> {
+    for pattern in PATTERNS {
+        for word in mem.iter_mut() {
+            // SAFETY: `word` is a valid, aligned `&mut u32`.
+            unsafe { (word as *mut u32).write_volatile(pattern) };
+        }
+        verify(mem, |_| pattern)?;
+    }
+
+    for word in mem.iter_mut() {
+        let halves = (word as *mut u32).cast::<u16>();
+        // SAFETY: both halves are in `word`, and aligned. The GBA is little
+        // endian, so the first `u16` is the low half.
+        unsafe {
+            halves.write_volatile(0x1234);
+            halves.add(1).write_volatile(0xABCD);
+        }
+    }
+    verify(mem, |_| 0xABCD_1234)?;
+
+    for (i, word) in mem.iter_mut().enumerate() {
+        // SAFETY: `word` is a valid, aligned `&mut u32`.
+        unsafe { (word as *mut u32).write_volatile(address_value(i)) };
+    }
+    verify(mem, address_value)?;
+
+    for word in mem.iter_mut() {
+        // SAFETY: `word` is a valid, aligned `&mut u32`.
+        unsafe { (word as *mut u32).write_volatile(0) };
+    }
+    Ok(())
+}
+
+/// Tests palette RAM, VRAM, and OAM, returning the first failure.
+///
+/// This erases all three regions, so it's meant for a diagnostics screen or
+/// to run at boot before anything is loaded. The display is kept in forced
+/// blank while testing, so the PPU doesn't get in the way of the CPU's
+/// accesses, and the test takes a few frames.
+pub fn memtest() -> Result<(), (Region, Failure)> {
+    let _blank = crate::display::ForcedBlank::new();
+    for region in Region::ALL {
+        // SAFETY: the region is memory that exists on every GBA, is aligned,
+        // and is only accessed by the PPU, which is kept out by forced blank.
+        let mem = unsafe {
+            core::slice::from_raw_parts_mut(region.base() as *mut u32, region.size() / 4)
+        };
+        test_words(mem).map_err(|failure| (region, failure))?;
+    }
+    Ok(())
+}
+
+/// Shows the result of a [`memtest`] by making the whole screen green if it
+/// passed, or red if it failed.
+///
+/// This sets `DISPCNT` to mode 0 with no layers, so the screen is only the
+/// backdrop color, which is then set. Since that's the first palette entry,
+/// it even works when palette RAM is the region that failed, unless that
+/// exact entry is broken.
+pub fn show(result: Result<(), (Region, Failure)>) {
+    let color: u16 = if result.is_ok() { 0x03E0 } else { 0x001F };
+    // SAFETY: `DISPCNT` is a plain read/write register, and the backdrop
+    // color is the first `u16` of palette RAM.
+    unsafe {
+        (Region::Palram.base() as *mut u16).write_volatile(color);
+        crate::mmio::DISPCNT.write_volatile(0);
+    }
+}
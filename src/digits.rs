@@ -0,0 +1,198 @@
+//! Number display without `core::fmt`.
+//!
+//! `core::fmt` is large and slow on the GBA, which matters for a score counter
+//! that's redrawn every frame. [`DigitTiles`] writes numbers straight into
+//! screenblock entries instead, and the BCD functions keep a score in a form
+//! that's cheap to add to and to display.
+//!
+//! None of this divides: the GBA has no divide instruction, so dividing by 10
+//! is done with a multiply.
+
+/// Divides by 10, returning the quotient and remainder.
+#[inline]
+const fn div10(n: u32) -> (u32, u32) {
+    let q = ((n as u64 * 0xCCCC_CCCD) >> 35) as u32;
+    (q, n - q * 10)
+}
+
+/// Converts a number to packed BCD, four bits per decimal digit.
+///
+/// Only the lowest 8 decimal digits fit, so larger numbers wrap.
+#[inline]
+#[must_use]
+pub const fn to_bcd(mut n: u32) -> u32 {
+    let mut bcd = 0;
+    let mut shift = 0;
+    while shift < 32 {
+        let (q, r) = div10(n);
+        bcd |= r << shift;
+        n = q;
+        shift += 4;
+    }
+    bcd
+}
+
+/// Converts packed BCD back to a number.
+///
+/// Nibbles above 9 aren't valid BCD, and give a meaningless result.
+#[inline]
+#[must_use]
+pub const fn from_bcd(bcd: u32) -> u32 {
+    let mut n = 0;
+    let mut shift = 32;
+    while shift > 0 {
+        shift -= 4;
+        n = n * 10 + ((bcd >> shift) & 0xF);
+    }
+    n
+}
+
+/// Adds two packed BCD numbers, saturating at `99999999`.
+#[inline]
+#[must_use]
+pub const fn bcd_add(a: u32, b: u32) -> u32 {
+    let mut sum = 0;
+    let mut carry = 0;
+    let mut shift = 0;
+    while shift < 32 {
+        let mut d = ((a >> shift) & 0xF) + ((b >> shift) & 0xF) + carry;
+        carry = (d > 9) as u32;
+        if carry != 0 {
+            d -= 10;
+        }
+        sum |= d << shift;
+        shift += 4;
+    }
+    if carry != 0 {
+        0x9999_9999
+    } else {
+        sum
+    }
+}
+
+/// The screenblock entries used to draw numbers.
+///
+/// The digit tiles `0` to `9` must be consecutive, so the entry for a digit is
+/// the entry for `0` plus the digit. The entries can include palette and flip
+/// bits as well as the tile index.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DigitTiles {
+    zero: u16,
+    blank: u16,
+    point: u16,
+}
+impl DigitTiles {
+    /// Uses the given entry for `0`, and the nine after it for `1` to `9`.
+    ///
+    /// The blank entry starts as tile 0 with the same palette, and the decimal
+    /// point starts as the entry after `9`.
+    #[inline]
+    #[must_use]
+    pub const fn new(zero: u16) -> Self {
+        Self {
+            zero,
+            blank: zero & 0xF000,
+            point: zero.wrapping_add(10),
+        }
+    }
+
+    /// Sets the entry used for padding.
+    #[inline]
+    #[must_use]
+    pub const fn with_blank(mut self, blank: u16) -> Self {
+        self.blank = blank;
+        self
+    }
+
+    /// Sets the entry used for the decimal point.
+    #[inline]
+    #[must_use]
+    pub const fn with_point(mut self, point: u16) -> Self {
+        self.point = point;
+        self
+    }
+
+    /// The entry for a single digit.
+    #[inline]
+    #[must_use]
+    pub const fn digit(&self, d: u8) -> u16 {
+        self.zero.wrapping_add(d as u16)
+    }
+
+    /// Writes `n` right-aligned, padded on the left with `pad`.
+    ///
+    /// If there's no room for every digit, the output is all nines.
+    fn fill_right(&self, mut n: u32, out: &mut [u16], pad: u16) {
+        let mut i = out.len();
+        while i > 0 {
+            i -= 1;
+            let (q, r) = div10(n);
+            out[i] = self.digit(r as u8);
+            n = q;
+            if n == 0 {
+                break;
+            }
+        }
+        if n != 0 {
+            out.fill(self.digit(9));
+        } else {
+            out[..i].fill(pad);
+        }
+    }
+
+    /// Writes `n` right-aligned in `out`, with blanks to the left.
+    ///
+    /// If `out` is too short for every digit, it's filled with nines, the way
+    /// score counters usually max out.
+    #[inline]
+    pub fn write(&self, n: u32, out: &mut [u16]) {
+        self.fill_right(n, out, self.blank);
+    }
+
+    /// Writes `n` using all of `out`, with zeroes to the left.
+    ///
+    /// If `out` is too short for every digit, it's filled with nines.
+    #[inline]
+    pub fn write_zero_padded(&self, n: u32, out: &mut [u16]) {
+        self.fill_right(n, out, self.zero);
+    }
+
+    /// Writes a packed BCD number using all of `out`, with zeroes to the left.
+    ///
+    /// Only the lowest `out.len()` digits are written.
+    #[inline]
+    pub fn write_bcd(&self, bcd: u32, out: &mut [u16]) {
+        let mut bcd = bcd;
+        for entry in out.iter_mut().rev() {
+            *entry = self.digit((bcd & 0xF) as u8);
+            bcd >>= 4;
+        }
+    }
+
+    /// Writes a fixed-point number with `frac_bits` fractional bits,
+    /// right-aligned, showing `decimals` digits after the point.
+    ///
+    /// The extra digits are truncated rather than rounded. If the whole part
+    /// doesn't fit, it's filled with nines.
+    ///
+    /// ## Panics
+    /// * If `out` doesn't have room for the point, the decimals, and at least
+    ///   one digit before the point.
+    /// * If `frac_bits` is more than 28.
+    #[cfg_attr(feature = "track_caller", track_caller)]
+    pub fn write_fixed(&self, raw: u32, frac_bits: u32, decimals: usize, out: &mut [u16]) {
+        assert!(frac_bits <= 28, "Too many fractional bits.");
+        assert!(out.len() > decimals + 1, "No room for the number.");
+        let mask = (1 << frac_bits) - 1;
+        let (whole, fraction) = out.split_at_mut(out.len() - decimals);
+        let (point, whole) = whole.split_last_mut().unwrap();
+        self.write(raw >> frac_bits, whole);
+        *point = self.point;
+        let mut frac = raw & mask;
+        for entry in fraction {
+            frac *= 10;
+            *entry = self.digit((frac >> frac_bits) as u8);
+            frac &= mask;
+        }
+    }
+}
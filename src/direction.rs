@@ -0,0 +1,335 @@
+//! 8 and 16 way directions, angles, and distances, without trigonometry or
+//! division.
+//!
+//! Directions are numbered clockwise on screen starting from right, since the
+//! GBA's y axis points down. Unit vectors are 8.8 fixed-point, so `256` is one
+//! pixel, which is the format used for sub-pixel movement and the affine
+//! registers.
+
+use crate::GbaCellSafe;
+
+/// Counts how many of the `tangents` (8.8 fixed-point) the angle of
+/// `(ax, ay)` is past.
+#[inline]
+const fn sector(ax: u32, ay: u32, tangents: &[i64]) -> u8 {
+    let mut s = 0;
+    while s < tangents.len() {
+        if (ay as i64) * 256 <= (ax as i64) * tangents[s] {
+            break;
+        }
+        s += 1;
+    }
+    s as u8
+}
+
+/// Picks the direction index out of `steps` from the sector within a quadrant.
+#[inline]
+const fn from_sector(dx: i32, dy: i32, s: u8, steps: u8) -> u8 {
+    let half = steps / 2;
+    match (dx >= 0, dy >= 0) {
+        (true, true) => s,
+        (false, true) => half - s,
+        (false, false) => half + s,
+        (true, false) => (steps - s) % steps,
+    }
+}
+
+/// One of 8 directions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum Dir8 {
+    Right,
+    DownRight,
+    Down,
+    DownLeft,
+    Left,
+    UpLeft,
+    Up,
+    UpRight,
+}
+impl Dir8 {
+    /// Every direction, clockwise from right.
+    pub const ALL: [Dir8; 8] = [
+        Dir8::Right,
+        Dir8::DownRight,
+        Dir8::Down,
+        Dir8::DownLeft,
+        Dir8::Left,
+        Dir8::UpLeft,
+        Dir8::Up,
+        Dir8::UpRight,
+    ];
+
+    /// The direction with the given index, counting clockwise from right.
+    ///
+    /// Indexes wrap around, so `8` is right again.
+    #[inline]
+    #[must_use]
+    pub const fn from_index(i: u8) -> Self {
+        Self::ALL[(i % 8) as usize]
+    }
+
+    /// The index of the direction, counting clockwise from right.
+    #[inline]
+    #[must_use]
+    pub const fn index(self) -> u8 {
+        self as u8
+    }
+
+    /// The closest direction to an offset, or `None` for `(0, 0)`.
+    #[must_use]
+    pub const fn from_delta(dx: i32, dy: i32) -> Option<Self> {
+        if dx == 0 && dy == 0 {
+            return None;
+        }
+        // tan(22.5°) and tan(67.5°)
+        let s = sector(dx.unsigned_abs(), dy.unsigned_abs(), &[106, 618]);
+        Some(Self::from_index(from_sector(dx, dy, s, 8)))
+    }
+
+    /// The whole-tile step in this direction, with each part `-1`, `0`, or
+    /// `1`.
+    #[inline]
+    #[must_use]
+    pub const fn step(self) -> (i32, i32) {
+        match self {
+            Dir8::Right => (1, 0),
+            Dir8::DownRight => (1, 1),
+            Dir8::Down => (0, 1),
+            Dir8::DownLeft => (-1, 1),
+            Dir8::Left => (-1, 0),
+            Dir8::UpLeft => (-1, -1),
+            Dir8::Up => (0, -1),
+            Dir8::UpRight => (1, -1),
+        }
+    }
+
+    /// The 8.8 fixed-point unit vector in this direction.
+    #[inline]
+    #[must_use]
+    pub const fn unit(self) -> (i32, i32) {
+        Dir16::from_index(self.index() * 2).unit()
+    }
+
+    /// Turns clockwise by `steps` eighths of a turn (or counterclockwise for
+    /// negative steps).
+    #[inline]
+    #[must_use]
+    pub const fn rotate(self, steps: i8) -> Self {
+        Self::from_index(self.index().wrapping_add(steps as u8))
+    }
+
+    /// The direction facing the other way.
+    #[inline]
+    #[must_use]
+    pub const fn opposite(self) -> Self {
+        self.rotate(4)
+    }
+}
+
+/// The cosine of each of the 16 directions, in 8.8 fixed-point.
+const COS16: [i32; 16] = [
+    256, 237, 181, 98, 0, -98, -181, -237, -256, -237, -181, -98, 0, 98, 181, 237,
+];
+
+/// One of 16 directions, in steps of 22.5°.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Dir16(u8);
+impl Dir16 {
+    /// Right, the direction with index 0.
+    pub const RIGHT: Self = Self(0);
+
+    /// The direction with the given index, counting clockwise from right.
+    ///
+    /// Indexes wrap around, so `16` is right again.
+    #[inline]
+    #[must_use]
+    pub const fn from_index(i: u8) -> Self {
+        Self(i % 16)
+    }
+
+    /// The index of the direction, counting clockwise from right.
+    #[inline]
+    #[must_use]
+    pub const fn index(self) -> u8 {
+        self.0
+    }
+
+    /// The closest direction to an offset, or `None` for `(0, 0)`.
+    ///
+    /// This is how to aim a projectile from one point toward another.
+    #[must_use]
+    pub const fn from_delta(dx: i32, dy: i32) -> Option<Self> {
+        if dx == 0 && dy == 0 {
+            return None;
+        }
+        // tan(11.25°), tan(33.75°), tan(56.25°), and tan(78.75°)
+        let s = sector(dx.unsigned_abs(), dy.unsigned_abs(), &[51, 171, 383, 1287]);
+        Some(Self::from_index(from_sector(dx, dy, s, 16)))
+    }
+
+    /// The 8.8 fixed-point unit vector in this direction.
+    #[inline]
+    #[must_use]
+    pub const fn unit(self) -> (i32, i32) {
+        (COS16[self.0 as usize], COS16[((self.0 + 12) % 16) as usize])
+    }
+
+    /// Turns clockwise by `steps` sixteenths of a turn (or counterclockwise for
+    /// negative steps).
+    #[inline]
+    #[must_use]
+    pub const fn rotate(self, steps: i8) -> Self {
+        Self::from_index(self.0.wrapping_add(steps as u8))
+    }
+
+    /// The direction facing the other way.
+    #[inline]
+    #[must_use]
+    pub const fn opposite(self) -> Self {
+        self.rotate(8)
+    }
+
+    /// The closest of the 8 directions, rounding clockwise when exactly
+    /// between two.
+    #[inline]
+    #[must_use]
+    pub const fn to_dir8(self) -> Dir8 {
+        Dir8::from_index(self.0.div_ceil(2))
+    }
+}
+
+impl From<Dir8> for Dir16 {
+    #[inline]
+    fn from(d: Dir8) -> Self {
+        Self(d.index() * 2)
+    }
+}
+
+impl From<Dir8> for Angle {
+    #[inline]
+    fn from(d: Dir8) -> Self {
+        Self(u16::from(d.index()) << 13)
+    }
+}
+
+impl From<Dir16> for Angle {
+    #[inline]
+    fn from(d: Dir16) -> Self {
+        Self(u16::from(d.index()) << 12)
+    }
+}
+
+/// An angle in 65536ths of a turn, clockwise on screen from right.
+///
+/// This is the same format as the BIOS `ArcTan2` result and the angle given
+/// to `ObjAffineSet`, so `0x4000` is a quarter turn (pointing down).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+#[repr(transparent)]
+pub struct Angle(u16);
+impl Angle {
+    /// Makes an angle from its bits, in 65536ths of a turn.
+    #[inline]
+    #[must_use]
+    pub const fn from_bits(bits: u16) -> Self {
+        Self(bits)
+    }
+
+    /// The angle in 65536ths of a turn.
+    #[inline]
+    #[must_use]
+    pub const fn bits(self) -> u16 {
+        self.0
+    }
+
+    /// The closest of the 16 directions.
+    #[inline]
+    #[must_use]
+    pub const fn to_dir16(self) -> Dir16 {
+        Dir16::from_index((self.0.wrapping_add(0x800) >> 12) as u8)
+    }
+
+    /// The closest of the 8 directions.
+    #[inline]
+    #[must_use]
+    pub const fn to_dir8(self) -> Dir8 {
+        Dir8::from_index((self.0.wrapping_add(0x1000) >> 13) as u8)
+    }
+}
+
+// SAFETY: `Angle` is a `repr(transparent)` newtype over `u16`.
+unsafe impl GbaCellSafe for Angle {}
+
+// SAFETY: `Angle` is a `repr(transparent)` newtype over `u16`, and every bit
+// pattern is a valid angle.
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Zeroable for Angle {}
+
+// SAFETY: as above.
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Pod for Angle {}
+
+/// `atan(2^-i)` in 65536ths of a turn, for each CORDIC step.
+const ATAN_STEPS: [i32; 14] = [
+    8192, 4836, 2555, 1297, 651, 326, 163, 81, 41, 20, 10, 5, 3, 1,
+];
+
+/// The angle of an offset, to within about 0.03°, or `None` for `(0, 0)`.
+///
+/// This uses CORDIC, which only needs shifts and adds, so it's cheap without
+/// a hardware divider or FPU.
+#[must_use]
+pub const fn atan2_approx(dy: i32, dx: i32) -> Option<Angle> {
+    if dx == 0 && dy == 0 {
+        return None;
+    }
+    // Turn the vector into the right half, then scale it so the larger part
+    // has 29 bits: enough precision, with room for the CORDIC gain of ~1.65.
+    let (mut x, mut y, mut angle) = if dx < 0 {
+        (-(dx as i64), -(dy as i64), 0x8000_i32)
+    } else {
+        (dx as i64, dy as i64, 0)
+    };
+    let largest = if x > y.abs() { x } else { y.abs() };
+    let bits = 64 - largest.leading_zeros() as i32;
+    if bits > 29 {
+        x >>= bits - 29;
+        y >>= bits - 29;
+    } else {
+        x <<= 29 - bits;
+        y <<= 29 - bits;
+    }
+    let (mut x, mut y) = (x as i32, y as i32);
+    // Rotate toward the x axis by smaller and smaller steps, adding up how
+    // far it was turned.
+    let mut i = 0;
+    while i < ATAN_STEPS.len() {
+        let (sx, sy) = (x >> i, y >> i);
+        if y > 0 {
+            (x, y) = (x + sy, y - sx);
+            angle += ATAN_STEPS[i];
+        } else {
+            (x, y) = (x - sy, y + sx);
+            angle -= ATAN_STEPS[i];
+        }
+        i += 1;
+    }
+    Some(Angle(angle as u16))
+}
+
+/// The length of an offset, to within about 4% plus rounding.
+///
+/// This is the "alpha max plus beta min" estimate, so it needs no square root
+/// or division. That estimate runs high away from the axes, so it's lowered
+/// a little unless the offset is nearly on an axis (the larger part at least
+/// 16 times the smaller).
+#[must_use]
+pub const fn dist_approx(dx: i32, dy: i32) -> u32 {
+    let (ax, ay) = (dx.unsigned_abs() as u64, dy.unsigned_abs() as u64);
+    let (max, min) = if ax > ay { (ax, ay) } else { (ay, ax) };
+    let mut approx = max * 1007 + min * 441;
+    if max < min * 16 {
+        approx -= max * 40;
+    }
+    ((approx + 512) >> 10) as u32
+}
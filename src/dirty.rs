@@ -0,0 +1,163 @@
+//! Dirty rectangle tracking for Mode 4 double buffering.
+//!
+//! In Mode 4 the game draws into the back page while the front page is shown,
+//! then flips. After a flip the new back page is a frame behind, and copying
+//! the whole 38 KiB page to catch it up costs a large part of the frame. A
+//! [`DirtyRects`] records what was drawn instead, so only those regions need
+//! copying from the page just finished to the new back page.
+
+use crate::collision::Aabb;
+
+/// The Mode 4 screen width in pixels.
+const WIDTH: i32 = 240;
+/// The Mode 4 screen height in pixels.
+const HEIGHT: i32 = 160;
+/// The number of `u16`s in one row of a Mode 4 page, which holds two pixels
+/// per `u16`.
+const ROW_UNITS: usize = WIDTH as usize / 2;
+
+/// The smallest box covering both boxes.
+#[inline]
+const fn union(a: &Aabb, b: &Aabb) -> Aabb {
+    let x = if a.x < b.x { a.x } else { b.x };
+    let y = if a.y < b.y { a.y } else { b.y };
+    let right = if a.right() > b.right() { a.right() } else { b.right() };
+    let bottom = if a.bottom() > b.bottom() { a.bottom() } else { b.bottom() };
+    Aabb::new(x, y, right - x, bottom - y)
+}
+
+#[inline]
+const fn area(a: &Aabb) -> i32 {
+    a.width * a.height
+}
+
+/// Records up to `N` changed regions of the screen.
+///
+/// Overlapping regions are merged as they're added. When all `N` are in use,
+/// a new region is merged into whichever existing one grows the least, so the
+/// regions always cover everything drawn, though they may cover more. `N`
+/// must be at least 1 (this is checked at compile time).
+#[derive(Debug, Clone)]
+pub struct DirtyRects<const N: usize> {
+    rects: [Aabb; N],
+    len: usize,
+}
+impl<const N: usize> DirtyRects<N> {
+    /// Helper to assert the capacity requirements at compile time.
+    const _ASSERT_CAPACITY: () = {
+        if N == 0 {
+            panic!("DirtyRects needs room for at least one region.")
+        }
+    };
+
+    /// Makes an empty set of regions.
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        let () = Self::_ASSERT_CAPACITY;
+        Self {
+            rects: [Aabb::new(0, 0, 0, 0); N],
+            len: 0,
+        }
+    }
+
+    /// The recorded regions.
+    #[inline]
+    #[must_use]
+    pub fn rects(&self) -> &[Aabb] {
+        &self.rects[..self.len]
+    }
+
+    /// If no regions are recorded.
+    #[inline]
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Forgets every region.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+
+    /// Records a drawn region.
+    ///
+    /// The region is clipped to the screen, and widened to even x edges since
+    /// Mode 4 VRAM is written two pixels at a time.
+    pub fn add(&mut self, rect: Aabb) {
+        let x = rect.x.clamp(0, WIDTH) & !1;
+        let y = rect.y.clamp(0, HEIGHT);
+        let right = (rect.right().clamp(0, WIDTH) + 1) & !1;
+        let bottom = rect.bottom().clamp(0, HEIGHT);
+        if right <= x || bottom <= y {
+            return;
+        }
+        let mut rect = Aabb::new(x, y, right - x, bottom - y);
+        // Merging can make the new box overlap ones it didn't before, so
+        // keep going until it overlaps nothing.
+        while let Some(i) = self.rects().iter().position(|r| r.overlaps(&rect)) {
+            rect = union(&rect, &self.rects[i]);
+            self.len -= 1;
+            self.rects[i] = self.rects[self.len];
+        }
+        if self.len < N {
+            self.rects[self.len] = rect;
+            self.len += 1;
+        } else if let Some(best) = self
+            .rects()
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, r)| area(&union(r, &rect)) - area(r))
+            .map(|(i, _)| i)
+        {
+            let merged = union(&self.rects[best], &rect);
+            self.len -= 1;
+            self.rects[best] = self.rects[self.len];
+            self.add(merged);
+        }
+    }
+
+    /// The number of pixels the regions cover.
+    #[inline]
+    #[must_use]
+    pub fn pixels(&self) -> u32 {
+        self.rects().iter().map(|r| area(r) as u32).sum()
+    }
+
+    /// Copies the regions from one Mode 4 page to another.
+    ///
+    /// Each page is 120 `u16`s per row, for 160 rows, such as
+    /// `0x0600_0000` and `0x0600_A000` viewed as slices.
+    ///
+    /// ## Panics
+    /// * If either page is shorter than 19200 `u16`s.
+    #[cfg_attr(feature = "track_caller", track_caller)]
+    pub fn copy(&self, from: &[u16], to: &mut [u16]) {
+        let page = ROW_UNITS * HEIGHT as usize;
+        assert!(from.len() >= page && to.len() >= page, "A Mode 4 page is 19200 u16s.");
+        for r in self.rects() {
+            let start = r.x as usize / 2;
+            let end = r.right() as usize / 2;
+            for row in r.y as usize..r.bottom() as usize {
+                let base = row * ROW_UNITS;
+                let src = &from[base + start..base + end];
+                let dst = &mut to[base + start..base + end];
+                for (d, &s) in dst.iter_mut().zip(src) {
+                    // VRAM can't be written a byte at a time, which `memcpy`
+                    // might do, so each `u16` is written with a volatile
+                    // write.
+                    // SAFETY: `d` is a valid, aligned `&mut u16`.
+                    unsafe { (d as *mut u16).write_volatile(s) };
+                }
+            }
+        }
+    }
+}
+
+impl<const N: usize> Default for DirtyRects<N> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,291 @@
+//! Forced blank, for fast bulk VRAM setup, and where in the frame we are.
+//!
+//! While the display is in forced blank, the PPU doesn't access VRAM, OAM, or
+//! palette RAM at all, so the CPU and DMA can write them with no wait states
+//! at any time rather than only during vblank. The screen shows white
+//! meanwhile, which is fine while loading a level or switching scenes.
+//!
+//! Otherwise, video memory should only be changed during vblank. Writes made
+//! while a frame is being drawn show up partway down the screen, which is the
+//! classic cause of tearing and flicker. [`in_vblank`] and
+//! [`lines_until_vblank`] tell where the frame is, and
+//! [`assert_safe_to_write`] catches such writes in debug builds.
+//! [`set_vcount_irq`] runs a handler partway down the screen instead, and
+//! [`enable_vblank_irq`] sets up the interrupt that starts vblank.
+//!
+//! [`set_mode`] combines the two to switch video modes cleanly.
+
+use crate::{mmio, GbaFnCell};
+
+/// The forced blank bit of `DISPCNT`.
+pub(crate) const FORCED_BLANK: u16 = 1 << 7;
+
+/// The video mode bits of `DISPCNT`.
+const MODE_MASK: u16 = 0b111;
+
+/// The vblank bit of `DISPSTAT`.
+const VBLANK: u16 = 1 << 0;
+
+/// The vblank interrupt enable bit of `DISPSTAT`.
+const VBLANK_IRQ_ENABLE: u16 = 1 << 3;
+
+/// The vcount interrupt enable bit of `DISPSTAT`.
+const VCOUNT_IRQ_ENABLE: u16 = 1 << 5;
+
+/// The vblank bit of `IE` and `IF`.
+pub(crate) const VBLANK_IRQ: u16 = 1 << 0;
+
+/// The vcount bit of `IE` and `IF`.
+const VCOUNT_IRQ: u16 = 1 << 2;
+
+/// The function [`on_vcount_irq`] calls.
+static VCOUNT_HANDLER: GbaFnCell<(), ()> = GbaFnCell::new();
+
+/// The first scanline of vblank.
+pub const VBLANK_LINE: u16 = 160;
+
+/// The number of scanlines in a frame, including vblank.
+pub const LINES_PER_FRAME: u16 = 228;
+
+/// The scanline being drawn, from 0 to 227.
+///
+/// Lines 160 and up are vblank.
+#[inline]
+#[must_use]
+pub fn vcount() -> u16 {
+    // SAFETY: `VCOUNT` is a read-only register with no side effects.
+    unsafe { mmio::VCOUNT.read_volatile() }
+}
+
+/// If the display is in vblank.
+///
+/// This reads the `DISPSTAT` flag, which is clear on the frame's last line
+/// (227), since the PPU is already fetching the first line of the next frame
+/// by then.
+#[inline]
+#[must_use]
+pub fn in_vblank() -> bool {
+    // SAFETY: reading `DISPSTAT` has no side effects.
+    unsafe { mmio::DISPSTAT.read_volatile() & VBLANK != 0 }
+}
+
+/// The number of scanlines left to draw before vblank starts.
+///
+/// This is 0 during vblank, and at most 161 (on line 227). Each
+/// line is 1232 cycles, so this is a rough budget for work that has to finish
+/// before the next vblank.
+#[inline]
+#[must_use]
+pub fn lines_until_vblank() -> u16 {
+    if in_vblank() {
+        0
+    } else {
+        // Line 227 is outside vblank, but a whole frame before the next one.
+        let line = vcount();
+        if line < VBLANK_LINE {
+            VBLANK_LINE - line
+        } else {
+            VBLANK_LINE + 1
+        }
+    }
+}
+
+/// If the display is in forced blank.
+#[inline]
+#[must_use]
+pub fn is_forced_blank() -> bool {
+    // SAFETY: `DISPCNT` is a plain read/write register.
+    unsafe { mmio::DISPCNT.read_volatile() & FORCED_BLANK != 0 }
+}
+
+/// If VRAM, OAM, and palette RAM can be written now without tearing, because
+/// the display is in vblank or forced blank.
+#[inline]
+#[must_use]
+pub fn safe_to_write() -> bool {
+    is_forced_blank() || in_vblank()
+}
+
+/// In debug builds, panics if video memory isn't safe to write right now.
+///
+/// Call this just before writing VRAM, OAM, or palette RAM. It compiles to
+/// nothing in release builds.
+///
+/// ## Panics
+/// * In debug builds, if the display is neither in vblank nor forced blank.
+#[inline]
+#[cfg_attr(feature = "track_caller", track_caller)]
+pub fn assert_safe_to_write() {
+    debug_assert!(
+        safe_to_write(),
+        "Video memory written on scanline {} (outside vblank and forced blank).",
+        vcount()
+    );
+}
+
+/// A video mode, the low 3 bits of `DISPCNT`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u16)]
+pub enum Mode {
+    /// Four regular tiled backgrounds.
+    Mode0 = 0,
+    /// Two regular tiled backgrounds and one affine background.
+    Mode1 = 1,
+    /// Two affine tiled backgrounds.
+    Mode2 = 2,
+    /// One full screen 15-bit bitmap.
+    Mode3 = 3,
+    /// Two paged 240x160 8-bit paletted bitmaps.
+    Mode4 = 4,
+    /// Two paged 160x128 15-bit bitmaps.
+    Mode5 = 5,
+}
+
+/// Switches video mode without showing a frame of garbage.
+///
+/// The display goes into forced blank, the mode is written, and then `setup`
+/// runs to load the new mode's graphics and background settings, with free
+/// access to video memory. The display comes back at the start of the next
+/// vblank, so the first frame shown is drawn entirely from the new setup.
+///
+/// Waiting for vblank busy-waits, so this is meant for scene changes rather
+/// than anything done every frame.
+pub fn set_mode<R>(mode: Mode, setup: impl FnOnce() -> R) -> R {
+    let blank = ForcedBlank::new();
+    // SAFETY: `DISPCNT` is a plain read/write register, and the display is
+    // in forced blank while the mode changes.
+    unsafe {
+        let dispcnt = mmio::DISPCNT.read_volatile();
+        mmio::DISPCNT.write_volatile((dispcnt & !MODE_MASK) | mode as u16);
+    }
+    let out = setup();
+    while in_vblank() {}
+    while !in_vblank() {}
+    drop(blank);
+    out
+}
+
+/// Keeps the display in forced blank until dropped.
+///
+/// ```no_run
+/// # use gba_cell::display::ForcedBlank;
+/// # fn load_level_graphics() {}
+/// {
+///     let _blank = ForcedBlank::new();
+///     load_level_graphics();
+/// } // the display comes back here
+/// ```
+///
+/// Guards can be nested. Dropping one returns forced blank to how it was when
+/// that guard was made, so the display only comes back when the outermost
+/// guard is dropped.
+#[derive(Debug)]
+#[must_use = "forced blank ends as soon as the guard is dropped"]
+pub struct ForcedBlank {
+    was_blank: bool,
+}
+impl ForcedBlank {
+    /// Turns on forced blank.
+    #[inline]
+    pub fn new() -> Self {
+        // SAFETY: `DISPCNT` is a plain read/write register, and setting
+        // forced blank only hides the display.
+        let dispcnt = unsafe { mmio::DISPCNT.read_volatile() };
+        unsafe { mmio::DISPCNT.write_volatile(dispcnt | FORCED_BLANK) };
+        Self {
+            was_blank: dispcnt & FORCED_BLANK != 0,
+        }
+    }
+}
+
+impl Default for ForcedBlank {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for ForcedBlank {
+    #[inline]
+    fn drop(&mut self) {
+        if !self.was_blank {
+            // SAFETY: `DISPCNT` is a plain read/write register. Only the
+            // forced blank bit is changed, so other changes made while the
+            // guard was alive are kept.
+            unsafe {
+                let dispcnt = mmio::DISPCNT.read_volatile();
+                mmio::DISPCNT.write_volatile(dispcnt & !FORCED_BLANK);
+            }
+        }
+    }
+}
+
+/// Turns on the vblank interrupt in `DISPSTAT` and `IE`.
+///
+/// The IRQ handler still has to deal with it when bit 0 (vblank) of `IF` is
+/// set, such as by ticking a [`FrameCounter`](crate::schedule::FrameCounter).
+/// `IME` is left alone.
+pub fn enable_vblank_irq() {
+    // SAFETY: as in `set_vcount_irq`.
+    unsafe {
+        let ime = mmio::IME.read_volatile();
+        mmio::IME.write_volatile(0);
+        let dispstat = mmio::DISPSTAT.read_volatile();
+        mmio::DISPSTAT.write_volatile(dispstat | VBLANK_IRQ_ENABLE);
+        mmio::IE.write_volatile(mmio::IE.read_volatile() | VBLANK_IRQ);
+        mmio::IME.write_volatile(ime);
+    }
+}
+
+/// Has `handler` run each time the display reaches `line`, such as to change
+/// scroll or palette settings partway down the screen.
+///
+/// This sets the line to match in `DISPSTAT`, turns on the vcount interrupt
+/// there and in `IE`, and stores the handler. The IRQ handler must call
+/// [`on_vcount_irq`] when the vcount bit is set in `IF` for it to run. `IME`
+/// is left alone.
+///
+/// ## Panics
+/// * If `line` is 228 or more, since it would never match.
+#[cfg_attr(feature = "track_caller", track_caller)]
+pub fn set_vcount_irq(line: u16, handler: extern "C" fn()) {
+    assert!(
+        line < LINES_PER_FRAME,
+        "The display only has lines 0 to 227."
+    );
+    VCOUNT_HANDLER.set(Some(handler));
+    // SAFETY: `IME`, `IE`, and `DISPSTAT` are plain read/write registers.
+    // Interrupts are held off while the two read-modify-writes happen, so an
+    // IRQ handler changing them at the same time can't be lost.
+    unsafe {
+        let ime = mmio::IME.read_volatile();
+        mmio::IME.write_volatile(0);
+        let dispstat = mmio::DISPSTAT.read_volatile();
+        mmio::DISPSTAT.write_volatile((dispstat & 0xFF) | VCOUNT_IRQ_ENABLE | (line << 8));
+        mmio::IE.write_volatile(mmio::IE.read_volatile() | VCOUNT_IRQ);
+        mmio::IME.write_volatile(ime);
+    }
+}
+
+/// Turns off the vcount interrupt set up by [`set_vcount_irq`], and clears
+/// the handler.
+pub fn clear_vcount_irq() {
+    // SAFETY: as in `set_vcount_irq`.
+    unsafe {
+        let ime = mmio::IME.read_volatile();
+        mmio::IME.write_volatile(0);
+        let dispstat = mmio::DISPSTAT.read_volatile();
+        mmio::DISPSTAT.write_volatile(dispstat & !VCOUNT_IRQ_ENABLE);
+        mmio::IE.write_volatile(mmio::IE.read_volatile() & !VCOUNT_IRQ);
+        mmio::IME.write_volatile(ime);
+    }
+    VCOUNT_HANDLER.clear();
+}
+
+/// Runs the handler given to [`set_vcount_irq`], if there is one.
+///
+/// Call this from the IRQ handler when bit 2 (vcount) of `IF` is set.
+#[inline]
+pub fn on_vcount_irq() {
+    VCOUNT_HANDLER.call();
+}
@@ -0,0 +1,113 @@
+//! Palette effects that run once per frame during vblank.
+//!
+//! A [`PaletteCycle`] (made with [`palette_cycle`]) rotates a span of palette
+//! entries every few frames, which animates waterfalls, fire, and conveyor
+//! belts drawn with those colors without touching any tiles.
+//!
+//! ```no_run
+//! # use gba_cell::effects::palette_cycle;
+//! # fn wait_for_vblank() {}
+//! // Background palette 1, entries 1 to 7, one step every 4 frames.
+//! let mut water = palette_cycle(17..24, 4);
+//! loop {
+//!     wait_for_vblank();
+//!     water.tick();
+//! }
+//! ```
+
+use crate::mmio;
+use core::ops::Range;
+
+/// The start of palette RAM: 256 background colors, then 256 object colors.
+const PALRAM: usize = 0x0500_0000;
+
+/// The number of entries in palette RAM.
+const PALRAM_ENTRIES: u16 = 512;
+
+/// Rotates a span of palette entries every `speed` frames.
+///
+/// The entries are given as indexes into the whole of palette RAM, where 0 to
+/// 255 are the background colors and 256 to 511 are the object colors. Each
+/// step moves every color up one entry, and the last one wraps around to the
+/// front. To cycle the other way, store the colors in the opposite order.
+///
+/// ## Panics
+/// * If `range` is empty or goes past entry 511.
+/// * If `speed` is 0.
+#[inline]
+#[must_use]
+#[cfg_attr(feature = "track_caller", track_caller)]
+pub const fn palette_cycle(range: Range<u16>, speed: u16) -> PaletteCycle {
+    assert!(
+        range.start < range.end && range.end <= PALRAM_ENTRIES,
+        "Palette cycles must be a non-empty span of entries 0 to 511."
+    );
+    assert!(speed != 0, "Palette cycles can't step every 0 frames.");
+    PaletteCycle {
+        start: range.start,
+        len: range.end - range.start,
+        speed,
+        wait: speed,
+    }
+}
+
+/// A palette rotation, from [`palette_cycle`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PaletteCycle {
+    start: u16,
+    len: u16,
+    speed: u16,
+    wait: u16,
+}
+impl PaletteCycle {
+    /// Counts one frame, rotating the colors if it's time for a step.
+    /// Returns if they were rotated.
+    ///
+    /// Call this once per frame during vblank, since the colors are written
+    /// straight to palette RAM.
+    pub fn tick(&mut self) -> bool {
+        self.wait -= 1;
+        if self.wait != 0 {
+            return false;
+        }
+        self.wait = self.speed;
+        self.step();
+        true
+    }
+
+    /// Rotates the colors by one entry right away.
+    pub fn step(&self) {
+        crate::display::assert_safe_to_write();
+        let first = (PALRAM as *mut u16).wrapping_add(usize::from(self.start));
+        let last = first.wrapping_add(usize::from(self.len) - 1);
+        // SAFETY: every entry from `first` to `last` is inside palette RAM,
+        // which takes 16 bit accesses. The DMA copies the `len - 1` entries
+        // below `last` up by one, starting from the top with both addresses
+        // decrementing, so each entry is read before it's overwritten. It's
+        // started immediately and this waits for it to finish.
+        unsafe {
+            let wrapped = last.read_volatile();
+            if self.len > 1 {
+                mmio::DMA3SAD.write_volatile(last.wrapping_sub(1) as u32);
+                mmio::DMA3DAD.write_volatile(last as u32);
+                mmio::DMA3CNT_L.write_volatile(self.len - 1);
+                // Enabled, 16 bit units, source and destination decrementing.
+                mmio::DMA3CNT_H.write_volatile(1 << 15 | 1 << 7 | 1 << 5);
+                while mmio::DMA3CNT_H.read_volatile() & (1 << 15) != 0 {}
+            }
+            first.write_volatile(wrapped);
+        }
+    }
+
+    /// Changes how many frames pass between steps, starting the count over.
+    ///
+    /// ## Panics
+    /// * If `speed` is 0.
+    #[inline]
+    #[cfg_attr(feature = "track_caller", track_caller)]
+    pub fn set_speed(&mut self, speed: u16) {
+        assert!(speed != 0, "Palette cycles can't step every 0 frames.");
+        self.speed = speed;
+        self.wait = speed;
+    }
+}
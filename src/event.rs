@@ -0,0 +1,294 @@
+//! An event bus for IRQ handlers to notify the main loop.
+//!
+//! Instead of one flag global per thing that can happen, IRQ handlers
+//! [`post`](EventBus::post) small events to a shared [`EventBus`], and the
+//! main loop [`drain`](EventBus::drain)s them once per frame.
+//!
+//! The same queue can carry log messages: [`log_from_irq!`](crate::log_from_irq)
+//! posts a [`LogRecord`] holding the format string and arguments, and the text
+//! is only formatted when the main loop displays the record, so handlers never
+//! run `core::fmt`.
+
+use crate::{GbaCell, GbaQueue};
+use core::fmt;
+
+/// The standard set of events that IRQ handlers tend to send.
+///
+/// Any other `Copy` type can be used with [`EventBus`] instead, but this one
+/// is only 4 bytes and covers the common cases.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Event {
+    /// A vertical blank started.
+    VBlank,
+    /// A byte arrived over the serial port.
+    SerialByte(u8),
+    /// The given timer (0 to 3) overflowed.
+    TimerTick(u8),
+    /// A key combination was pressed. Holds the key bits that were detected.
+    KeyCombo(u16),
+    /// A game-specific event.
+    User(u16),
+}
+
+/// A queue of events, posted from IRQ handlers and drained by the main loop.
+///
+/// This is a [`GbaQueue`] plus a count of events that were dropped because the
+/// queue was full. Posting and taking events mask interrupts for the few
+/// cycles the queue update takes, so unlike a bare [`GbaQueue`], any number of
+/// handlers can post, even ones that interrupt each other.
+pub struct EventBus<E = Event, const N: usize = 32> {
+    queue: GbaQueue<E, N>,
+    dropped: GbaCell<u16>,
+}
+impl<E, const N: usize> EventBus<E, N>
+where
+    E: Copy,
+{
+    /// Constructs a new bus with no events waiting.
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            queue: GbaQueue::new(),
+            dropped: GbaCell::new(0),
+        }
+    }
+
+    /// Sends an event to the main loop.
+    ///
+    /// If the bus is full the event is dropped, counted, and `false` is
+    /// returned.
+    #[inline]
+    pub fn post(&self, event: E) -> bool {
+        with_irqs_masked(|| {
+            // SAFETY: nothing else can push or pop while interrupts are
+            // masked, so this is the only producer.
+            let sent = unsafe { self.queue.push(event) }.is_ok();
+            if !sent {
+                self.dropped.write(self.dropped.read().wrapping_add(1));
+            }
+            sent
+        })
+    }
+
+    /// Takes the oldest waiting event, if any.
+    #[inline]
+    pub fn next(&self) -> Option<E> {
+        // SAFETY: as in `post`, this is the only consumer.
+        with_irqs_masked(|| unsafe { self.queue.pop() })
+    }
+
+    /// Iterates over the waiting events, removing each as it's returned.
+    ///
+    /// Events posted while draining will also be returned, so an IRQ that
+    /// fires during the loop isn't missed until next frame.
+    #[inline]
+    pub fn drain(&self) -> impl Iterator<Item = E> + '_ {
+        core::iter::from_fn(move || self.next())
+    }
+
+    /// The total number of events dropped so far, wrapping at `u16::MAX`.
+    ///
+    /// To see how many events were dropped in a frame, compare against the count from the last frame.
+    #[inline]
+    #[must_use]
+    pub fn dropped(&self) -> u16 {
+        self.dropped.read()
+    }
+}
+
+/// Runs `f` with `IME` off, then puts `IME` back the way it was.
+#[inline]
+#[cfg(target_arch = "arm")]
+fn with_irqs_masked<R>(f: impl FnOnce() -> R) -> R {
+    use crate::mmio;
+    use core::sync::atomic::{compiler_fence, Ordering};
+
+    // SAFETY: `IME` is a plain read/write register.
+    let ime = unsafe { mmio::IME.read_volatile() };
+    // SAFETY: as above.
+    unsafe { mmio::IME.write_volatile(0) };
+    // Keep the queue's memory accesses between the two `IME` writes.
+    compiler_fence(Ordering::SeqCst);
+    let r = f();
+    compiler_fence(Ordering::SeqCst);
+    // SAFETY: as above.
+    unsafe { mmio::IME.write_volatile(ime) };
+    r
+}
+
+/// Runs `f`. Off the GBA there are no interrupts to mask, and no `IME` to
+/// write.
+#[inline]
+#[cfg(not(target_arch = "arm"))]
+fn with_irqs_masked<R>(f: impl FnOnce() -> R) -> R {
+    f()
+}
+
+impl<E, const N: usize> Default for EventBus<E, N>
+where
+    E: Copy,
+{
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// One argument of a [`LogRecord`], which keeps track of whether it's signed.
+///
+/// It formats like the `i32` or `u32` it holds, with any of the integer
+/// formatting traits (`{}`, `{:x}`, `{:b}`, and so on).
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LogArg {
+    /// An unsigned integer, `bool`, or `char`, converted with `as u32`.
+    Unsigned(u32),
+    /// A signed integer, converted with `as i32`.
+    Signed(i32),
+}
+
+macro_rules! log_arg_fmt {
+    ($($t:ident),*) => {
+        $(
+            impl fmt::$t for LogArg {
+                #[inline]
+                fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                    match self {
+                        Self::Unsigned(n) => fmt::$t::fmt(n, f),
+                        Self::Signed(n) => fmt::$t::fmt(n, f),
+                    }
+                }
+            }
+        )*
+    };
+}
+log_arg_fmt!(Display, Debug, LowerHex, UpperHex, Octal, Binary);
+
+/// Values that [`log_from_irq!`](crate::log_from_irq) can take as arguments.
+pub trait IntoLogArg {
+    /// Converts the value, truncating anything wider than 32 bits.
+    fn into_log_arg(self) -> LogArg;
+}
+
+macro_rules! impl_into_log_arg {
+    ($variant:ident as $as:ty: $($t:ty),*) => {
+        $(
+            impl IntoLogArg for $t {
+                #[inline]
+                fn into_log_arg(self) -> LogArg {
+                    LogArg::$variant(self as $as)
+                }
+            }
+        )*
+    };
+}
+impl_into_log_arg!(Unsigned as u32: u8, u16, u32, u64, usize, bool, char);
+impl_into_log_arg!(Signed as i32: i8, i16, i32, i64, isize);
+
+/// A log message posted by [`log_from_irq!`](crate::log_from_irq), formatted
+/// later by its [`Display`](fmt::Display) impl.
+#[derive(Clone, Copy)]
+pub struct LogRecord {
+    format: fn(&[LogArg; 3], &mut fmt::Formatter<'_>) -> fmt::Result,
+    args: [LogArg; 3],
+}
+impl LogRecord {
+    /// Makes a record from a formatting function and up to three arguments.
+    ///
+    /// This is usually done by [`log_from_irq!`](crate::log_from_irq).
+    #[inline]
+    #[must_use]
+    pub const fn new(
+        format: fn(&[LogArg; 3], &mut fmt::Formatter<'_>) -> fmt::Result,
+        args: [LogArg; 3],
+    ) -> Self {
+        Self { format, args }
+    }
+
+    /// The arguments, with unused ones set to `LogArg::Unsigned(0)`.
+    #[inline]
+    #[must_use]
+    pub const fn args(&self) -> [LogArg; 3] {
+        self.args
+    }
+}
+
+impl fmt::Display for LogRecord {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        (self.format)(&self.args, f)
+    }
+}
+
+impl fmt::Debug for LogRecord {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LogRecord")
+            .field("args", &self.args)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Posts a log message to an [`EventBus<LogRecord>`](crate::event::EventBus) without
+/// formatting it, for use in IRQ handlers.
+///
+/// Takes the bus, a format string literal, and up to three integer arguments.
+/// Signed arguments are stored as `i32` and unsigned ones (or `bool`s and
+/// `char`s) as `u32`, so negative values still print with their sign, and
+/// anything wider is truncated. The format string is checked at compile
+/// time, but can only use positional arguments (`{}`, `{:04x}`), not named
+/// ones. Evaluates to `false` if the bus was full and the message dropped.
+///
+/// ```
+/// # use gba_cell::{event::{EventBus, LogRecord}, log_from_irq};
+/// static LOG: EventBus<LogRecord, 8> = EventBus::new();
+///
+/// // In the IRQ handler:
+/// log_from_irq!(LOG, "timer {} overflowed at {:#x}", 2, 0x1234);
+/// log_from_irq!(LOG, "scrolled by {}", -3_i16);
+///
+/// // In the main loop:
+/// let record = LOG.next().unwrap();
+/// assert_eq!(format!("{record}"), "timer 2 overflowed at 0x1234");
+/// let record = LOG.next().unwrap();
+/// assert_eq!(format!("{record}"), "scrolled by -3");
+/// ```
+#[macro_export]
+macro_rules! log_from_irq {
+    ($bus:expr, $fmt:literal $(,)?) => {
+        $bus.post($crate::event::LogRecord::new(
+            |_, f| ::core::write!(f, $fmt),
+            [$crate::event::LogArg::Unsigned(0); 3],
+        ))
+    };
+    ($bus:expr, $fmt:literal, $a:expr $(,)?) => {
+        $bus.post($crate::event::LogRecord::new(
+            |args, f| ::core::write!(f, $fmt, args[0]),
+            [
+                $crate::event::IntoLogArg::into_log_arg($a),
+                $crate::event::LogArg::Unsigned(0),
+                $crate::event::LogArg::Unsigned(0),
+            ],
+        ))
+    };
+    ($bus:expr, $fmt:literal, $a:expr, $b:expr $(,)?) => {
+        $bus.post($crate::event::LogRecord::new(
+            |args, f| ::core::write!(f, $fmt, args[0], args[1]),
+            [
+                $crate::event::IntoLogArg::into_log_arg($a),
+                $crate::event::IntoLogArg::into_log_arg($b),
+                $crate::event::LogArg::Unsigned(0),
+            ],
+        ))
+    };
+    ($bus:expr, $fmt:literal, $a:expr, $b:expr, $c:expr $(,)?) => {
+        $bus.post($crate::event::LogRecord::new(
+            |args, f| ::core::write!(f, $fmt, args[0], args[1], args[2]),
+            [
+                $crate::event::IntoLogArg::into_log_arg($a),
+                $crate::event::IntoLogArg::into_log_arg($b),
+                $crate::event::IntoLogArg::into_log_arg($c),
+            ],
+        ))
+    };
+}
@@ -0,0 +1,337 @@
+//! A minimal frame-driven `async` executor.
+//!
+//! [`Executor::run_frame`] is called once per frame and polls each task that
+//! has been woken since the last frame. This makes `async` blocks a handy way
+//! to script cutscenes and menus: "wait 30 frames, then wait until A is
+//! pressed, then fade out" reads top to bottom instead of as a state machine.
+//!
+//! There's no allocation. Tasks are pinned by the caller (usually with
+//! [`core::pin::pin!`]) and borrowed by the executor, and each task's "woken"
+//! flag is a [`GbaCell<bool>`] in a [`WakeFlags`] static, so wakers are safe
+//! to use from interrupt handlers.
+
+use crate::GbaCell;
+use core::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll, RawWaker, RawWakerVTable, Waker},
+};
+
+/// The "woken" flags for the tasks of an [`Executor`].
+///
+/// These have to be in a `static`, because wakers can outlive the executor.
+pub struct WakeFlags<const N: usize>([GbaCell<bool>; N]);
+impl<const N: usize> WakeFlags<N> {
+    /// Constructs a new set of flags, all clear.
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self([const { GbaCell::new(false) }; N])
+    }
+}
+
+impl<const N: usize> Default for WakeFlags<N> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// A `static` rather than a `const`, so that it has one address that wakers
+// can be recognized by.
+static VTABLE: RawWakerVTable = RawWakerVTable::new(clone_waker, wake, wake, drop_waker);
+
+unsafe fn clone_waker(data: *const ()) -> RawWaker {
+    RawWaker::new(data, &VTABLE)
+}
+unsafe fn wake(data: *const ()) {
+    // SAFETY: wakers are only made from flags in a `&'static WakeFlags`.
+    unsafe { (*data.cast::<GbaCell<bool>>()).write(true) }
+}
+unsafe fn drop_waker(_: *const ()) {}
+
+#[inline]
+fn waker_for(flag: &'static GbaCell<bool>) -> Waker {
+    let raw = RawWaker::new((flag as *const GbaCell<bool>).cast(), &VTABLE);
+    // SAFETY: the vtable functions only ever read the pointer as a
+    // `&'static GbaCell<bool>`, which is `Sync`.
+    unsafe { Waker::from_raw(raw) }
+}
+
+type Task<'a> = Pin<&'a mut dyn Future<Output = ()>>;
+
+/// Polls up to `N` tasks, once per frame at most.
+pub struct Executor<'a, const N: usize> {
+    tasks: [Option<Task<'a>>; N],
+    woken: &'static WakeFlags<N>,
+}
+impl<'a, const N: usize> Executor<'a, N> {
+    /// Makes an executor with no tasks.
+    ///
+    /// Each executor should have its own [`WakeFlags`].
+    #[inline]
+    #[must_use]
+    pub const fn new(woken: &'static WakeFlags<N>) -> Self {
+        Self {
+            tasks: [const { None }; N],
+            woken,
+        }
+    }
+
+    /// Adds a task, which is first polled on the next frame.
+    ///
+    /// If every slot is in use, the task is given back as an error.
+    pub fn spawn(&mut self, task: Task<'a>) -> Result<(), Task<'a>> {
+        let Some(slot) = self.tasks.iter().position(Option::is_none) else {
+            return Err(task);
+        };
+        self.tasks[slot] = Some(task);
+        self.woken.0[slot].write(true);
+        Ok(())
+    }
+
+    /// The number of tasks that haven't finished.
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.tasks.iter().flatten().count()
+    }
+
+    /// If every task has finished.
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.tasks.iter().all(Option::is_none)
+    }
+
+    /// Polls every task that's been woken since the last frame.
+    ///
+    /// Finished tasks are dropped, freeing their slot. Returns the number of
+    /// tasks that are still running.
+    pub fn run_frame(&mut self) -> usize {
+        for (task, flag) in self.tasks.iter_mut().zip(&self.woken.0) {
+            let Some(fut) = task else { continue };
+            if !flag.read() {
+                continue;
+            }
+            flag.write(false);
+            let waker = waker_for(flag);
+            if fut
+                .as_mut()
+                .poll(&mut Context::from_waker(&waker))
+                .is_ready()
+            {
+                *task = None;
+            }
+        }
+        self.len()
+    }
+}
+
+/// Waits for the given number of frames.
+///
+/// `wait_frames(0).await` finishes immediately, and `wait_frames(1).await`
+/// resumes on the next frame.
+#[inline]
+pub fn wait_frames(frames: u32) -> WaitFrames {
+    WaitFrames(frames)
+}
+
+/// The future returned by [`wait_frames`].
+#[derive(Debug)]
+#[must_use = "futures do nothing unless awaited"]
+pub struct WaitFrames(u32);
+impl Future for WaitFrames {
+    type Output = ();
+
+    #[inline]
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.0 == 0 {
+            return Poll::Ready(());
+        }
+        self.0 -= 1;
+        cx.waker().wake_by_ref();
+        Poll::Pending
+    }
+}
+
+/// Gives up the rest of this frame, resuming next frame.
+#[inline]
+pub fn next_frame() -> WaitFrames {
+    wait_frames(1)
+}
+
+/// Waits until the condition returns `true`, checking once per frame.
+///
+/// This covers conditions like "until A is pressed" or "until the fade is
+/// done", with the condition reading whatever input or state it needs.
+#[inline]
+pub fn wait_until<F>(condition: F) -> WaitUntil<F>
+where
+    F: FnMut() -> bool,
+{
+    WaitUntil(condition)
+}
+
+/// The future returned by [`wait_until`].
+#[derive(Debug)]
+#[must_use = "futures do nothing unless awaited"]
+pub struct WaitUntil<F>(F);
+impl<F> Future for WaitUntil<F>
+where
+    F: FnMut() -> bool + Unpin,
+{
+    type Output = ();
+
+    #[inline]
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if (self.0)() {
+            Poll::Ready(())
+        } else {
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    }
+}
+
+/// Lets tasks wait for an interrupt.
+///
+/// The interrupt handler calls [`notify`](Self::notify), and any task awaiting
+/// [`wait`](Self::wait) is woken and resumes on the executor's next frame.
+/// Tasks run by an [`Executor`] aren't polled at all while they wait. With
+/// any other executor the task is re-polled every time instead, which still
+/// works but isn't free.
+///
+/// One task at a time gets the direct wake-up. If several tasks wait on the
+/// same signal, the rest fall back to being re-polled each frame.
+#[derive(Debug)]
+pub struct IrqSignal {
+    count: GbaCell<u16>,
+    waiter: GbaCell<Option<&'static GbaCell<bool>>>,
+}
+impl IrqSignal {
+    /// Constructs a new signal with no waiters.
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            count: GbaCell::new(0),
+            waiter: GbaCell::new(None),
+        }
+    }
+
+    /// Records that the interrupt happened, waking the waiting task.
+    #[inline]
+    pub fn notify(&self) {
+        self.count.write(self.count.read().wrapping_add(1));
+        if let Some(flag) = self.waiter.read() {
+            self.waiter.write(None);
+            flag.write(true);
+        }
+    }
+
+    /// Waits until the next time the interrupt happens.
+    ///
+    /// Interrupts that happened before the first poll don't count.
+    #[inline]
+    pub fn wait(&self) -> IrqWait<'_> {
+        IrqWait {
+            signal: self,
+            start: None,
+        }
+    }
+}
+
+impl Default for IrqSignal {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The future returned by [`IrqSignal::wait`].
+#[derive(Debug)]
+#[must_use = "futures do nothing unless awaited"]
+pub struct IrqWait<'s> {
+    signal: &'s IrqSignal,
+    start: Option<u16>,
+}
+impl Future for IrqWait<'_> {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let count = self.signal.count.read();
+        let start = match self.start {
+            Some(start) if start != count => return Poll::Ready(()),
+            Some(start) => start,
+            None => *self.start.insert(count),
+        };
+        let waker = cx.waker();
+        let flag = core::ptr::eq(waker.vtable(), &VTABLE)
+            // SAFETY: wakers with our vtable always point at a `'static` flag.
+            .then(|| unsafe { &*waker.data().cast::<GbaCell<bool>>() });
+        match (flag, self.signal.waiter.read()) {
+            (Some(flag), None) => self.signal.waiter.write(Some(flag)),
+            (Some(flag), Some(current)) if core::ptr::eq(flag, current) => (),
+            _ => waker.wake_by_ref(),
+        }
+        // The interrupt can happen after `count` was read but before the
+        // waiter was stored, in which case nothing would wake this task, so
+        // check again now that it's stored.
+        if self.signal.count.read() != start {
+            if let (Some(flag), Some(current)) = (flag, self.signal.waiter.read()) {
+                if core::ptr::eq(flag, current) {
+                    self.signal.waiter.write(None);
+                }
+            }
+            return Poll::Ready(());
+        }
+        Poll::Pending
+    }
+}
+
+/// An [`IrqSignal`] for each of the GBA's 14 interrupt sources.
+///
+/// Signals are indexed by their bit in the `IE`/`IF` registers: 0 is vblank,
+/// 1 is hblank, 2 is vcount, 3 to 6 are timers 0 to 3, 7 is serial, 8 to 11
+/// are DMA 0 to 3, 12 is keypad, and 13 is the game pak.
+#[derive(Debug)]
+pub struct IrqSignals([IrqSignal; 14]);
+impl IrqSignals {
+    /// Constructs a new set of signals with no waiters.
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self([const { IrqSignal::new() }; 14])
+    }
+
+    /// Notifies every signal whose bit is set in `bits`.
+    ///
+    /// Call this from the IRQ handler with the `IF` bits being acknowledged.
+    #[inline]
+    pub fn notify(&self, bits: u16) {
+        for (i, signal) in self.0.iter().enumerate() {
+            if bits & (1 << i) != 0 {
+                signal.notify();
+            }
+        }
+    }
+
+    /// The signal for one interrupt source.
+    ///
+    /// ## Panics
+    /// * If `bit` is 14 or more.
+    #[inline]
+    #[must_use]
+    #[cfg_attr(feature = "track_caller", track_caller)]
+    pub fn get(&self, bit: usize) -> &IrqSignal {
+        &self.0[bit]
+    }
+}
+
+impl Default for IrqSignals {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
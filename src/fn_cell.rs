@@ -0,0 +1,108 @@
+//! Provides the [`GbaFnCell`] type.
+
+use crate::{GbaCell, GbaCellSafe};
+
+mod sealed {
+    pub trait Sealed<R> {}
+}
+
+/// The argument tuples a [`GbaFnCell`] can take, from `()` to four
+/// arguments.
+///
+/// This only exists to pick the stored function pointer type, and can't be
+/// implemented outside the crate.
+pub trait FnArgs<R>: sealed::Sealed<R> {
+    /// The stored pointer, an `Option<extern "C" fn(..) -> R>`.
+    type Ptr: GbaCellSafe;
+    /// The empty pointer.
+    const NONE: Self::Ptr;
+}
+
+/// A cell holding an optional `extern "C"` callback, such as the function an
+/// IRQ handler should call.
+///
+/// `Args` is the tuple of argument types and `Ret` the return type, so a
+/// `GbaFnCell<(u16,), bool>` holds an `Option<extern "C" fn(u16) -> bool>`.
+///
+/// Calls work whether the stored function is ARM or Thumb code (such as one
+/// made with [`arm_fn!`](crate::arm_fn)). A function pointer keeps the Thumb
+/// bit in its low bit, and calls through one always use `bx`, which switches
+/// instruction sets based on that bit. That only holds for pointers that came
+/// from a real function, which is why this type can't be filled from an
+/// address.
+#[repr(transparent)]
+pub struct GbaFnCell<Args: FnArgs<Ret>, Ret>(GbaCell<Args::Ptr>);
+
+impl<Args: FnArgs<Ret>, Ret> GbaFnCell<Args, Ret> {
+    /// Constructs a new, empty cell.
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self(GbaCell::new(Args::NONE))
+    }
+
+    /// Empties the cell.
+    #[inline]
+    pub fn clear(&self) {
+        self.0.write(Args::NONE);
+    }
+}
+
+impl<Args: FnArgs<Ret>, Ret> Default for GbaFnCell<Args, Ret> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+macro_rules! impl_fn_cell {
+    ($($arg:ident),*) => {
+        impl<R, $($arg),*> sealed::Sealed<R> for ($($arg,)*) {}
+        impl<R, $($arg),*> FnArgs<R> for ($($arg,)*) {
+            type Ptr = Option<extern "C" fn($($arg),*) -> R>;
+            const NONE: Self::Ptr = None;
+        }
+
+        impl<R, $($arg),*> GbaFnCell<($($arg,)*), R> {
+            /// Constructs a new cell holding a function.
+            #[inline]
+            #[must_use]
+            pub const fn with(f: extern "C" fn($($arg),*) -> R) -> Self {
+                Self(GbaCell::new(Some(f)))
+            }
+
+            /// The stored function, if any.
+            #[inline]
+            #[must_use]
+            pub fn get(&self) -> Option<extern "C" fn($($arg),*) -> R> {
+                self.0.read()
+            }
+
+            /// Stores a function, or empties the cell with `None`.
+            #[inline]
+            pub fn set(&self, f: Option<extern "C" fn($($arg),*) -> R>) {
+                self.0.write(f);
+            }
+
+            /// Calls the stored function, or returns `None` if the cell is
+            /// empty.
+            #[inline]
+            #[allow(non_snake_case)]
+            pub fn call(&self, $($arg: $arg),*) -> Option<R> {
+                self.get().map(|f| f($($arg),*))
+            }
+        }
+
+        impl<R, $($arg),*> core::fmt::Debug for GbaFnCell<($($arg,)*), R> {
+            #[inline]
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                f.debug_tuple("GbaFnCell").field(&self.get()).finish()
+            }
+        }
+    };
+}
+impl_fn_cell!();
+impl_fn_cell!(A);
+impl_fn_cell!(A, B);
+impl_fn_cell!(A, B, C);
+impl_fn_cell!(A, B, C, D);
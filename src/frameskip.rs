@@ -0,0 +1,95 @@
+//! Frame skipping, so slowdown stays playable.
+//!
+//! When update and draw together take longer than a frame, a game that does
+//! one update per loop slows down. [`FrameSkip`] instead keeps the updates at
+//! a fixed rate of one per vblank, catching up with extra updates and by
+//! skipping draws, the way commercial games degrade under load.
+//!
+//! ```no_run
+//! # use gba_cell::frameskip::FrameSkip;
+//! # fn wait_for_vblank() {}
+//! # fn vblank_count() -> u32 { 0 }
+//! # fn update() {}
+//! # fn draw() {}
+//! let mut skip = FrameSkip::new(2);
+//! loop {
+//!     wait_for_vblank();
+//!     let plan = skip.begin(vblank_count());
+//!     for _ in 0..plan.updates {
+//!         update();
+//!     }
+//!     if plan.draw {
+//!         draw();
+//!     }
+//! }
+//! ```
+
+/// What to do in one pass of the main loop.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FramePlan {
+    /// How many fixed-length updates to run.
+    pub updates: u32,
+    /// If the frame should be drawn.
+    pub draw: bool,
+}
+
+/// Decides how many updates to run and whether to draw, from how many vblanks
+/// have passed.
+#[derive(Debug, Clone)]
+pub struct FrameSkip {
+    last: Option<u32>,
+    max_skip: u8,
+    skipped: u8,
+    total_skipped: u32,
+}
+impl FrameSkip {
+    /// Allows skipping up to `max_skip` draws in a row.
+    ///
+    /// With `max_skip` of 0 every frame is drawn, and the game slows down
+    /// instead of skipping.
+    #[inline]
+    #[must_use]
+    pub const fn new(max_skip: u8) -> Self {
+        Self {
+            last: None,
+            max_skip,
+            skipped: 0,
+            total_skipped: 0,
+        }
+    }
+
+    /// Plans a pass of the main loop, given the current vblank count (such as
+    /// from a [`FrameCounter`](crate::schedule::FrameCounter)).
+    ///
+    /// Each vblank since the last pass gets an update, up to `max_skip + 1`
+    /// updates so that a long stall doesn't cause a burst of catching up. If
+    /// more than one vblank has passed, the last pass ran long, so the draw is
+    /// skipped to make up time, unless `max_skip` draws in a row have already
+    /// been skipped.
+    pub fn begin(&mut self, now: u32) -> FramePlan {
+        let elapsed = match self.last {
+            Some(last) => now.wrapping_sub(last),
+            None => 1,
+        };
+        self.last = Some(now);
+        let behind = elapsed > 1;
+        let draw = !behind || self.skipped >= self.max_skip;
+        if draw {
+            self.skipped = 0;
+        } else {
+            self.skipped += 1;
+            self.total_skipped = self.total_skipped.wrapping_add(1);
+        }
+        FramePlan {
+            updates: elapsed.clamp(1, u32::from(self.max_skip) + 1),
+            draw,
+        }
+    }
+
+    /// The number of draws skipped so far, such as for a debug display.
+    #[inline]
+    #[must_use]
+    pub const fn total_skipped(&self) -> u32 {
+        self.total_skipped
+    }
+}
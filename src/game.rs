@@ -0,0 +1,98 @@
+//! A ready-made main loop.
+//!
+//! A game implements [`GbaGame`] and hands itself to [`run`], which takes
+//! care of the timing: it halts until vblank, has the game draw while video
+//! memory is safe to write, then reads the keys and updates while the next
+//! frame is on screen. Updates happen at a fixed rate of one per vblank, using
+//! [`FrameSkip`] to catch up when a frame runs long.
+//!
+//! The crate doesn't own the interrupt handler, so the game's handler must
+//! acknowledge vblank by calling [`IrqLatch::record`] and
+//! [`FrameCounter::tick`], and `IME` must be on:
+//!
+//! ```no_run
+//! # use gba_cell::prelude::*;
+//! # use gba_cell::game;
+//! static LATCH: IrqLatch = IrqLatch::new();
+//! static FRAMES: FrameCounter = FrameCounter::new();
+//!
+//! // Called from the IRQ handler with the acknowledged `IF` bits.
+//! fn on_irq(bits: u16) {
+//!     if bits & 1 != 0 {
+//!         FRAMES.tick();
+//!     }
+//!     LATCH.record(bits);
+//! }
+//!
+//! struct Game {
+//!     x: i32,
+//! }
+//! impl GbaGame for Game {
+//!     fn update(&mut self, keys: &Keys) {
+//!         if keys.right() {
+//!             self.x += 1;
+//!         }
+//!     }
+//!     fn draw(&mut self) {
+//!         // Write `self.x` to OAM.
+//!     }
+//! }
+//!
+//! game::run(Game { x: 0 }, &LATCH, &FRAMES);
+//! ```
+
+use crate::display::{self, VBLANK_IRQ};
+use crate::frameskip::FrameSkip;
+use crate::keys::Keys;
+use crate::power::{halt_until, IrqLatch};
+use crate::schedule::FrameCounter;
+
+/// A game that [`run`] can drive.
+pub trait GbaGame {
+    /// How many draws in a row may be skipped to keep updates at one per
+    /// frame, as for [`FrameSkip::new`].
+    ///
+    /// The default of 0 draws every frame, so the game slows down when it
+    /// runs long instead.
+    const MAX_SKIP: u8 = 0;
+
+    /// Advances the game by one frame, given the keys held.
+    ///
+    /// This runs while the frame is being drawn, so it shouldn't write video
+    /// memory. Anything [`draw`](Self::draw) needs should be kept in `self`.
+    fn update(&mut self, keys: &Keys);
+
+    /// Copies the game's state into video memory.
+    ///
+    /// This runs at the start of vblank, so it should be short: copying OAM
+    /// and changing a few palette entries or scroll registers is fine, but
+    /// work that can be done ahead belongs in [`update`](Self::update).
+    fn draw(&mut self);
+}
+
+/// Runs `game` forever, with one update per vblank.
+///
+/// This turns on the vblank interrupt, then loops:
+/// 1. Halts until the next vblank, with [`halt_until`].
+/// 2. Calls [`draw`](GbaGame::draw), unless the frame is being skipped.
+/// 3. Reads the keys, and calls [`update`](GbaGame::update) once for each
+///    vblank since the last pass of the loop (up to `MAX_SKIP + 1`).
+///
+/// The IRQ handler must call [`FrameCounter::tick`] on `frames` and
+/// [`IrqLatch::record`] on `latch` for vblank, and `IME` must be on, or this
+/// never gets past the first halt.
+pub fn run<G: GbaGame>(mut game: G, latch: &IrqLatch, frames: &FrameCounter) -> ! {
+    display::enable_vblank_irq();
+    let mut skip = FrameSkip::new(G::MAX_SKIP);
+    loop {
+        halt_until(latch, VBLANK_IRQ);
+        let plan = skip.begin(frames.get());
+        if plan.draw {
+            game.draw();
+        }
+        let keys = Keys::read();
+        for _ in 0..plan.updates {
+            game.update(&keys);
+        }
+    }
+}
@@ -0,0 +1,144 @@
+//! Checksums for validating save data and assets.
+//!
+//! On the GBA the inner loops are placed in IWRAM and compiled as ARM code,
+//! which is several times faster than running Thumb code from ROM. The CRC32
+//! lookup table stays in ROM, since it's only read. Turning off the
+//! `iwram_hash` feature leaves the loops in ROM, to save IWRAM.
+
+/// The CRC32 (IEEE 802.3, reflected) lookup table, built at compile time.
+static CRC32_TABLE: [u32; 256] = {
+    let mut table = [0_u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut c = i as u32;
+        let mut k = 0;
+        while k < 8 {
+            c = if c & 1 != 0 {
+                0xEDB8_8320 ^ (c >> 1)
+            } else {
+                c >> 1
+            };
+            k += 1;
+        }
+        table[i] = c;
+        i += 1;
+    }
+    table
+};
+
+/// Computes the CRC32 of some bytes.
+///
+/// This is the same CRC32 used by zip, PNG, and most other places, so results
+/// can be checked against host tools.
+#[inline]
+#[must_use]
+pub fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = Crc32::new();
+    crc.update(bytes);
+    crc.finish()
+}
+
+/// Computes the Adler-32 checksum of some bytes, as used by zlib.
+///
+/// This is faster than [`crc32`] but worse at catching errors in short data.
+#[inline]
+#[must_use]
+pub fn adler32(bytes: &[u8]) -> u32 {
+    let mut adler = Adler32::new();
+    adler.update(bytes);
+    adler.finish()
+}
+
+/// An in-progress CRC32, for data that arrives in pieces.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Crc32(u32);
+impl Crc32 {
+    /// Starts a new checksum.
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self(u32::MAX)
+    }
+
+    /// Adds more bytes to the checksum.
+    #[cfg_attr(
+        all(feature = "on_gba", feature = "iwram_hash", target_arch = "arm"),
+        link_section = ".iwram.crc32_update",
+        instruction_set(arm::a32)
+    )]
+    pub fn update(&mut self, bytes: &[u8]) {
+        let mut crc = self.0;
+        for &b in bytes {
+            crc = CRC32_TABLE[usize::from((crc as u8) ^ b)] ^ (crc >> 8);
+        }
+        self.0 = crc;
+    }
+
+    /// The checksum of all the bytes given so far.
+    #[inline]
+    #[must_use]
+    pub const fn finish(&self) -> u32 {
+        !self.0
+    }
+}
+
+impl Default for Crc32 {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// An in-progress Adler-32, for data that arrives in pieces.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Adler32 {
+    a: u32,
+    b: u32,
+}
+impl Adler32 {
+    const MOD: u32 = 65521;
+    /// The most bytes that can be summed before `b` could overflow.
+    const CHUNK: usize = 5552;
+
+    /// Starts a new checksum.
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { a: 1, b: 0 }
+    }
+
+    /// Adds more bytes to the checksum.
+    #[cfg_attr(
+        all(feature = "on_gba", feature = "iwram_hash", target_arch = "arm"),
+        link_section = ".iwram.adler32_update",
+        instruction_set(arm::a32)
+    )]
+    pub fn update(&mut self, bytes: &[u8]) {
+        let Self { mut a, mut b } = *self;
+        // The modulo needs a (slow, software) division, so it's only done
+        // once per chunk rather than once per byte.
+        for chunk in bytes.chunks(Self::CHUNK) {
+            for &byte in chunk {
+                a += u32::from(byte);
+                b += a;
+            }
+            a %= Self::MOD;
+            b %= Self::MOD;
+        }
+        *self = Self { a, b };
+    }
+
+    /// The checksum of all the bytes given so far.
+    #[inline]
+    #[must_use]
+    pub const fn finish(&self) -> u32 {
+        (self.b << 16) | self.a
+    }
+}
+
+impl Default for Adler32 {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
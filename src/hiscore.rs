@@ -0,0 +1,229 @@
+//! High score tables, with initials entry and checked save data.
+//!
+//! A [`HiScores`] keeps the best `N` scores in order. When a new score
+//! [`qualifies`](HiScores::qualifies), an [`InitialsEntry`] turns key presses
+//! into three letters, and the table is then written to save memory with
+//! [`save`](HiScores::save). [`load`](HiScores::load) checks a CRC32, so
+//! blank or corrupted save data is detected instead of showing garbage.
+
+use crate::hash::Crc32;
+use crate::keys::{Key, Keys};
+
+/// One line of a high score table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Score {
+    /// The score.
+    pub score: u32,
+    /// The player's initials, as ASCII.
+    pub initials: [u8; 3],
+}
+impl Score {
+    /// Makes a score.
+    #[inline]
+    #[must_use]
+    pub const fn new(score: u32, initials: [u8; 3]) -> Self {
+        Self { score, initials }
+    }
+}
+
+/// The best `N` scores, highest first.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct HiScores<const N: usize> {
+    scores: [Score; N],
+    len: usize,
+}
+impl<const N: usize> HiScores<N> {
+    /// The number of bytes [`save`](Self::save) writes: a length, 8 bytes per
+    /// score, and a CRC32.
+    pub const SAVE_LEN: usize = 4 + N * 8 + 4;
+
+    /// Makes an empty table.
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            scores: [Score::new(0, [0; 3]); N],
+            len: 0,
+        }
+    }
+
+    /// The scores, highest first.
+    #[inline]
+    #[must_use]
+    pub fn scores(&self) -> &[Score] {
+        &self.scores[..self.len]
+    }
+
+    /// The number of scores in the table.
+    #[inline]
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// If the table has no scores.
+    #[inline]
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Where a score would be placed, or `None` if it doesn't make the table.
+    ///
+    /// A score that ties one already in the table goes below it.
+    #[inline]
+    #[must_use]
+    pub fn qualifies(&self, score: u32) -> Option<usize> {
+        let rank = self.scores().partition_point(|s| s.score >= score);
+        (rank < N).then_some(rank)
+    }
+
+    /// Adds a score, pushing the lowest one off a full table, and returns
+    /// where it was placed.
+    ///
+    /// If the score doesn't make the table, it's given back as an error.
+    pub fn insert(&mut self, score: Score) -> Result<usize, Score> {
+        let Some(rank) = self.qualifies(score.score) else {
+            return Err(score);
+        };
+        if self.len < N {
+            self.len += 1;
+        }
+        self.scores[rank..self.len].rotate_right(1);
+        self.scores[rank] = score;
+        Ok(rank)
+    }
+
+    /// Removes every score.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+
+    /// Writes the table into `out`, returning the number of bytes written
+    /// ([`SAVE_LEN`](Self::SAVE_LEN)), or `None` if `out` is too small.
+    pub fn save(&self, out: &mut [u8]) -> Option<usize> {
+        let out = out.get_mut(..Self::SAVE_LEN)?;
+        out.fill(0);
+        out[..4].copy_from_slice(&(self.len as u32).to_le_bytes());
+        for (s, bytes) in self.scores().iter().zip(out[4..].chunks_exact_mut(8)) {
+            bytes[..4].copy_from_slice(&s.score.to_le_bytes());
+            bytes[4..7].copy_from_slice(&s.initials);
+        }
+        let (data, crc) = out.split_at_mut(Self::SAVE_LEN - 4);
+        let mut check = Crc32::new();
+        check.update(data);
+        crc.copy_from_slice(&check.finish().to_le_bytes());
+        Some(Self::SAVE_LEN)
+    }
+
+    /// Reads a table written by [`save`](Self::save).
+    ///
+    /// Returns `None` if the bytes are too short, the checksum doesn't match,
+    /// or the scores aren't a valid table.
+    pub fn load(bytes: &[u8]) -> Option<Self> {
+        let bytes = bytes.get(..Self::SAVE_LEN)?;
+        let (data, crc) = bytes.split_at(Self::SAVE_LEN - 4);
+        let mut check = Crc32::new();
+        check.update(data);
+        if crc != check.finish().to_le_bytes() {
+            return None;
+        }
+        let len = u32::from_le_bytes(data[..4].try_into().ok()?) as usize;
+        if len > N {
+            return None;
+        }
+        let mut table = Self::new();
+        for (s, bytes) in table.scores.iter_mut().zip(data[4..].chunks_exact(8)) {
+            s.score = u32::from_le_bytes(bytes[..4].try_into().ok()?);
+            s.initials.copy_from_slice(&bytes[4..7]);
+        }
+        table.len = len;
+        if table.scores().windows(2).any(|w| w[0].score < w[1].score) {
+            return None;
+        }
+        Some(table)
+    }
+}
+
+impl<const N: usize> Default for HiScores<N> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The letters that can be picked for initials, in the order up steps
+/// through them.
+const LETTERS: &[u8; 27] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ ";
+
+/// The input logic for entering three initials with the D-pad.
+///
+/// Up and down change the selected letter, left and right (or A and B) move
+/// between letters, and A on the last letter finishes. Drawing the letters
+/// and cursor is left to the game.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct InitialsEntry {
+    letters: [u8; 3],
+    cursor: u8,
+}
+impl InitialsEntry {
+    /// Starts entry at `AAA`, with the first letter selected.
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            letters: [0; 3],
+            cursor: 0,
+        }
+    }
+
+    /// The initials so far, as ASCII.
+    #[inline]
+    #[must_use]
+    pub const fn initials(&self) -> [u8; 3] {
+        [
+            LETTERS[self.letters[0] as usize],
+            LETTERS[self.letters[1] as usize],
+            LETTERS[self.letters[2] as usize],
+        ]
+    }
+
+    /// Which letter is selected, from 0 to 2.
+    #[inline]
+    #[must_use]
+    pub const fn cursor(&self) -> usize {
+        self.cursor as usize
+    }
+
+    /// Handles one frame's newly pressed keys, returning the initials once
+    /// they're confirmed.
+    pub fn update(&mut self, pressed: Keys) -> Option<[u8; 3]> {
+        let count = LETTERS.len() as u8;
+        let letter = &mut self.letters[self.cursor as usize];
+        if pressed.pressed(Key::Up) {
+            *letter = (*letter + 1) % count;
+        }
+        if pressed.pressed(Key::Down) {
+            *letter = (*letter + count - 1) % count;
+        }
+        if pressed.pressed(Key::Left) || pressed.pressed(Key::B) {
+            self.cursor = self.cursor.saturating_sub(1);
+        } else if pressed.pressed(Key::A) {
+            if self.cursor == 2 {
+                return Some(self.initials());
+            }
+            self.cursor += 1;
+        } else if pressed.pressed(Key::Right) {
+            self.cursor = (self.cursor + 1).min(2);
+        }
+        None
+    }
+}
+
+impl Default for InitialsEntry {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,390 @@
+//! Provides the [`Keys`] type, and [`KeyMap`] for remappable controls.
+//!
+//! The `KEYINPUT` register reports each key as `0` when pressed and `1` when
+//! released. [`Keys`] flips that once, when the register value is converted,
+//! so everywhere else a set bit means "pressed".
+
+use crate::GbaCellSafe;
+use core::fmt::{self, Debug};
+use core::ops::{BitAnd, BitOr, Not};
+
+/// One of the GBA's ten keys.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[repr(u16)]
+pub enum Key {
+    A = 1 << 0,
+    B = 1 << 1,
+    Select = 1 << 2,
+    Start = 1 << 3,
+    Right = 1 << 4,
+    Left = 1 << 5,
+    Up = 1 << 6,
+    Down = 1 << 7,
+    R = 1 << 8,
+    L = 1 << 9,
+}
+impl Key {
+    /// Every key, in bit order.
+    pub const ALL: [Key; 10] = [
+        Key::A,
+        Key::B,
+        Key::Select,
+        Key::Start,
+        Key::Right,
+        Key::Left,
+        Key::Up,
+        Key::Down,
+        Key::R,
+        Key::L,
+    ];
+
+    /// The key's bit within `KEYINPUT` (and within [`Keys`]).
+    #[inline]
+    #[must_use]
+    pub const fn bit(self) -> u16 {
+        self as u16
+    }
+}
+
+/// The set of keys pressed at some moment.
+///
+/// A set bit means the key is pressed, using the same bit positions as
+/// `KEYINPUT`.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[repr(transparent)]
+pub struct Keys(u16);
+impl Keys {
+    /// The bits of `KEYINPUT` that hold keys.
+    const MASK: u16 = 0b11_1111_1111;
+
+    /// No keys pressed.
+    pub const NONE: Self = Self(0);
+
+    /// Makes a set from bits where `1` means pressed.
+    ///
+    /// Bits beyond the ten keys are ignored.
+    #[inline]
+    #[must_use]
+    pub const fn from_bits(bits: u16) -> Self {
+        Self(bits & Self::MASK)
+    }
+
+    /// Makes a set from a raw `KEYINPUT` value, where `0` means pressed.
+    #[inline]
+    #[must_use]
+    pub const fn from_keyinput(raw: u16) -> Self {
+        Self(!raw & Self::MASK)
+    }
+
+    /// Reads the keys currently held from the `KEYINPUT` register.
+    #[inline]
+    #[must_use]
+    #[cfg(feature = "on_gba")]
+    pub fn read() -> Self {
+        // SAFETY: `KEYINPUT` is a read-only register with no side effects.
+        Self::from_keyinput(unsafe { crate::mmio::KEYINPUT.read_volatile() })
+    }
+
+    /// The bits of the set, where `1` means pressed.
+    #[inline]
+    #[must_use]
+    pub const fn bits(self) -> u16 {
+        self.0
+    }
+
+    /// Converts back to the `KEYINPUT` format, where `0` means pressed.
+    #[inline]
+    #[must_use]
+    pub const fn to_keyinput(self) -> u16 {
+        !self.0 & Self::MASK
+    }
+
+    /// If the given key is pressed.
+    #[inline]
+    #[must_use]
+    pub const fn pressed(self, key: Key) -> bool {
+        self.0 & key.bit() != 0
+    }
+
+    /// If every key in `other` is pressed, such as for a button combo.
+    #[inline]
+    #[must_use]
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// If no keys are pressed.
+    #[inline]
+    #[must_use]
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Keys pressed now that weren't pressed in `previous`.
+    #[inline]
+    #[must_use]
+    pub const fn newly_pressed(self, previous: Self) -> Self {
+        Self(self.0 & !previous.0)
+    }
+
+    /// Keys pressed in `previous` that aren't pressed now.
+    #[inline]
+    #[must_use]
+    pub const fn newly_released(self, previous: Self) -> Self {
+        Self(previous.0 & !self.0)
+    }
+
+    /// Iterates the pressed keys, in bit order.
+    #[inline]
+    pub fn iter(self) -> impl Iterator<Item = Key> {
+        Key::ALL.into_iter().filter(move |&k| self.pressed(k))
+    }
+
+    /// If A is pressed.
+    #[inline]
+    #[must_use]
+    pub const fn a(self) -> bool {
+        self.pressed(Key::A)
+    }
+    /// If B is pressed.
+    #[inline]
+    #[must_use]
+    pub const fn b(self) -> bool {
+        self.pressed(Key::B)
+    }
+    /// If Select is pressed.
+    #[inline]
+    #[must_use]
+    pub const fn select(self) -> bool {
+        self.pressed(Key::Select)
+    }
+    /// If Start is pressed.
+    #[inline]
+    #[must_use]
+    pub const fn start(self) -> bool {
+        self.pressed(Key::Start)
+    }
+    /// If Right is pressed.
+    #[inline]
+    #[must_use]
+    pub const fn right(self) -> bool {
+        self.pressed(Key::Right)
+    }
+    /// If Left is pressed.
+    #[inline]
+    #[must_use]
+    pub const fn left(self) -> bool {
+        self.pressed(Key::Left)
+    }
+    /// If Up is pressed.
+    #[inline]
+    #[must_use]
+    pub const fn up(self) -> bool {
+        self.pressed(Key::Up)
+    }
+    /// If Down is pressed.
+    #[inline]
+    #[must_use]
+    pub const fn down(self) -> bool {
+        self.pressed(Key::Down)
+    }
+    /// If R is pressed.
+    #[inline]
+    #[must_use]
+    pub const fn r(self) -> bool {
+        self.pressed(Key::R)
+    }
+    /// If L is pressed.
+    #[inline]
+    #[must_use]
+    pub const fn l(self) -> bool {
+        self.pressed(Key::L)
+    }
+}
+
+// SAFETY: `Keys` is a `repr(transparent)` newtype over `u16`.
+unsafe impl GbaCellSafe for Keys {}
+
+// SAFETY: `Key` is a fieldless `repr(u16)` enum, so it has no padding.
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::NoUninit for Key {}
+
+// SAFETY: A `Key` is a `u16` with exactly one of the ten key bits set.
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::CheckedBitPattern for Key {
+    type Bits = u16;
+
+    #[inline]
+    fn is_valid_bit_pattern(bits: &u16) -> bool {
+        *bits & Keys::MASK == *bits && bits.is_power_of_two()
+    }
+}
+
+// SAFETY: `Keys` is a `repr(transparent)` newtype over `u16`, and zero is
+// `Keys::NONE`.
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Zeroable for Keys {}
+
+// SAFETY: `Keys` is a `repr(transparent)` newtype over `u16`.
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::NoUninit for Keys {}
+
+// Only the ten key bits may be set, so bytes that came from a raw `KEYINPUT`
+// value (or anything else with the upper bits set) fail the check instead of
+// making a `Keys` that compares unequal to the same keys.
+//
+// SAFETY: `Keys` is a `repr(transparent)` newtype over `u16`.
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::CheckedBitPattern for Keys {
+    type Bits = u16;
+
+    #[inline]
+    fn is_valid_bit_pattern(bits: &u16) -> bool {
+        *bits & !Self::MASK == 0
+    }
+}
+
+impl Debug for Keys {
+    /// Lists the names of the pressed keys, such as `Keys[A, Start]`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Keys")?;
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl From<Key> for Keys {
+    #[inline]
+    fn from(key: Key) -> Self {
+        Self(key.bit())
+    }
+}
+
+impl BitOr for Keys {
+    type Output = Self;
+    #[inline]
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+impl BitOr<Key> for Keys {
+    type Output = Self;
+    #[inline]
+    fn bitor(self, rhs: Key) -> Self {
+        Self(self.0 | rhs.bit())
+    }
+}
+impl BitOr for Key {
+    type Output = Keys;
+    #[inline]
+    fn bitor(self, rhs: Self) -> Keys {
+        Keys(self.bit() | rhs.bit())
+    }
+}
+impl BitAnd for Keys {
+    type Output = Self;
+    #[inline]
+    fn bitand(self, rhs: Self) -> Self {
+        Self(self.0 & rhs.0)
+    }
+}
+impl Not for Keys {
+    type Output = Self;
+    #[inline]
+    fn not(self) -> Self {
+        Self(!self.0 & Self::MASK)
+    }
+}
+
+/// A button remapping, applied to the keys read from the hardware before the
+/// game sees them, for configurable controls.
+///
+/// Each physical key produces one logical key. Several physical keys can
+/// produce the same logical key, and a logical key nothing maps to can't be
+/// pressed. The map is ten bytes, from [`to_bytes`](Self::to_bytes), for
+/// storing in save data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyMap([u8; 10]);
+impl KeyMap {
+    /// Every key produces itself.
+    pub const IDENTITY: Self = Self([0, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
+
+    /// A and B are swapped.
+    pub const SWAP_AB: Self = Self::IDENTITY.swapped(Key::A, Key::B);
+
+    /// The logical key that a physical key produces.
+    #[inline]
+    #[must_use]
+    pub const fn get(&self, physical: Key) -> Key {
+        Key::ALL[self.0[physical.bit().trailing_zeros() as usize] as usize]
+    }
+
+    /// Makes `physical` produce `logical`.
+    #[inline]
+    pub fn set(&mut self, physical: Key, logical: Key) {
+        self.0[physical.bit().trailing_zeros() as usize] = logical.bit().trailing_zeros() as u8;
+    }
+
+    /// The same map, but with what `a` and `b` produce swapped.
+    #[inline]
+    #[must_use]
+    pub const fn swapped(self, a: Key, b: Key) -> Self {
+        let mut map = self.0;
+        let (a, b) = (
+            a.bit().trailing_zeros() as usize,
+            b.bit().trailing_zeros() as usize,
+        );
+        (map[a], map[b]) = (map[b], map[a]);
+        Self(map)
+    }
+
+    /// Converts physical keys to logical keys.
+    #[must_use]
+    pub const fn apply(&self, physical: Keys) -> Keys {
+        let mut logical = 0;
+        let mut i = 0;
+        while i < 10 {
+            if physical.0 & (1 << i) != 0 {
+                logical |= 1 << self.0[i];
+            }
+            i += 1;
+        }
+        Keys(logical)
+    }
+
+    /// Reads the keys currently held from the `KEYINPUT` register, and
+    /// remaps them.
+    #[inline]
+    #[must_use]
+    #[cfg(feature = "on_gba")]
+    pub fn read(&self) -> Keys {
+        self.apply(Keys::read())
+    }
+
+    /// The logical key index (in [`Key::ALL`]) for each physical key.
+    #[inline]
+    #[must_use]
+    pub const fn to_bytes(&self) -> [u8; 10] {
+        self.0
+    }
+
+    /// Makes a map from [`to_bytes`](Self::to_bytes) output, or `None` if a
+    /// byte isn't a key index, such as when save data is blank or corrupted.
+    #[must_use]
+    pub const fn from_bytes(bytes: [u8; 10]) -> Option<Self> {
+        let mut i = 0;
+        while i < 10 {
+            if bytes[i] >= 10 {
+                return None;
+            }
+            i += 1;
+        }
+        Some(Self(bytes))
+    }
+}
+
+impl Default for KeyMap {
+    #[inline]
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
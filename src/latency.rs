@@ -0,0 +1,117 @@
+//! Measuring interrupt latency with a hardware timer.
+//!
+//! A [`LatencyProbe`] runs a timer at the full CPU clock and has it raise an
+//! interrupt each time it overflows. The timer keeps counting up from its
+//! reload value after the overflow, so reading it at the start of the IRQ
+//! handler gives the exact number of cycles between the interrupt being
+//! requested and the handler running. This is the number that matters when
+//! tuning nested interrupts (for example, to keep audio DMA refills on time).
+
+use crate::{mmio, GbaCell};
+
+/// Tracks the latency of one timer's interrupt, in CPU cycles.
+#[derive(Debug)]
+pub struct LatencyProbe {
+    timer: usize,
+    reload: GbaCell<u16>,
+    last: GbaCell<u16>,
+    worst: GbaCell<u16>,
+    samples: GbaCell<u16>,
+}
+impl LatencyProbe {
+    /// Constructs a probe that will use the given timer (0 to 3).
+    ///
+    /// ## Panics
+    /// * If `timer` is more than 3.
+    #[inline]
+    #[must_use]
+    #[cfg_attr(feature = "track_caller", track_caller)]
+    pub const fn new(timer: usize) -> Self {
+        assert!(timer < 4, "The GBA only has timers 0 to 3.");
+        Self {
+            timer,
+            reload: GbaCell::new(0),
+            last: GbaCell::new(0),
+            worst: GbaCell::new(0),
+            samples: GbaCell::new(0),
+        }
+    }
+
+    #[inline]
+    fn counter(&self) -> *mut u16 {
+        mmio::TM0CNT_L.wrapping_add(self.timer * 2)
+    }
+
+    #[inline]
+    fn control(&self) -> *mut u16 {
+        mmio::TM0CNT_H.wrapping_add(self.timer * 2)
+    }
+
+    /// Starts the timer, raising an interrupt every `period` cycles.
+    ///
+    /// The timer's bit must also be set in `IE`, and the IRQ handler must call
+    /// [`sample`](Self::sample) when it fires. Latencies of `period` cycles or
+    /// more can't be told apart from shorter ones, so the period should be
+    /// comfortably longer than the worst latency expected.
+    pub fn start(&self, period: u16) {
+        let reload = 0_u16.wrapping_sub(period);
+        self.reload.write(reload);
+        // SAFETY: the timer registers are plain read/write registers, and
+        // `self.timer` is a valid timer index.
+        unsafe {
+            self.control().write_volatile(0);
+            self.counter().write_volatile(reload);
+            // Full clock speed, IRQ on overflow, enabled.
+            self.control().write_volatile((1 << 6) | (1 << 7));
+        }
+    }
+
+    /// Stops the timer.
+    #[inline]
+    pub fn stop(&self) {
+        // SAFETY: see `start`.
+        unsafe { self.control().write_volatile(0) };
+    }
+
+    /// Records a sample. Call this as early as possible in the IRQ handler.
+    #[inline]
+    pub fn sample(&self) {
+        // SAFETY: see `start`.
+        let now = unsafe { self.counter().read_volatile() };
+        let latency = now.wrapping_sub(self.reload.read());
+        self.last.write(latency);
+        if latency > self.worst.read() {
+            self.worst.write(latency);
+        }
+        self.samples.write(self.samples.read().saturating_add(1));
+    }
+
+    /// The latency of the most recent sample.
+    #[inline]
+    #[must_use]
+    pub fn last(&self) -> u16 {
+        self.last.read()
+    }
+
+    /// The worst latency seen since the last [`reset`](Self::reset).
+    #[inline]
+    #[must_use]
+    pub fn worst(&self) -> u16 {
+        self.worst.read()
+    }
+
+    /// The number of samples taken since the last reset, saturating.
+    #[inline]
+    #[must_use]
+    pub fn samples(&self) -> u16 {
+        self.samples.read()
+    }
+
+    /// Clears the recorded samples.
+    #[inline]
+    pub fn reset(&self) {
+        self.last.write(0);
+        self.worst.write(0);
+        self.samples.write(0);
+    }
+}
@@ -6,29 +6,187 @@
 //! Nintendo Gameboy Advanced. Use on any other platform may lead to Undefined
 //! Behaviour.**
 
+#![no_std]
+
+#[cfg(feature = "std")]
+extern crate std;
+
 use core::fmt::Debug;
+use core::num::{NonZeroI16, NonZeroI32, NonZeroI8, NonZeroU16, NonZeroU32, NonZeroU8};
+use core::ptr::NonNull;
+
+pub mod adpcm;
+pub mod arena;
+mod arm_fn;
+pub mod assets;
+#[cfg(feature = "on_gba")]
+pub mod audio;
+#[cfg(feature = "on_gba")]
+pub mod cart;
+pub mod collision;
+pub mod color;
+#[cfg(feature = "on_gba")]
+pub mod diagnostics;
+pub mod digits;
+pub mod direction;
+pub mod dirty;
+#[cfg(feature = "on_gba")]
+pub mod display;
+#[cfg(feature = "on_gba")]
+pub mod effects;
+#[cfg(feature = "on_gba")]
+pub mod event;
+#[cfg(feature = "on_gba")]
+pub mod executor;
+#[cfg(feature = "on_gba")]
+pub mod fn_cell;
+pub mod frameskip;
+#[cfg(feature = "on_gba")]
+pub mod game;
+pub mod hash;
+pub mod hiscore;
+pub mod keys;
+#[cfg(feature = "on_gba")]
+pub mod latency;
+pub mod line;
+pub mod lz77;
+#[cfg(feature = "on_gba")]
+mod mmio;
+pub mod noise;
+pub mod obj;
+#[cfg(feature = "on_gba")]
+pub mod patch;
+pub mod path;
+pub mod pool;
+#[cfg(feature = "on_gba")]
+pub mod power;
+pub mod prelude;
+#[cfg(feature = "on_gba")]
+pub mod queue;
+pub mod ratio;
+pub mod replay;
+#[cfg(feature = "on_gba")]
+pub mod reset;
+pub mod scene;
+#[cfg(feature = "on_gba")]
+pub mod schedule;
+pub mod sfx;
+pub mod snapshot;
+#[cfg(feature = "on_gba")]
+pub mod streaming;
+#[cfg(feature = "on_gba")]
+pub mod switches;
+pub mod tiles;
+pub mod timer;
+#[cfg(feature = "on_gba")]
+pub mod transaction;
+#[cfg(feature = "on_gba")]
+pub mod watchdog;
+
+#[cfg(feature = "on_gba")]
+pub use fn_cell::GbaFnCell;
+#[cfg(feature = "on_gba")]
+pub use queue::GbaQueue;
+#[cfg(feature = "on_gba")]
+pub use transaction::transaction;
 
 /// Marker trait bound for the methods of [`GbaCell`].
 ///
 /// When a type implements this trait it indicates that the type can be
 /// atomically loaded/stored using a single volatile access.
 ///
+/// The trait is implemented for the primitive types that qualify, so using
+/// any other type in a [`GbaCell`] is an ordinary "trait bound not satisfied"
+/// compile error that names the type. Your own types can implement it too.
+///
+/// `f32` is included for code that uses floats off the hot path. The GBA has no
+/// FPU, so float math is done in software and is slow, but loading or storing
+/// an `f32` is still a single 32-bit access.
+///
 /// ## Safety
 /// The type must fit in a single register, and have an alignment equal to its
 /// size. Generally that means it should be one of:
 ///
-/// * an 8, 16, or 32 bit integer
+/// * an 8, 16, or 32 bit integer, or an `f32`
 /// * a function pointer
 /// * a data pointer to a sized type
 /// * an optional non-null pointer (to function or sized data)
 /// * a `repr(transparent)` newtype over one of the above
-/// 
-/// Note that while the trait requirements are enforcable at the trait level,
-/// the size & alignment requirements are enforced using `const` assertions
-/// wherever a [`GbaCell`] is used.
-pub unsafe trait GbaCellSafe: Copy {}
+///
+/// As a backstop, the size & alignment requirements are also checked by
+/// [`GbaCellSafe::OK`], which every [`GbaCell`] method evaluates.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` can't be stored in a `GbaCell`",
+    label = "not known to be GbaCell-safe",
+    note = "a `GbaCell` value must have a size & align of 1, 2, or 4, such as an integer or a pointer"
+)]
+pub unsafe trait GbaCellSafe: Copy {
+    /// Checks the size & alignment requirements at compile time.
+    ///
+    /// The size must equal the alignment, and be 1, 2, or 4 bytes. For any
+    /// other type evaluating this constant is a compile error, which names the
+    /// type as `<T as GbaCellSafe>::OK`.
+    const OK: () = {
+        let size = core::mem::size_of::<Self>();
+        let align = core::mem::align_of::<Self>();
+        match (size, align) {
+            (1, 1) | (2, 2) | (4, 4) => {}
+            _ => {
+                panic!("Provided type cannot be made GbaCell-safe! Expected a size & align of 1, 2, or 4.")
+            }
+        }
+    };
+}
+
+macro_rules! impl_gba_cell_safe {
+    ($($t:ty),* $(,)?) => {
+        $(
+            unsafe impl GbaCellSafe for $t {}
+        )*
+    };
+}
+impl_gba_cell_safe!(u8, i8, u16, i16, u32, i32, bool, char);
+impl_gba_cell_safe!(f32);
+impl_gba_cell_safe!(NonZeroU8, NonZeroI8, NonZeroU16, NonZeroI16, NonZeroU32, NonZeroI32);
+impl_gba_cell_safe!(Option<NonZeroU8>, Option<NonZeroI8>, Option<NonZeroU16>);
+impl_gba_cell_safe!(Option<NonZeroI16>, Option<NonZeroU32>, Option<NonZeroI32>);
 
-unsafe impl<T> GbaCellSafe for T where T: Copy {}
+// Pointer-sized types are 4 bytes on the GBA, but can be wider when the crate
+// is built for host tests. They skip the size check there, so that cells of
+// pointers (such as `GbaFnCell`) still build.
+macro_rules! impl_gba_cell_safe_ptr {
+    ($([$($param:ident),*] $t:ty),* $(,)?) => {
+        $(
+            unsafe impl<$($param),*> GbaCellSafe for $t {
+                #[cfg(not(target_pointer_width = "32"))]
+                const OK: () = ();
+            }
+        )*
+    };
+}
+impl_gba_cell_safe_ptr!([] usize, [] isize);
+impl_gba_cell_safe_ptr!([T] *const T, [T] *mut T, [T] &T, [T] Option<&T>);
+impl_gba_cell_safe_ptr!([T] NonNull<T>, [T] Option<NonNull<T>>);
+
+macro_rules! impl_gba_cell_safe_fn {
+    ($($arg:ident),*) => {
+        impl_gba_cell_safe_ptr!(
+            [R $(, $arg)*] fn($($arg),*) -> R,
+            [R $(, $arg)*] Option<fn($($arg),*) -> R>,
+            [R $(, $arg)*] unsafe fn($($arg),*) -> R,
+            [R $(, $arg)*] Option<unsafe fn($($arg),*) -> R>,
+            [R $(, $arg)*] extern "C" fn($($arg),*) -> R,
+            [R $(, $arg)*] Option<extern "C" fn($($arg),*) -> R>,
+            [R $(, $arg)*] unsafe extern "C" fn($($arg),*) -> R,
+            [R $(, $arg)*] Option<unsafe extern "C" fn($($arg),*) -> R>,
+        );
+    };
+}
+impl_gba_cell_safe_fn!();
+impl_gba_cell_safe_fn!(A);
+impl_gba_cell_safe_fn!(A, B);
+impl_gba_cell_safe_fn!(A, B, C);
+impl_gba_cell_safe_fn!(A, B, C, D);
 
 /// A "cell" type suitable to hold a global on the GBA.
 #[repr(transparent)]
@@ -49,7 +207,6 @@ where
     T: GbaCellSafe + Default,
 {
     #[inline]
-    #[must_use]
     fn default() -> Self {
         Self::new(T::default())
     }
@@ -60,7 +217,6 @@ where
     T: GbaCellSafe + Default,
 {
     #[inline]
-    #[must_use]
     fn clone(&self) -> Self {
         Self::new(self.read())
     }
@@ -69,27 +225,19 @@ where
 #[cfg(feature = "on_gba")]
 unsafe impl<T> Sync for GbaCell<T> {}
 
+// SAFETY: A cell of all zeroes holds a zeroed `T`.
+#[cfg(feature = "bytemuck")]
+unsafe impl<T> bytemuck::Zeroable for GbaCell<T> where T: bytemuck::Zeroable {}
+
 impl<T> GbaCell<T>
 where
     T: GbaCellSafe,
 {
-    /// Helper to assert the size & alignment requirements of the wrapped value
-    /// at compile time. 
-    const _ASSERT_GBACELL_SAFE: () = {
-        let size = core::mem::size_of::<T>();
-        let align = core::mem::align_of::<T>();
-        match (size, align) {
-            (1, 1) | (2, 2) | (4, 4) => {}
-            _ => {
-                panic!("Provided type cannot be made GbaCell-safe! Expected a size & align of 1, 2, or 4.")
-            }
-        }
-    };
-
     /// Constructs a new cell with the value given
     #[inline]
     #[must_use]
     pub const fn new(t: T) -> Self {
+        let () = T::OK;
         Self(core::cell::UnsafeCell::new(t))
     }
 
@@ -99,8 +247,9 @@ where
     #[cfg(feature = "on_gba")]
     #[cfg_attr(feature = "track_caller", track_caller)]
     pub fn read(&self) -> T {
+        let () = T::OK;
         // SAFETY: Guranteed to meet the size & alignment requirements of the
-        // GBA's single-instruction reads because of Self::_ASSERT_GBACELL_SAFE.
+        // GBA's single-instruction reads because of `T::OK`.
         unsafe { self.0.get().read_volatile() }
     }
 
@@ -109,8 +258,9 @@ where
     #[cfg(feature = "on_gba")]
     #[cfg_attr(feature = "track_caller", track_caller)]
     pub fn write(&self, t: T) {
+        let () = T::OK;
         // SAFETY: Guranteed to meet the size & alignment requirements of the
-        // GBA's single-instruction reads because of Self::_ASSERT_GBACELL_SAFE.
+        // GBA's single-instruction writes because of `T::OK`.
         unsafe { self.0.get().write_volatile(t) }
     }
 }
@@ -0,0 +1,138 @@
+//! Integer line and circle rasterizing.
+//!
+//! These step through grid positions using only adds and compares, so they
+//! work equally well for plotting pixels and for walking tiles. For example,
+//! a line of sight check is `Line::new(from, to).all(|p| !is_wall(p))`.
+
+/// The points on a line between two grid positions, using Bresenham's
+/// algorithm.
+///
+/// Both ends are included, starting from the first.
+#[derive(Debug, Clone)]
+pub struct Line {
+    x: i32,
+    y: i32,
+    end_x: i32,
+    end_y: i32,
+    dx: i32,
+    dy: i32,
+    step_x: i32,
+    step_y: i32,
+    err: i32,
+    done: bool,
+}
+impl Line {
+    /// Makes a line from `start` to `end`, as `(x, y)` pairs.
+    #[inline]
+    #[must_use]
+    pub const fn new(start: (i32, i32), end: (i32, i32)) -> Self {
+        let dx = (end.0 - start.0).abs();
+        let dy = -(end.1 - start.1).abs();
+        Self {
+            x: start.0,
+            y: start.1,
+            end_x: end.0,
+            end_y: end.1,
+            dx,
+            dy,
+            step_x: if start.0 < end.0 { 1 } else { -1 },
+            step_y: if start.1 < end.1 { 1 } else { -1 },
+            err: dx + dy,
+            done: false,
+        }
+    }
+}
+impl Iterator for Line {
+    type Item = (i32, i32);
+
+    #[inline]
+    fn next(&mut self) -> Option<(i32, i32)> {
+        if self.done {
+            return None;
+        }
+        let point = (self.x, self.y);
+        if self.x == self.end_x && self.y == self.end_y {
+            self.done = true;
+            return Some(point);
+        }
+        let e2 = 2 * self.err;
+        if e2 >= self.dy {
+            self.err += self.dy;
+            self.x += self.step_x;
+        }
+        if e2 <= self.dx {
+            self.err += self.dx;
+            self.y += self.step_y;
+        }
+        Some(point)
+    }
+}
+
+/// The points on the outline of a circle, using the midpoint algorithm.
+///
+/// The points come out eight at a time, one in each octant, so they aren't in
+/// order around the circle. Points where two octants meet can come out twice.
+#[derive(Debug, Clone)]
+pub struct Circle {
+    center_x: i32,
+    center_y: i32,
+    x: i32,
+    y: i32,
+    err: i32,
+    octant: u8,
+}
+impl Circle {
+    /// Makes a circle around `center` with the given radius.
+    ///
+    /// A radius of 0 is just the center point, and a negative radius has no
+    /// points.
+    #[inline]
+    #[must_use]
+    pub const fn new(center: (i32, i32), radius: i32) -> Self {
+        Self {
+            center_x: center.0,
+            center_y: center.1,
+            x: radius,
+            y: 0,
+            err: 1 - radius,
+            octant: 0,
+        }
+    }
+}
+impl Iterator for Circle {
+    type Item = (i32, i32);
+
+    #[inline]
+    fn next(&mut self) -> Option<(i32, i32)> {
+        if self.y > self.x {
+            return None;
+        }
+        let (x, y) = (self.x, self.y);
+        if x == 0 {
+            self.y = 1;
+            return Some((self.center_x, self.center_y));
+        }
+        let (ox, oy) = match self.octant {
+            0 => (x, y),
+            1 => (y, x),
+            2 => (-y, x),
+            3 => (-x, y),
+            4 => (-x, -y),
+            5 => (-y, -x),
+            6 => (y, -x),
+            _ => (x, -y),
+        };
+        self.octant += 1;
+        if self.octant == 8 {
+            self.octant = 0;
+            self.y += 1;
+            if self.err < 0 {
+                self.err += 2 * self.y + 1;
+            } else {
+                self.x -= 1;
+                self.err += 2 * (self.y - self.x) + 1;
+            }
+        }
+        Some((self.center_x + ox, self.center_y + oy))
+    }
+}
@@ -0,0 +1,300 @@
+//! LZ77 compression in the format used by the GBA BIOS.
+//!
+//! Data produced by [`compress`] (or by other tools targeting the BIOS, such
+//! as `grit`) can be unpacked with [`decompress`], or with the BIOS
+//! `LZ77UnComp` functions.
+//!
+//! The format is a 4 byte header, with `0x10` in the low byte and the
+//! decompressed size in the upper 24 bits, followed by blocks of one flag byte
+//! and then 8 entries. Flag bits are read from the most significant end. A `0`
+//! bit means the entry is one literal byte, and a `1` bit means the entry is a
+//! two byte back-reference: the top 4 bits hold the copy length minus 3, and
+//! the other 12 bits hold the distance back minus 1.
+//!
+//! Unlike the BIOS version, [`decompress`] can write to any byte slice, so it
+//! has none of the alignment rules of the VRAM-safe BIOS function. It can't be
+//! used to write *directly* into VRAM though, since VRAM ignores byte writes.
+//! An [`Lz77Stream`] can, and can also stop and resume partway through, so a
+//! large asset can be unpacked over several frames.
+
+/// The type tag found in the low byte of an LZ77 header.
+pub const LZ77_TAG: u8 = 0x10;
+
+/// The ways that decompression can fail.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Lz77Error {
+    /// The header is missing, or doesn't have the LZ77 tag.
+    BadHeader,
+    /// The output buffer is smaller than the size given in the header.
+    OutputTooSmall,
+    /// The compressed data ended before the output was complete.
+    Truncated,
+    /// A back-reference pointed before the start of the output.
+    BadDistance,
+}
+
+/// Reads the decompressed size out of the header of some LZ77 data.
+#[inline]
+pub fn decompressed_len(src: &[u8]) -> Result<usize, Lz77Error> {
+    match src {
+        [LZ77_TAG, a, b, c, ..] => Ok(u32::from_le_bytes([*a, *b, *c, 0]) as usize),
+        _ => Err(Lz77Error::BadHeader),
+    }
+}
+
+/// Decompresses LZ77 data into `dst`.
+///
+/// On success, returns the number of bytes written, which is the size given
+/// in the header. Any bytes in `dst` beyond that aren't touched.
+pub fn decompress(src: &[u8], dst: &mut [u8]) -> Result<usize, Lz77Error> {
+    Lz77Stream::new(src)?.decompress(dst, usize::MAX)
+}
+
+/// Somewhere an [`Lz77Stream`] can write bytes and read them back.
+trait Output {
+    fn byte_len(&self) -> usize;
+    fn get(&self, i: usize) -> u8;
+    fn set(&mut self, i: usize, b: u8);
+}
+
+impl Output for [u8] {
+    #[inline]
+    fn byte_len(&self) -> usize {
+        self.len()
+    }
+    #[inline]
+    fn get(&self, i: usize) -> u8 {
+        self[i]
+    }
+    #[inline]
+    fn set(&mut self, i: usize, b: u8) {
+        self[i] = b;
+    }
+}
+
+impl Output for [u16] {
+    #[inline]
+    fn byte_len(&self) -> usize {
+        self.len() * 2
+    }
+    #[inline]
+    fn get(&self, i: usize) -> u8 {
+        (self[i / 2] >> ((i & 1) * 8)) as u8
+    }
+    #[inline]
+    fn set(&mut self, i: usize, b: u8) {
+        let half = &mut self[i / 2];
+        let shift = (i & 1) * 8;
+        let new = (*half & !(0xFF << shift)) | (u16::from(b) << shift);
+        // A plain write could be narrowed to a byte write, which VRAM ignores.
+        // SAFETY: `half` is a valid, aligned `&mut u16`.
+        unsafe { (half as *mut u16).write_volatile(new) };
+    }
+}
+
+/// Decompresses LZ77 data a piece at a time.
+///
+/// Each call to [`decompress`](Self::decompress) or
+/// [`decompress_u16`](Self::decompress_u16) writes up to a given number of
+/// bytes and then stops, remembering where it was, so the work can be spread
+/// across frames. Back-references read earlier output, so every call for one
+/// stream must be given the same buffer.
+///
+/// After an error, the rest of the output is garbage and the stream shouldn't
+/// be used any more.
+///
+/// ```
+/// # use gba_cell::lz77::Lz77Stream;
+/// // `ABC`, then a back-reference for 9 more bytes.
+/// let packed = [0x10, 12, 0, 0, 0x10, b'A', b'B', b'C', 0x60, 0x02, 0, 0];
+/// let mut stream = Lz77Stream::new(&packed).unwrap();
+/// let mut out = [0; 12];
+/// assert_eq!(stream.decompress(&mut out, 5), Ok(5));
+/// assert_eq!(&out[..5], b"ABCAB");
+/// assert_eq!(stream.decompress(&mut out, 100), Ok(7));
+/// assert!(stream.is_done());
+/// assert_eq!(&out, b"ABCABCABCABC");
+/// ```
+#[derive(Debug, Clone)]
+pub struct Lz77Stream<'a> {
+    src: &'a [u8],
+    pos: usize,
+    len: usize,
+    out: usize,
+    flags: u8,
+    flag_bits: u8,
+    distance: usize,
+    copy_left: usize,
+}
+impl<'a> Lz77Stream<'a> {
+    /// Starts decompressing `src`, reading the size from its header.
+    #[inline]
+    pub fn new(src: &'a [u8]) -> Result<Self, Lz77Error> {
+        Ok(Self {
+            src,
+            pos: 4,
+            len: decompressed_len(src)?,
+            out: 0,
+            flags: 0,
+            flag_bits: 0,
+            distance: 0,
+            copy_left: 0,
+        })
+    }
+
+    /// The decompressed size, from the header.
+    #[inline]
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// If the decompressed size is 0.
+    #[inline]
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The number of bytes written so far.
+    #[inline]
+    #[must_use]
+    pub const fn written(&self) -> usize {
+        self.out
+    }
+
+    /// If every byte has been written.
+    #[inline]
+    #[must_use]
+    pub const fn is_done(&self) -> bool {
+        self.out >= self.len
+    }
+
+    /// Writes up to `limit` more bytes into `dst`, returning how many were
+    /// written.
+    ///
+    /// `dst` is the whole output, not just the part for this call, and must
+    /// hold at least [`len`](Self::len) bytes. Any bytes beyond that aren't
+    /// touched.
+    #[inline]
+    pub fn decompress(&mut self, dst: &mut [u8], limit: usize) -> Result<usize, Lz77Error> {
+        self.run(dst, limit)
+    }
+
+    /// Writes up to `limit` more bytes into `dst`, as little-endian pairs,
+    /// returning how many were written.
+    ///
+    /// This is for VRAM, which ignores byte writes: every write is a
+    /// volatile `u16` write, with the other half of the `u16` read back from
+    /// `dst`. `dst` must hold at least [`len`](Self::len) bytes, and a final
+    /// odd byte leaves the upper half of the last `u16` as it was.
+    #[inline]
+    pub fn decompress_u16(&mut self, dst: &mut [u16], limit: usize) -> Result<usize, Lz77Error> {
+        self.run(dst, limit)
+    }
+
+    #[inline]
+    fn next(&mut self) -> Result<u8, Lz77Error> {
+        let b = *self.src.get(self.pos).ok_or(Lz77Error::Truncated)?;
+        self.pos += 1;
+        Ok(b)
+    }
+
+    fn run<O: Output + ?Sized>(&mut self, dst: &mut O, limit: usize) -> Result<usize, Lz77Error> {
+        if dst.byte_len() < self.len {
+            return Err(Lz77Error::OutputTooSmall);
+        }
+        let start = self.out;
+        let end = self.len.min(start.saturating_add(limit));
+        while self.out < end {
+            if self.copy_left > 0 {
+                // The source and destination ranges can overlap, which
+                // repeats the most recent bytes, so this has to go one byte
+                // at a time.
+                dst.set(self.out, dst.get(self.out - self.distance));
+                self.out += 1;
+                self.copy_left -= 1;
+                continue;
+            }
+            if self.flag_bits == 0 {
+                self.flags = self.next()?;
+                self.flag_bits = 8;
+            }
+            self.flag_bits -= 1;
+            if self.flags & (1 << self.flag_bits) == 0 {
+                let b = self.next()?;
+                dst.set(self.out, b);
+                self.out += 1;
+            } else {
+                let hi = self.next()?;
+                let lo = self.next()?;
+                self.copy_left = usize::from(hi >> 4) + 3;
+                self.distance = ((usize::from(hi & 0xF) << 8) | usize::from(lo)) + 1;
+                if self.distance > self.out {
+                    return Err(Lz77Error::BadDistance);
+                }
+            }
+        }
+        Ok(self.out - start)
+    }
+}
+
+/// Compresses `data` into the BIOS LZ77 format.
+///
+/// Back-references never use a distance of 1, so that the output is also safe
+/// to give to the VRAM-safe BIOS function (which writes 2 bytes at a time).
+/// The output is padded to a multiple of 4 bytes, as the BIOS requires.
+///
+/// ## Panics
+/// * If `data` is 16 MiB or more, which doesn't fit in the header.
+#[cfg(feature = "std")]
+#[must_use]
+pub fn compress(data: &[u8]) -> std::vec::Vec<u8> {
+    const MIN_MATCH: usize = 3;
+    const MAX_MATCH: usize = 0xF + MIN_MATCH;
+    const MAX_DISTANCE: usize = 0x1000;
+
+    assert!(data.len() < (1 << 24), "LZ77 data must be less than 16 MiB");
+    let mut out = std::vec::Vec::with_capacity(4 + data.len() + data.len() / 8);
+    out.push(LZ77_TAG);
+    out.extend_from_slice(&(data.len() as u32).to_le_bytes()[..3]);
+
+    let mut pos = 0;
+    while pos < data.len() {
+        let flag_index = out.len();
+        out.push(0);
+        for bit in (0..8).rev() {
+            if pos >= data.len() {
+                break;
+            }
+            let max_len = MAX_MATCH.min(data.len() - pos);
+            let mut best = (0, 0);
+            for distance in 2..=MAX_DISTANCE.min(pos) {
+                let start = pos - distance;
+                let len = (0..max_len)
+                    .take_while(|&i| data[start + i] == data[pos + i])
+                    .count();
+                if len > best.0 {
+                    best = (len, distance);
+                    if len == max_len {
+                        break;
+                    }
+                }
+            }
+            let (len, distance) = best;
+            if len >= MIN_MATCH {
+                out[flag_index] |= 1 << bit;
+                let packed = ((len - MIN_MATCH) << 12) | (distance - 1);
+                out.extend_from_slice(&(packed as u16).to_be_bytes());
+                pos += len;
+            } else {
+                out.push(data[pos]);
+                pos += 1;
+            }
+        }
+    }
+    while out.len() % 4 != 0 {
+        out.push(0);
+    }
+    out
+}
@@ -0,0 +1,46 @@
+//! Addresses of the few hardware registers this crate touches directly.
+//!
+//! This module is only built with the `on_gba` feature, and so is every item
+//! that uses it. Dereferencing these anywhere but on a GBA is Undefined
+//! Behavior, so code that touches them mustn't be reachable in host builds.
+
+/// Display control. Bit 7 is forced blank.
+pub(crate) const DISPCNT: *mut u16 = 0x0400_0000 as *mut u16;
+/// Display status. Bit 0 is set during vblank (lines 160 to 226), and bits 8
+/// to 15 are the scanline the vcount interrupt matches.
+pub(crate) const DISPSTAT: *mut u16 = 0x0400_0004 as *mut u16;
+/// Key status. Bits are `0` while a key is pressed.
+pub(crate) const KEYINPUT: *const u16 = 0x0400_0130 as *const u16;
+/// Interrupt master enable. Only bit 0 is used.
+pub(crate) const IME: *mut u16 = 0x0400_0208 as *mut u16;
+/// Writing 0 halts the CPU until an enabled interrupt is requested.
+pub(crate) const HALTCNT: *mut u8 = 0x0400_0301 as *mut u8;
+/// Timer 0's counter (when read) or reload value (when written). Timers 1 to
+/// 3 follow at 4 byte steps.
+pub(crate) const TM0CNT_L: *mut u16 = 0x0400_0100 as *mut u16;
+/// Timer 0's control bits. Timers 1 to 3 follow at 4 byte steps.
+pub(crate) const TM0CNT_H: *mut u16 = 0x0400_0102 as *mut u16;
+/// The scanline being drawn, from 0 to 227.
+pub(crate) const VCOUNT: *const u16 = 0x0400_0006 as *const u16;
+/// Sound on/off. Bit 7 powers the whole sound system.
+pub(crate) const SOUNDCNT_X: *mut u16 = 0x0400_0084 as *mut u16;
+/// Sound output bias. Bits 1 to 9 are the bias level, normally `0x200`.
+pub(crate) const SOUNDBIAS: *mut u16 = 0x0400_0088 as *mut u16;
+/// Interrupt enable bits. Bit 12 is the keypad.
+pub(crate) const IE: *mut u16 = 0x0400_0200 as *mut u16;
+/// Interrupt request flags. Writing a `1` bit acknowledges that interrupt.
+pub(crate) const IF: *mut u16 = 0x0400_0202 as *mut u16;
+/// Keypad interrupt control: the key bits, then bit 14 to enable the
+/// interrupt and bit 15 to require all the keys rather than any.
+pub(crate) const KEYCNT: *mut u16 = 0x0400_0132 as *mut u16;
+/// Game pak wait state control. Bits 2 to 4 are the ROM's first and
+/// sequential access times, and bit 14 turns on the prefetch buffer.
+pub(crate) const WAITCNT: *mut u16 = 0x0400_0204 as *mut u16;
+/// DMA 3's source address.
+pub(crate) const DMA3SAD: *mut u32 = 0x0400_00D4 as *mut u32;
+/// DMA 3's destination address.
+pub(crate) const DMA3DAD: *mut u32 = 0x0400_00D8 as *mut u32;
+/// DMA 3's transfer count, where `0` means `0x10000`.
+pub(crate) const DMA3CNT_L: *mut u16 = 0x0400_00DC as *mut u16;
+/// DMA 3's control bits. Bit 15 is set until the transfer is done.
+pub(crate) const DMA3CNT_H: *mut u16 = 0x0400_00DE as *mut u16;
@@ -0,0 +1,69 @@
+//! Hash-based noise and shuffling.
+//!
+//! These use multiply-xor hashes, which are cheap on the GBA's CPU: a 32-bit
+//! multiply takes a few cycles, while division isn't available at all. None
+//! of this is suitable for anything security related.
+
+/// Mixes the bits of a 32-bit value, so that every input bit affects every
+/// output bit.
+///
+/// This is the "lowbias32" hash by Chris Wellons.
+#[inline]
+#[must_use]
+pub const fn hash32(mut x: u32) -> u32 {
+    x ^= x >> 16;
+    x = x.wrapping_mul(0x7FEB_352D);
+    x ^= x >> 15;
+    x = x.wrapping_mul(0x846C_A68B);
+    x ^= x >> 16;
+    x
+}
+
+/// A random-looking value for each grid position.
+///
+/// The same position and seed always give the same value, so this can
+/// decorate a map (which grass tile goes where, say) without storing
+/// anything.
+#[inline]
+#[must_use]
+pub const fn noise2d(x: i32, y: i32, seed: u32) -> u32 {
+    let h = hash32(seed ^ (x as u32).wrapping_mul(0x9E37_79B9));
+    hash32(h ^ (y as u32).wrapping_mul(0x85EB_CA6B))
+}
+
+/// Maps a random `u32` to the range `0..n` without dividing.
+///
+/// This takes the top 32 bits of `r * n`, which is very slightly biased for
+/// `n` that aren't a power of two, but not in any way a game can notice.
+#[inline]
+#[must_use]
+pub const fn below(r: u32, n: u32) -> u32 {
+    ((r as u64 * n as u64) >> 32) as u32
+}
+
+/// Shuffles `items` in place with a Fisher–Yates shuffle.
+///
+/// `next` is called once per item (less one) for a random `u32`, so any
+/// generator works, such as `|| rng.next_u32()`.
+pub fn shuffle<T, F>(items: &mut [T], mut next: F)
+where
+    F: FnMut() -> u32,
+{
+    for i in (1..items.len()).rev() {
+        let j = below(next(), i as u32 + 1) as usize;
+        items.swap(i, j);
+    }
+}
+
+/// Shuffles `items` in place, the same way every time for a given seed.
+///
+/// This is handy for things like dealing a numbered puzzle or daily
+/// challenge, where the result should be reproducible.
+#[inline]
+pub fn shuffle_seeded<T>(items: &mut [T], seed: u32) {
+    let mut counter = seed;
+    shuffle(items, || {
+        counter = counter.wrapping_add(0x9E37_79B9);
+        hash32(counter)
+    });
+}
@@ -0,0 +1,167 @@
+//! Sprite (OBJ) screen coordinates.
+//!
+//! OAM stores a sprite's x position in 9 bits and its y position in 8 bits,
+//! and both wrap around. That makes positions slightly off the top or left of
+//! the screen work (`-8` wraps to `504`), but also means a sprite far enough
+//! off the bottom can wrap around and appear at the top. [`ObjX`] and
+//! [`ObjY`] convert from `i32` screen positions and move sprites that aren't
+//! visible to a spot where they can't wrap back into view.
+
+use crate::GbaCellSafe;
+
+/// The screen's width in pixels.
+const SCREEN_WIDTH: i32 = 240;
+/// The screen's height in pixels.
+const SCREEN_HEIGHT: i32 = 160;
+
+/// A sprite's x position, as stored in OAM attribute 1 (bits 0 to 8).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[repr(transparent)]
+pub struct ObjX(u16);
+impl ObjX {
+    /// Just past the right edge, where sprites up to 64 pixels wide are
+    /// hidden without wrapping.
+    pub const OFFSCREEN: Self = Self(SCREEN_WIDTH as u16);
+
+    /// Converts a screen position, wrapping it into the 9-bit range.
+    ///
+    /// This is correct for any position where the sprite is at least partly
+    /// visible. Use [`from_screen`](Self::from_screen) when it might not be.
+    #[inline]
+    #[must_use]
+    pub const fn wrapping(x: i32) -> Self {
+        Self((x & 0x1FF) as u16)
+    }
+
+    /// Converts a screen position for a sprite `width` pixels wide, using
+    /// [`OFFSCREEN`](Self::OFFSCREEN) if none of it would be visible.
+    #[inline]
+    #[must_use]
+    pub const fn from_screen(x: i32, width: u8) -> Self {
+        if x <= -(width as i32) || x >= SCREEN_WIDTH {
+            Self::OFFSCREEN
+        } else {
+            Self::wrapping(x)
+        }
+    }
+
+    /// Makes a position from its 9 OAM bits. Higher bits are ignored.
+    #[inline]
+    #[must_use]
+    pub const fn from_bits(bits: u16) -> Self {
+        Self(bits & 0x1FF)
+    }
+
+    /// The 9 OAM bits.
+    #[inline]
+    #[must_use]
+    pub const fn bits(self) -> u16 {
+        self.0
+    }
+
+    /// The screen position, treating the 9 bits as signed (`-256..=255`).
+    #[inline]
+    #[must_use]
+    pub const fn to_screen(self) -> i32 {
+        ((self.0 << 7) as i16 >> 7) as i32
+    }
+}
+
+/// A sprite's y position, as stored in OAM attribute 0 (bits 0 to 7).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[repr(transparent)]
+pub struct ObjY(u8);
+impl ObjY {
+    /// Just past the bottom edge, where sprites up to 64 pixels tall are
+    /// hidden without wrapping.
+    pub const OFFSCREEN: Self = Self(SCREEN_HEIGHT as u8);
+
+    /// Converts a screen position, wrapping it into the 8-bit range.
+    ///
+    /// This is correct for any position where the sprite is at least partly
+    /// visible. Use [`from_screen`](Self::from_screen) when it might not be.
+    #[inline]
+    #[must_use]
+    pub const fn wrapping(y: i32) -> Self {
+        Self(y as u8)
+    }
+
+    /// Converts a screen position for a sprite `height` pixels tall, using
+    /// [`OFFSCREEN`](Self::OFFSCREEN) if none of it would be visible.
+    #[inline]
+    #[must_use]
+    pub const fn from_screen(y: i32, height: u8) -> Self {
+        if y <= -(height as i32) || y >= SCREEN_HEIGHT {
+            Self::OFFSCREEN
+        } else {
+            Self::wrapping(y)
+        }
+    }
+
+    /// Makes a position from its 8 OAM bits.
+    #[inline]
+    #[must_use]
+    pub const fn from_bits(bits: u8) -> Self {
+        Self(bits)
+    }
+
+    /// The 8 OAM bits.
+    #[inline]
+    #[must_use]
+    pub const fn bits(self) -> u8 {
+        self.0
+    }
+
+    /// Where a sprite `height` pixels tall is drawn.
+    ///
+    /// The hardware draws a sprite that would cross the bottom of the 256
+    /// pixel range wrapped around to the top instead, so the result depends
+    /// on the height.
+    #[inline]
+    #[must_use]
+    pub const fn to_screen(self, height: u8) -> i32 {
+        let y = self.0 as i32;
+        if y + height as i32 > 256 {
+            y - 256
+        } else {
+            y
+        }
+    }
+}
+
+// SAFETY: `ObjX` is a `repr(transparent)` newtype over `u16`.
+unsafe impl GbaCellSafe for ObjX {}
+// SAFETY: `ObjY` is a `repr(transparent)` newtype over `u8`.
+unsafe impl GbaCellSafe for ObjY {}
+
+// SAFETY: `ObjX` is a `repr(transparent)` newtype over `u16`, and zero is the
+// left edge.
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Zeroable for ObjX {}
+
+// SAFETY: `ObjX` is a `repr(transparent)` newtype over `u16`.
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::NoUninit for ObjX {}
+
+// Only the 9 OAM bits may be set, so a whole attribute 1 (with the flip and
+// size bits) fails the check rather than making an out of range position.
+//
+// SAFETY: `ObjX` is a `repr(transparent)` newtype over `u16`.
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::CheckedBitPattern for ObjX {
+    type Bits = u16;
+
+    #[inline]
+    fn is_valid_bit_pattern(bits: &u16) -> bool {
+        *bits < 512
+    }
+}
+
+// SAFETY: `ObjY` is a `repr(transparent)` newtype over `u8`, so every bit
+// pattern is a valid position.
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Zeroable for ObjY {}
+
+// SAFETY: as above.
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Pod for ObjY {}
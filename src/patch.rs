@@ -0,0 +1,207 @@
+//! Memory patches applied every frame, like cheat codes.
+//!
+//! A [`PatchList`] holds (address, value, width) patches that are written
+//! again each time [`apply`](PatchList::apply) is called, usually from the
+//! vblank handler. Writing every frame keeps a value pinned even while the
+//! game keeps changing it, which is how "infinite lives" codes work. This is
+//! meant for debugging tools, trainer builds, and accessibility options.
+
+use crate::GbaCell;
+
+/// How many bytes a [`Patch`] writes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum Width {
+    U8 = 1,
+    U16 = 2,
+    U32 = 4,
+}
+
+/// A value to write to an address.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Patch {
+    address: usize,
+    value: u32,
+    width: Width,
+}
+impl Patch {
+    /// Makes a patch that writes `value` (truncated to `width`) to `address`.
+    ///
+    /// ## Safety
+    /// * `address` must be valid for volatile writes of `width` bytes for as
+    ///   long as the patch is in a [`PatchList`], and aligned to `width`.
+    /// * Writing the value there must be sound at any time the list is
+    ///   applied, including in the middle of whatever code the vblank
+    ///   interrupt interrupted.
+    #[inline]
+    #[must_use]
+    pub const unsafe fn new(address: usize, value: u32, width: Width) -> Self {
+        Self {
+            address,
+            value,
+            width,
+        }
+    }
+
+    /// The address to write to.
+    #[inline]
+    #[must_use]
+    pub const fn address(&self) -> usize {
+        self.address
+    }
+
+    /// The value to write.
+    #[inline]
+    #[must_use]
+    pub const fn value(&self) -> u32 {
+        self.value
+    }
+
+    /// How many bytes to write.
+    #[inline]
+    #[must_use]
+    pub const fn width(&self) -> Width {
+        self.width
+    }
+
+    #[inline]
+    fn write(&self) {
+        // SAFETY: `Patch::new` requires the address to be valid and aligned
+        // for the width, and the write to be sound whenever patches apply.
+        unsafe {
+            match self.width {
+                Width::U8 => (self.address as *mut u8).write_volatile(self.value as u8),
+                Width::U16 => (self.address as *mut u16).write_volatile(self.value as u16),
+                Width::U32 => (self.address as *mut u32).write_volatile(self.value),
+            }
+        }
+    }
+}
+
+/// One slot of a [`PatchList`]. The patch is only used while `active` is set,
+/// which happens after the other parts are written, so [`PatchList::apply`]
+/// never sees a half-written patch.
+#[derive(Debug)]
+struct Slot {
+    active: GbaCell<bool>,
+    address: GbaCell<usize>,
+    value: GbaCell<u32>,
+    width: GbaCell<u8>,
+}
+impl Slot {
+    #[inline]
+    const fn new() -> Self {
+        Self {
+            active: GbaCell::new(false),
+            address: GbaCell::new(0),
+            value: GbaCell::new(0),
+            width: GbaCell::new(1),
+        }
+    }
+
+    #[inline]
+    fn get(&self) -> Option<Patch> {
+        if !self.active.read() {
+            return None;
+        }
+        let width = match self.width.read() {
+            1 => Width::U8,
+            2 => Width::U16,
+            _ => Width::U32,
+        };
+        Some(Patch {
+            address: self.address.read(),
+            value: self.value.read(),
+            width,
+        })
+    }
+}
+
+/// Holds up to `N` patches.
+///
+/// Every part is a [`GbaCell`], so patches can be added and removed from the
+/// main loop while [`apply`](Self::apply) runs in an interrupt.
+#[derive(Debug)]
+pub struct PatchList<const N: usize> {
+    enabled: GbaCell<bool>,
+    slots: [Slot; N],
+}
+impl<const N: usize> PatchList<N> {
+    /// Constructs a new, enabled list with no patches.
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            enabled: GbaCell::new(true),
+            slots: [const { Slot::new() }; N],
+        }
+    }
+
+    /// Turns applying the patches on or off, without removing them.
+    #[inline]
+    pub fn set_enabled(&self, enabled: bool) {
+        self.enabled.write(enabled);
+    }
+
+    /// Adds a patch, returning the slot it's in.
+    ///
+    /// If every slot is in use, the patch is given back as an error.
+    pub fn add(&self, patch: Patch) -> Result<usize, Patch> {
+        let Some(index) = self.slots.iter().position(|s| !s.active.read()) else {
+            return Err(patch);
+        };
+        let slot = &self.slots[index];
+        slot.address.write(patch.address);
+        slot.value.write(patch.value);
+        slot.width.write(patch.width as u8);
+        slot.active.write(true);
+        Ok(index)
+    }
+
+    /// The patch in a slot, if there is one.
+    #[inline]
+    #[must_use]
+    pub fn get(&self, index: usize) -> Option<Patch> {
+        self.slots.get(index)?.get()
+    }
+
+    /// Removes the patch in a slot, returning it.
+    ///
+    /// The memory isn't restored: it keeps the last patched value until the
+    /// game changes it.
+    #[inline]
+    pub fn remove(&self, index: usize) -> Option<Patch> {
+        let patch = self.get(index)?;
+        self.slots[index].active.write(false);
+        Some(patch)
+    }
+
+    /// Removes every patch.
+    #[inline]
+    pub fn clear(&self) {
+        self.slots.iter().for_each(|s| s.active.write(false));
+    }
+
+    /// Iterates the patches, with their slots.
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = (usize, Patch)> + '_ {
+        self.slots
+            .iter()
+            .enumerate()
+            .filter_map(|(i, s)| Some((i, s.get()?)))
+    }
+
+    /// Writes every patch, if the list is enabled.
+    pub fn apply(&self) {
+        if self.enabled.read() {
+            self.slots.iter().filter_map(Slot::get).for_each(|p| p.write());
+        }
+    }
+}
+
+impl<const N: usize> Default for PatchList<N> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
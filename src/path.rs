@@ -0,0 +1,375 @@
+//! A* and Dijkstra pathfinding over tile grids, with caller-provided memory.
+//!
+//! A [`Pathfinder`] doesn't allocate. It works in a [`PathNode`] per tile and
+//! a list of [`OpenEntry`]s, both borrowed from the caller, which can put them
+//! in EWRAM since a 64x64 map needs 32 KiB of nodes. Searches can also be
+//! spread over several frames by giving [`step`](Pathfinder::step) a budget of
+//! nodes to expand each call.
+//!
+//! The map is described by a closure giving the cost of entering each tile,
+//! or `None` for tiles that can't be entered. That's usually a lookup of the
+//! map's collision flags.
+
+/// Marks a node without a parent.
+const NO_PARENT: u16 = u16::MAX;
+
+/// The search state of one tile. A [`Pathfinder`] needs one per map tile.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PathNode {
+    g: u16,
+    f: u16,
+    parent: u16,
+    state: NodeState,
+}
+impl PathNode {
+    /// A node that hasn't been searched, for initializing arrays.
+    pub const EMPTY: Self = Self {
+        g: 0,
+        f: 0,
+        parent: NO_PARENT,
+        state: NodeState::Unseen,
+    };
+}
+impl Default for PathNode {
+    #[inline]
+    fn default() -> Self {
+        Self::EMPTY
+    }
+}
+
+/// A tile waiting to be searched. A [`Pathfinder`] needs a list of these.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub struct OpenEntry(u32);
+impl OpenEntry {
+    /// An unused entry, for initializing arrays.
+    pub const EMPTY: Self = Self(0);
+
+    /// Sorts by the estimated total cost, in the upper half.
+    #[inline]
+    const fn new(tile: u16, f: u16) -> Self {
+        Self((f as u32) << 16 | tile as u32)
+    }
+
+    #[inline]
+    const fn tile(self) -> u16 {
+        self.0 as u16
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum NodeState {
+    Unseen,
+    Open,
+    Closed,
+}
+
+/// How a search is going.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Search {
+    /// [`start`](Pathfinder::start) hasn't been called.
+    Idle,
+    /// The search needs more [`step`](Pathfinder::step)s.
+    Searching,
+    /// A path was found, and can be read with [`path`](Pathfinder::path).
+    Found,
+    /// There's no path to the goal.
+    NoPath,
+    /// The open list filled up before the search finished.
+    OutOfMemory,
+}
+
+/// Finds paths on a `width` by `height` grid of tiles.
+///
+/// Moving straight costs twice the entered tile's cost, and moving diagonally
+/// (when enabled) costs three times, approximating the extra distance.
+/// Diagonal moves can't cut the corner of a tile that can't be entered.
+#[derive(Debug)]
+pub struct Pathfinder<'a> {
+    nodes: &'a mut [PathNode],
+    open: &'a mut [OpenEntry],
+    open_len: usize,
+    width: u16,
+    height: u16,
+    goal: u16,
+    diagonals: bool,
+    heuristic: bool,
+    status: Search,
+}
+impl<'a> Pathfinder<'a> {
+    /// Makes a pathfinder using the given memory.
+    ///
+    /// `open` limits how many tiles can be waiting to be searched. A quarter
+    /// of the number of tiles is usually plenty.
+    ///
+    /// ## Panics
+    /// * If there are fewer `nodes` than tiles.
+    /// * If there are `u16::MAX` or more tiles.
+    #[cfg_attr(feature = "track_caller", track_caller)]
+    pub fn new(
+        width: u16,
+        height: u16,
+        nodes: &'a mut [PathNode],
+        open: &'a mut [OpenEntry],
+    ) -> Self {
+        let tiles = usize::from(width) * usize::from(height);
+        assert!(tiles < usize::from(NO_PARENT), "The map is too large.");
+        assert!(nodes.len() >= tiles, "Not enough nodes for the map.");
+        Self {
+            nodes,
+            open,
+            open_len: 0,
+            width,
+            height,
+            goal: 0,
+            diagonals: false,
+            heuristic: true,
+            status: Search::Idle,
+        }
+    }
+
+    /// Sets if paths can move diagonally. They can't by default.
+    #[inline]
+    #[must_use]
+    pub fn with_diagonals(mut self, diagonals: bool) -> Self {
+        self.diagonals = diagonals;
+        self
+    }
+
+    /// Searches evenly in all directions (Dijkstra's algorithm) rather than
+    /// toward the goal (A*).
+    ///
+    /// This is slower, but finds the cheapest path even when some tiles cost
+    /// 0. A* estimates the cost to the goal assuming each tile costs at least
+    /// 1, so with cost 0 tiles the estimate can be too high, and A* can then
+    /// settle for a more expensive path.
+    #[inline]
+    #[must_use]
+    pub fn dijkstra(mut self) -> Self {
+        self.heuristic = false;
+        self
+    }
+
+    /// How the current search is going.
+    #[inline]
+    #[must_use]
+    pub const fn status(&self) -> Search {
+        self.status
+    }
+
+    /// Starts a new search, replacing any previous one.
+    ///
+    /// ## Panics
+    /// * If either position is outside the map.
+    #[cfg_attr(feature = "track_caller", track_caller)]
+    pub fn start(&mut self, from: (u16, u16), to: (u16, u16)) {
+        let tiles = usize::from(self.width) * usize::from(self.height);
+        self.nodes[..tiles].fill(PathNode::EMPTY);
+        self.open_len = 0;
+        self.goal = self.index(to);
+        let start = self.index(from);
+        self.nodes[usize::from(start)] = PathNode {
+            g: 0,
+            f: self.estimate(start),
+            parent: NO_PARENT,
+            state: NodeState::Open,
+        };
+        self.status = if self.push(start) {
+            Search::Searching
+        } else {
+            Search::OutOfMemory
+        };
+    }
+
+    /// Expands up to `budget` tiles of the search, returning the new status.
+    ///
+    /// `cost(x, y)` gives the cost of entering a tile, or `None` if it can't be
+    /// entered. The goal itself must be enterable to be found.
+    pub fn step<F>(&mut self, budget: usize, mut cost: F) -> Search
+    where
+        F: FnMut(u16, u16) -> Option<u8>,
+    {
+        if self.status != Search::Searching {
+            return self.status;
+        }
+        for _ in 0..budget {
+            let Some(current) = self.pop() else {
+                self.status = Search::NoPath;
+                return self.status;
+            };
+            let node = &mut self.nodes[usize::from(current)];
+            if node.state == NodeState::Closed {
+                // A stale entry from before the tile's cost was lowered.
+                continue;
+            }
+            node.state = NodeState::Closed;
+            if current == self.goal {
+                self.status = Search::Found;
+                return self.status;
+            }
+            if !self.expand(current, &mut cost) {
+                self.status = Search::OutOfMemory;
+                return self.status;
+            }
+        }
+        self.status
+    }
+
+    /// Writes the found path into `out`, from the start to the goal, and
+    /// returns the part of `out` used.
+    ///
+    /// Returns `None` if no path has been found, or if `out` is too short.
+    pub fn path<'o>(&self, out: &'o mut [(u16, u16)]) -> Option<&'o [(u16, u16)]> {
+        if self.status != Search::Found {
+            return None;
+        }
+        let mut len = 0;
+        let mut i = self.goal;
+        while i != NO_PARENT {
+            len += 1;
+            i = self.nodes[usize::from(i)].parent;
+        }
+        let out = out.get_mut(..len)?;
+        let mut i = self.goal;
+        for entry in out.iter_mut().rev() {
+            *entry = (i % self.width, i / self.width);
+            i = self.nodes[usize::from(i)].parent;
+        }
+        Some(out)
+    }
+
+    #[inline]
+    #[cfg_attr(feature = "track_caller", track_caller)]
+    fn index(&self, (x, y): (u16, u16)) -> u16 {
+        assert!(
+            x < self.width && y < self.height,
+            "Position outside the map."
+        );
+        y * self.width + x
+    }
+
+    /// The estimated cost from a tile to the goal, assuming each tile costs
+    /// at least 1.
+    ///
+    /// On a long thin map the estimate can pass `u16::MAX`, so it's worked
+    /// out in `u32` and saturates, like the path costs do.
+    #[inline]
+    fn estimate(&self, i: u16) -> u16 {
+        if !self.heuristic {
+            return 0;
+        }
+        let dx = u32::from((i % self.width).abs_diff(self.goal % self.width));
+        let dy = u32::from((i / self.width).abs_diff(self.goal / self.width));
+        let estimate = if self.diagonals {
+            2 * (dx + dy) - dx.min(dy)
+        } else {
+            2 * (dx + dy)
+        };
+        u16::try_from(estimate).unwrap_or(u16::MAX)
+    }
+
+    /// Opens the neighbors of a tile. Returns `false` if the open list is full.
+    fn expand<F>(&mut self, current: u16, cost: &mut F) -> bool
+    where
+        F: FnMut(u16, u16) -> Option<u8>,
+    {
+        let (x, y) = (current % self.width, current / self.width);
+        let g = self.nodes[usize::from(current)].g;
+        let directions: &[(i8, i8)] = if self.diagonals {
+            &[
+                (1, 0),
+                (0, 1),
+                (-1, 0),
+                (0, -1),
+                (1, 1),
+                (-1, 1),
+                (-1, -1),
+                (1, -1),
+            ]
+        } else {
+            &[(1, 0), (0, 1), (-1, 0), (0, -1)]
+        };
+        for &(dx, dy) in directions {
+            let Some(nx) = x.checked_add_signed(dx.into()).filter(|&n| n < self.width) else {
+                continue;
+            };
+            let Some(ny) = y.checked_add_signed(dy.into()).filter(|&n| n < self.height) else {
+                continue;
+            };
+            let Some(c) = cost(nx, ny) else {
+                continue;
+            };
+            let diagonal = dx != 0 && dy != 0;
+            if diagonal && (cost(nx, y).is_none() || cost(x, ny).is_none()) {
+                continue;
+            }
+            let step = u16::from(c) * if diagonal { 3 } else { 2 };
+            let next_g = g.saturating_add(step);
+            let next = ny * self.width + nx;
+            let node = self.nodes[usize::from(next)];
+            let better = match node.state {
+                NodeState::Unseen => true,
+                NodeState::Open => next_g < node.g,
+                NodeState::Closed => false,
+            };
+            if better {
+                self.nodes[usize::from(next)] = PathNode {
+                    g: next_g,
+                    f: next_g.saturating_add(self.estimate(next)),
+                    parent: current,
+                    state: NodeState::Open,
+                };
+                if !self.push(next) {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+
+    /// Adds a tile to the open list's binary heap.
+    fn push(&mut self, tile: u16) -> bool {
+        if self.open_len == self.open.len() {
+            return false;
+        }
+        let mut i = self.open_len;
+        self.open[i] = OpenEntry::new(tile, self.nodes[usize::from(tile)].f);
+        self.open_len += 1;
+        while i > 0 {
+            let parent = (i - 1) / 2;
+            if self.open[parent] <= self.open[i] {
+                break;
+            }
+            self.open.swap(parent, i);
+            i = parent;
+        }
+        true
+    }
+
+    /// Removes the open tile with the lowest estimated total cost.
+    fn pop(&mut self) -> Option<u16> {
+        if self.open_len == 0 {
+            return None;
+        }
+        let top = self.open[0].tile();
+        self.open_len -= 1;
+        self.open[0] = self.open[self.open_len];
+        let mut i = 0;
+        loop {
+            let left = 2 * i + 1;
+            let right = left + 1;
+            let mut smallest = i;
+            if left < self.open_len && self.open[left] < self.open[smallest] {
+                smallest = left;
+            }
+            if right < self.open_len && self.open[right] < self.open[smallest] {
+                smallest = right;
+            }
+            if smallest == i {
+                break;
+            }
+            self.open.swap(i, smallest);
+            i = smallest;
+        }
+        Some(top)
+    }
+}
@@ -0,0 +1,239 @@
+//! A fixed-capacity pool of game objects, such as bullets or enemies.
+//!
+//! Objects are referred to by [`Handle`]s rather than references. A handle
+//! remembers the generation of its slot, so a handle to an object that's gone
+//! stays safely invalid even after the slot is reused by a new object.
+
+/// Refers to an object in a [`Pool`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Handle {
+    slot: u16,
+    generation: u16,
+}
+impl Handle {
+    /// The slot the object is in, from `0` to `N - 1`.
+    ///
+    /// The slot doesn't change while the object is alive, so it can be used to
+    /// index parallel arrays, such as to give each object its own OAM entry.
+    #[inline]
+    #[must_use]
+    pub const fn index(self) -> usize {
+        self.slot as usize
+    }
+}
+
+/// Holds up to `N` objects.
+///
+/// Objects can be removed right away with [`despawn`](Self::despawn), or
+/// marked with [`despawn_later`](Self::despawn_later) while iterating and
+/// then all removed at once with [`flush`](Self::flush). Marked objects stay
+/// in the pool, and are still iterated, until the flush.
+#[derive(Debug, Clone)]
+pub struct Pool<T, const N: usize> {
+    objects: [Option<T>; N],
+    generations: [u16; N],
+    doomed: [bool; N],
+    len: usize,
+}
+impl<T, const N: usize> Pool<T, N> {
+    /// Makes an empty pool.
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            objects: [const { None }; N],
+            generations: [0; N],
+            doomed: [false; N],
+            len: 0,
+        }
+    }
+
+    /// The number of objects in the pool.
+    #[inline]
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// If the pool has no objects.
+    #[inline]
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// If every slot is in use.
+    #[inline]
+    #[must_use]
+    pub const fn is_full(&self) -> bool {
+        self.len == N
+    }
+
+    /// Adds an object, using the lowest free slot.
+    ///
+    /// If the pool is full, the object is given back as an error.
+    pub fn spawn(&mut self, object: T) -> Result<Handle, T> {
+        let Some(slot) = self.objects.iter().position(Option::is_none) else {
+            return Err(object);
+        };
+        self.objects[slot] = Some(object);
+        self.len += 1;
+        Ok(Handle {
+            slot: slot as u16,
+            generation: self.generations[slot],
+        })
+    }
+
+    /// If the handle's object is still in the pool.
+    #[inline]
+    #[must_use]
+    pub fn contains(&self, handle: Handle) -> bool {
+        self.get(handle).is_some()
+    }
+
+    /// The handle's object, if it's still in the pool.
+    #[inline]
+    #[must_use]
+    pub fn get(&self, handle: Handle) -> Option<&T> {
+        let slot = handle.index();
+        if self.generations.get(slot)? != &handle.generation {
+            return None;
+        }
+        self.objects[slot].as_ref()
+    }
+
+    /// The handle's object mutably, if it's still in the pool.
+    #[inline]
+    #[must_use]
+    pub fn get_mut(&mut self, handle: Handle) -> Option<&mut T> {
+        let slot = handle.index();
+        if self.generations.get(slot)? != &handle.generation {
+            return None;
+        }
+        self.objects[slot].as_mut()
+    }
+
+    /// Removes the handle's object right away, returning it.
+    #[inline]
+    pub fn despawn(&mut self, handle: Handle) -> Option<T> {
+        if !self.contains(handle) {
+            return None;
+        }
+        self.free(handle.index())
+    }
+
+    /// Marks the handle's object to be removed by the next
+    /// [`flush`](Self::flush). Returns if the object was in the pool.
+    #[inline]
+    pub fn despawn_later(&mut self, handle: Handle) -> bool {
+        let alive = self.contains(handle);
+        if alive {
+            self.doomed[handle.index()] = true;
+        }
+        alive
+    }
+
+    /// If the handle's object is marked to be removed.
+    #[inline]
+    #[must_use]
+    pub fn is_despawning(&self, handle: Handle) -> bool {
+        self.contains(handle) && self.doomed[handle.index()]
+    }
+
+    /// Removes every object marked by [`despawn_later`](Self::despawn_later).
+    ///
+    /// Returns how many objects were removed.
+    pub fn flush(&mut self) -> usize {
+        let mut removed = 0;
+        for slot in 0..N {
+            if self.doomed[slot] {
+                self.free(slot);
+                removed += 1;
+            }
+        }
+        removed
+    }
+
+    /// Removes every object for which `keep` returns `false`.
+    pub fn retain<F>(&mut self, mut keep: F)
+    where
+        F: FnMut(Handle, &mut T) -> bool,
+    {
+        for slot in 0..N {
+            let handle = self.handle(slot);
+            if let Some(object) = &mut self.objects[slot] {
+                if !keep(handle, object) {
+                    self.free(slot);
+                }
+            }
+        }
+    }
+
+    /// Removes every object.
+    #[inline]
+    pub fn clear(&mut self) {
+        for slot in 0..N {
+            if self.objects[slot].is_some() {
+                self.free(slot);
+            }
+        }
+    }
+
+    /// Iterates the objects with their handles, in slot order.
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = (Handle, &T)> {
+        self.objects
+            .iter()
+            .zip(self.generations)
+            .enumerate()
+            .filter_map(|(slot, (o, generation))| {
+                let handle = Handle {
+                    slot: slot as u16,
+                    generation,
+                };
+                o.as_ref().map(|o| (handle, o))
+            })
+    }
+
+    /// Iterates the objects mutably with their handles, in slot order.
+    #[inline]
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (Handle, &mut T)> {
+        self.objects
+            .iter_mut()
+            .zip(self.generations)
+            .enumerate()
+            .filter_map(|(slot, (o, generation))| {
+                let handle = Handle {
+                    slot: slot as u16,
+                    generation,
+                };
+                o.as_mut().map(|o| (handle, o))
+            })
+    }
+
+    #[inline]
+    fn handle(&self, slot: usize) -> Handle {
+        Handle {
+            slot: slot as u16,
+            generation: self.generations[slot],
+        }
+    }
+
+    #[inline]
+    fn free(&mut self, slot: usize) -> Option<T> {
+        let object = self.objects[slot].take();
+        if object.is_some() {
+            self.len -= 1;
+        }
+        self.doomed[slot] = false;
+        self.generations[slot] = self.generations[slot].wrapping_add(1);
+        object
+    }
+}
+
+impl<T, const N: usize> Default for Pool<T, N> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,251 @@
+//! Halting the CPU until an interrupt, without the BIOS.
+//!
+//! The BIOS `IntrWait` call works by having the IRQ handler also write the
+//! acknowledged bits to a BIOS-owned flag word, and it forces `IME` on. The
+//! functions here do the same job with an [`IrqLatch`] that the handler
+//! writes to instead, and leave `IME` the way they found it. They also skip
+//! the cost of the SWI itself.
+//!
+//! An [`IdleMeter`] measures how long the CPU spends halted, which is how much
+//! of each frame is left over, [`idle`] halts unless a [`Scheduler`] already
+//! has work due, and [`sleep_until_keys`] puts the whole system to sleep.
+
+use crate::keys::Keys;
+use crate::schedule::Scheduler;
+use crate::display::{FORCED_BLANK, LINES_PER_FRAME};
+use crate::{audio, mmio, GbaCell};
+
+/// Records which interrupts have happened, for [`halt_until`].
+///
+/// The IRQ handler must call [`record`](Self::record) with the `IF` bits it
+/// acknowledges, since once `IF` is cleared there's no other way to tell which
+/// interrupt woke the CPU.
+#[derive(Debug)]
+pub struct IrqLatch(GbaCell<u16>);
+impl IrqLatch {
+    /// Constructs a new latch with no bits set.
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self(GbaCell::new(0))
+    }
+
+    /// Marks the given interrupts as having happened.
+    #[inline]
+    pub fn record(&self, bits: u16) {
+        self.0.write(self.0.read() | bits);
+    }
+
+    /// Clears the given interrupts, returning which of them had been set.
+    #[inline]
+    pub fn take(&self, bits: u16) -> u16 {
+        let current = self.0.read();
+        self.0.write(current & !bits);
+        current & bits
+    }
+}
+
+impl Default for IrqLatch {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Halts the CPU until any enabled interrupt is requested.
+///
+/// The CPU wakes even if `IME` is off, in which case the interrupt stays
+/// pending instead of being handled.
+#[inline]
+pub fn halt() {
+    // SAFETY: writing 0 to `HALTCNT` enters halt mode, which has no effect
+    // other than pausing the CPU until `IE & IF` is non-zero.
+    unsafe { mmio::HALTCNT.write_volatile(0) };
+}
+
+/// Halts until one of the interrupts in `bits` has happened.
+///
+/// Any of `bits` already recorded in the latch are cleared first, so this
+/// always waits for a new interrupt (like `IntrWait` with "discard old
+/// flags" set). The bits that ended the wait are cleared from the latch and
+/// returned.
+///
+/// The interrupts must be enabled in `IE` and handled by an IRQ handler that
+/// calls [`IrqLatch::record`], or this never returns.
+pub fn halt_until(latch: &IrqLatch, bits: u16) -> u16 {
+    latch.take(bits);
+    // SAFETY: `IME` is a plain read/write register.
+    let ime = unsafe { mmio::IME.read_volatile() };
+    loop {
+        // With `IME` off, an interrupt between the check and the halt stays
+        // pending, so the halt ends immediately instead of missing it.
+        // SAFETY: as above.
+        unsafe { mmio::IME.write_volatile(0) };
+        let seen = latch.take(bits);
+        if seen != 0 {
+            // SAFETY: as above. This puts back the value `IME` had on entry.
+            unsafe { mmio::IME.write_volatile(ime) };
+            return seen;
+        }
+        halt();
+        // Let the pending interrupt be handled, which records it in the latch.
+        // SAFETY: as above. The handler runs here, between two of this
+        // loop's `IME` writes, where nothing else is in progress.
+        unsafe { mmio::IME.write_volatile(1) };
+    }
+}
+
+/// Halts until one of the interrupts in `bits` has happened, like
+/// [`halt_until`], unless `scheduler` already has work due.
+///
+/// A task that's already due (scheduled for 0 frames and not yet run) would
+/// otherwise wait a whole frame, so then this returns 0 right away without
+/// halting. Any other time it halts, since waking from halt only takes a few
+/// cycles and halting saves battery.
+///
+/// The same requirements as [`halt_until`] apply: the interrupts must be
+/// enabled in `IE`, and the IRQ handler must call [`IrqLatch::record`].
+pub fn idle<const N: usize>(scheduler: &Scheduler<N>, latch: &IrqLatch, bits: u16) -> u16 {
+    if scheduler.next_due() == Some(0) {
+        return 0;
+    }
+    halt_until(latch, bits)
+}
+
+/// The keypad bit of `IE` and `IF`.
+const KEYPAD_IRQ: u16 = 1 << 12;
+
+/// Puts the system to sleep (the BIOS `Stop` state) until all of `keys` are
+/// pressed, such as L + R + Select.
+///
+/// This is the sequence from GBATEK done in one place:
+/// 1. Waits for `keys` to not all be held, so the combo that asked for sleep
+///    doesn't wake it right away.
+/// 2. Masks interrupts, turns off sound with [`audio::disable`], and puts the
+///    display in forced blank, since neither turns off by itself.
+/// 3. Sets `KEYCNT` and `IE` so that only the key combo can wake the system,
+///    and stops.
+/// 4. On waking, puts `KEYCNT`, `IE`, `DISPCNT`, sound, and `IME` back the way
+///    they were.
+///
+/// Sound is powered back on if it was on, but the PSG registers are reset, as
+/// described in the [`audio`] module. The keys are still held when this
+/// returns, so they'll show up as newly pressed.
+///
+/// ## Panics
+/// * If `keys` is empty, since nothing could wake the system.
+#[cfg_attr(feature = "track_caller", track_caller)]
+pub fn sleep_until_keys(keys: Keys) {
+    assert!(
+        !keys.is_empty(),
+        "Sleeping needs at least one key to wake on."
+    );
+    while Keys::read().contains(keys) {}
+    // SAFETY: `IME`, `IE`, `IF`, `KEYCNT`, and `DISPCNT` are plain registers.
+    // Interrupts are masked while `IE` and `KEYCNT` are changed, and they're
+    // restored before `IME` is.
+    unsafe {
+        let ime = mmio::IME.read_volatile();
+        mmio::IME.write_volatile(0);
+        let ie = mmio::IE.read_volatile();
+        let keycnt = mmio::KEYCNT.read_volatile();
+        let dispcnt = mmio::DISPCNT.read_volatile();
+        let sound = audio::is_enabled();
+
+        audio::disable();
+        mmio::DISPCNT.write_volatile(dispcnt | FORCED_BLANK);
+        mmio::KEYCNT.write_volatile(keys.bits() | (1 << 14) | (1 << 15));
+        mmio::IE.write_volatile(KEYPAD_IRQ);
+        mmio::IF.write_volatile(KEYPAD_IRQ);
+        // Writing 0x80 to `HALTCNT` stops until `IE & IF` is non-zero.
+        mmio::HALTCNT.write_volatile(0x80);
+        mmio::IF.write_volatile(KEYPAD_IRQ);
+
+        mmio::KEYCNT.write_volatile(keycnt);
+        mmio::IE.write_volatile(ie);
+        mmio::DISPCNT.write_volatile(dispcnt);
+        if sound {
+            audio::enable();
+        }
+        mmio::IME.write_volatile(ime);
+    }
+}
+
+/// Measures how many scanlines per frame the CPU spends halted.
+///
+/// Time is measured with `VCOUNT`, so it's only accurate to a scanline, and a
+/// single halt of a whole frame or more is undercounted. Measure the halts
+/// with [`measure`](Self::measure) (or use [`halt_until`](Self::halt_until)),
+/// and call [`end_frame`](Self::end_frame) once per frame, such as right
+/// after waiting for vblank.
+#[derive(Debug)]
+pub struct IdleMeter {
+    current: GbaCell<u16>,
+    last: GbaCell<u16>,
+}
+impl IdleMeter {
+    /// Constructs a new meter with no idle time counted.
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            current: GbaCell::new(0),
+            last: GbaCell::new(0),
+        }
+    }
+
+    /// Runs `f`, counting the scanlines it takes as idle.
+    #[inline]
+    pub fn measure<R>(&self, f: impl FnOnce() -> R) -> R {
+        // SAFETY: `VCOUNT` is a read-only register with no side effects.
+        let start = unsafe { mmio::VCOUNT.read_volatile() };
+        let r = f();
+        // SAFETY: as above.
+        let end = unsafe { mmio::VCOUNT.read_volatile() };
+        let lines = if end >= start {
+            end - start
+        } else {
+            end + LINES_PER_FRAME - start
+        };
+        self.current.write(self.current.read().saturating_add(lines));
+        r
+    }
+
+    /// Calls [`halt_until`](crate::power::halt_until), counting the time as
+    /// idle.
+    #[inline]
+    pub fn halt_until(&self, latch: &IrqLatch, bits: u16) -> u16 {
+        self.measure(|| halt_until(latch, bits))
+    }
+
+    /// Finishes a frame, making its total available from
+    /// [`idle_lines`](Self::idle_lines).
+    #[inline]
+    pub fn end_frame(&self) {
+        self.last.write(self.current.read().min(LINES_PER_FRAME));
+        self.current.write(0);
+    }
+
+    /// The scanlines spent idle in the last finished frame, out of 228.
+    #[inline]
+    #[must_use]
+    pub fn idle_lines(&self) -> u16 {
+        self.last.read()
+    }
+
+    /// The percentage of the last finished frame spent idle.
+    ///
+    /// A game can use this to cut back on effects when it's close to 0.
+    #[inline]
+    #[must_use]
+    pub fn idle_percent(&self) -> u8 {
+        (u32::from(self.idle_lines()) * 100 / u32::from(LINES_PER_FRAME)) as u8
+    }
+}
+
+impl Default for IdleMeter {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,39 @@
+//! The most commonly used items, for glob importing.
+//!
+//! ```
+//! use gba_cell::prelude::*;
+//! ```
+//!
+//! This covers the cell types, input, and the main-loop building blocks.
+//! Everything else (such as the checksum and compression functions) is left in
+//! its module, since it's usually only needed in one place.
+
+pub use crate::arm_fn;
+pub use crate::color::Color;
+pub use crate::direction::{atan2_approx, dist_approx, Angle, Dir16, Dir8};
+pub use crate::keys::{Key, KeyMap, Keys};
+pub use crate::pool::{Handle, Pool};
+pub use crate::scene::{SceneStack, Transition};
+pub use crate::timer::{Cooldown, Stopwatch};
+pub use crate::{GbaCell, GbaCellSafe};
+
+#[cfg(feature = "on_gba")]
+pub use crate::event::{Event, EventBus, LogRecord};
+#[cfg(feature = "on_gba")]
+pub use crate::executor::{next_frame, wait_frames, wait_until, Executor, IrqSignal};
+#[cfg(feature = "on_gba")]
+pub use crate::fn_cell::GbaFnCell;
+#[cfg(feature = "on_gba")]
+pub use crate::game::GbaGame;
+#[cfg(feature = "on_gba")]
+pub use crate::power::{halt, halt_until, idle, sleep_until_keys, IrqLatch};
+#[cfg(feature = "on_gba")]
+pub use crate::queue::GbaQueue;
+#[cfg(feature = "on_gba")]
+pub use crate::reset::{soft_reset, ResetChord};
+#[cfg(feature = "on_gba")]
+pub use crate::schedule::{Action, FrameCounter, Scheduler};
+#[cfg(feature = "on_gba")]
+pub use crate::transaction::{transaction, IrqToken, Masked};
+#[cfg(feature = "on_gba")]
+pub use crate::watchdog::Watchdog;
@@ -0,0 +1,163 @@
+//! Provides the [`GbaQueue`] type.
+
+use crate::GbaCell;
+use core::{
+    cell::UnsafeCell,
+    mem::MaybeUninit,
+    sync::atomic::{compiler_fence, Ordering},
+};
+
+/// A fixed-capacity single-producer single-consumer queue.
+///
+/// This is for handing values between IRQ and non-IRQ code, such as bytes
+/// received by a serial interrupt handler that the main loop processes later.
+/// The queue holds up to `N` values, and `N` must be a power of two no larger
+/// than `0x8000` (this is checked at compile time).
+///
+/// The head and tail positions are each a [`GbaCell<u16>`], so the producer
+/// and consumer never need to disable interrupts. In exchange, all pushing has
+/// to happen in one context and all popping in one context, such as "pushes in
+/// the serial handler, pops in the main loop", which is why
+/// [`push`](Self::push) and [`pop`](Self::pop) are `unsafe`. Two producers
+/// that interrupt each other can move the tail backwards past a value, so that
+/// the consumer reads a slot that was never written, and two consumers can
+/// read a slot while it's being overwritten. [`EventBus`](crate::event::EventBus)
+/// is a safe wrapper that masks interrupts instead.
+pub struct GbaQueue<T, const N: usize> {
+    head: GbaCell<u16>,
+    tail: GbaCell<u16>,
+    slots: UnsafeCell<[MaybeUninit<T>; N]>,
+}
+
+// SAFETY: the slots are only accessed through the `unsafe` methods, whose
+// callers promise there's only one producer and one consumer.
+unsafe impl<T, const N: usize> Sync for GbaQueue<T, N> where T: Copy {}
+
+impl<T, const N: usize> GbaQueue<T, N>
+where
+    T: Copy,
+{
+    /// Helper to assert the capacity requirements at compile time.
+    const _ASSERT_CAPACITY: () = {
+        if !N.is_power_of_two() || N > 0x8000 {
+            panic!("GbaQueue capacity must be a power of two no larger than 0x8000.")
+        }
+    };
+
+    /// Constructs a new, empty queue.
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        let () = Self::_ASSERT_CAPACITY;
+        Self {
+            head: GbaCell::new(0),
+            tail: GbaCell::new(0),
+            slots: UnsafeCell::new([MaybeUninit::uninit(); N]),
+        }
+    }
+
+    /// The most values the queue can hold at once.
+    #[inline]
+    #[must_use]
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// The number of values currently in the queue.
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        usize::from(self.tail.read().wrapping_sub(self.head.read()))
+    }
+
+    /// If the queue has no values in it.
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// If the queue has no room for more values.
+    #[inline]
+    #[must_use]
+    pub fn is_full(&self) -> bool {
+        self.len() >= N
+    }
+
+    /// Adds a value to the back of the queue.
+    ///
+    /// If the queue is full the value is given back as an error.
+    ///
+    /// ## Safety
+    /// * Only one context may push to the queue: a call to `push` mustn't
+    ///   interrupt, or be interrupted by, another call to `push` on the same
+    ///   queue.
+    #[inline]
+    pub unsafe fn push(&self, t: T) -> Result<(), T> {
+        let tail = self.tail.read();
+        if usize::from(tail.wrapping_sub(self.head.read())) >= N {
+            return Err(t);
+        }
+        let slot = usize::from(tail) % N;
+        // SAFETY: the slot index is in bounds, and only the producer writes to
+        // slots between `tail` and `head + N`, which the consumer won't read
+        // until `tail` is moved past them below.
+        unsafe { (*self.slots.get())[slot] = MaybeUninit::new(t) };
+        compiler_fence(Ordering::SeqCst);
+        self.tail.write(tail.wrapping_add(1));
+        Ok(())
+    }
+
+    /// Takes the value at the front of the queue.
+    ///
+    /// ## Safety
+    /// * Only one context may take values from the queue: calls to `pop`,
+    ///   [`peek`](Self::peek), and [`clear`](Self::clear) on the same queue
+    ///   mustn't interrupt, or be interrupted by, each other.
+    #[inline]
+    pub unsafe fn pop(&self) -> Option<T> {
+        // SAFETY: the caller is the only consumer.
+        let t = unsafe { self.peek() }?;
+        compiler_fence(Ordering::SeqCst);
+        self.head.write(self.head.read().wrapping_add(1));
+        Some(t)
+    }
+
+    /// Gets a copy of the value at the front of the queue without removing it.
+    ///
+    /// ## Safety
+    /// * The same as for [`pop`](Self::pop), since the slot being read could
+    ///   otherwise be popped and reused partway through.
+    #[inline]
+    #[must_use]
+    pub unsafe fn peek(&self) -> Option<T> {
+        let head = self.head.read();
+        if head == self.tail.read() {
+            return None;
+        }
+        compiler_fence(Ordering::SeqCst);
+        let slot = usize::from(head) % N;
+        // SAFETY: slots between `head` and `tail` were initialized by the
+        // producer before it moved `tail`.
+        Some(unsafe { (*self.slots.get())[slot].assume_init() })
+    }
+
+    /// Removes every value in the queue.
+    ///
+    /// ## Safety
+    /// * The same as for [`pop`](Self::pop).
+    #[inline]
+    pub unsafe fn clear(&self) {
+        self.head.write(self.tail.read());
+    }
+}
+
+impl<T, const N: usize> Default for GbaQueue<T, N>
+where
+    T: Copy,
+{
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,138 @@
+//! Ratios in the small hardware ranges.
+//!
+//! Several registers take coefficients with odd maximums: blending uses
+//! `0..=16`, PSG master volume uses `0..=7`, and 5-bit color channels use
+//! `0..=31`. Writing a percentage or `1.0` straight into one of these is an
+//! easy off-by-one, or overflows into the next field. These types clamp on
+//! construction and convert from percentages and 8.8 fixed-point fractions
+//! (where `256` is `1.0`), so a stored value is always in range.
+
+use crate::GbaCellSafe;
+
+macro_rules! ratio_type {
+    ($(#[$meta:meta])* $name:ident, $max:literal) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+        #[repr(transparent)]
+        pub struct $name(u8);
+        impl $name {
+            /// The largest value, meaning 100%.
+            pub const MAX: u8 = $max;
+            /// Nothing (0%).
+            pub const ZERO: Self = Self(0);
+            /// Everything (100%).
+            pub const FULL: Self = Self($max);
+
+            /// Makes a ratio from a raw hardware value, clamped to
+            /// [`MAX`](Self::MAX).
+            #[inline]
+            #[must_use]
+            pub const fn new(value: u8) -> Self {
+                if value > $max {
+                    Self($max)
+                } else {
+                    Self(value)
+                }
+            }
+
+            /// Makes a ratio from a percentage, rounded to the nearest step.
+            /// Percentages over 100 are clamped.
+            #[inline]
+            #[must_use]
+            pub const fn from_percent(percent: u8) -> Self {
+                let percent = if percent > 100 { 100 } else { percent as u32 };
+                Self(((percent * $max + 50) / 100) as u8)
+            }
+
+            /// Makes a ratio from an 8.8 fixed-point fraction, rounded to the
+            /// nearest step. Fractions over `256` (`1.0`) are clamped.
+            #[inline]
+            #[must_use]
+            pub const fn from_fraction(fraction: u16) -> Self {
+                let fraction = if fraction > 256 { 256 } else { fraction as u32 };
+                Self(((fraction * $max + 128) >> 8) as u8)
+            }
+
+            /// The raw hardware value.
+            #[inline]
+            #[must_use]
+            pub const fn get(self) -> u8 {
+                self.0
+            }
+
+            /// The ratio as a percentage, rounded to the nearest percent.
+            #[inline]
+            #[must_use]
+            pub const fn to_percent(self) -> u8 {
+                ((self.0 as u32 * 100 + $max / 2) / $max) as u8
+            }
+
+            /// The ratio as an 8.8 fixed-point fraction, rounded.
+            #[inline]
+            #[must_use]
+            pub const fn to_fraction(self) -> u16 {
+                ((self.0 as u32 * 256 + $max / 2) / $max) as u16
+            }
+
+            /// `1.0` minus this ratio, such as the second layer's weight
+            /// when alpha blending.
+            #[inline]
+            #[must_use]
+            pub const fn complement(self) -> Self {
+                Self($max - self.0)
+            }
+        }
+
+        impl From<$name> for u8 {
+            #[inline]
+            fn from(r: $name) -> u8 {
+                r.0
+            }
+        }
+
+        // SAFETY: it's a `repr(transparent)` newtype over `u8`.
+        unsafe impl GbaCellSafe for $name {}
+
+        // SAFETY: it's a `repr(transparent)` newtype over `u8`, and zero is
+        // `ZERO`.
+        #[cfg(feature = "bytemuck")]
+        unsafe impl bytemuck::Zeroable for $name {}
+
+        // SAFETY: it's a `repr(transparent)` newtype over `u8`.
+        #[cfg(feature = "bytemuck")]
+        unsafe impl bytemuck::NoUninit for $name {}
+
+        // Values over `MAX` fail the check, since the other methods assume
+        // they can't happen (`complement` would overflow).
+        //
+        // SAFETY: it's a `repr(transparent)` newtype over `u8`.
+        #[cfg(feature = "bytemuck")]
+        unsafe impl bytemuck::CheckedBitPattern for $name {
+            type Bits = u8;
+
+            #[inline]
+            fn is_valid_bit_pattern(bits: &u8) -> bool {
+                *bits <= $max
+            }
+        }
+    };
+}
+
+ratio_type!(
+    /// A blend coefficient in `0..=16`, for the `EVA` and `EVB` fields of
+    /// `BLDALPHA` and the `EVY` field of `BLDY`.
+    Blend16,
+    16
+);
+
+ratio_type!(
+    /// A PSG master volume in `0..=7`, for the `SOUNDCNT_L` volume fields.
+    Volume7,
+    7
+);
+
+ratio_type!(
+    /// A 5-bit coefficient in `0..=31`, such as one channel of a color.
+    Coeff31,
+    31
+);
@@ -0,0 +1,263 @@
+//! Recording and playing back key input, for attract-mode demos and
+//! reproducing bugs.
+//!
+//! If the game logic only depends on its input (and on random numbers seeded
+//! at the start of the recording), then feeding it recorded input makes it do
+//! exactly the same thing again. [`StateChecksums`] checks that it does, by
+//! comparing a hash of the game state each frame.
+//!
+//! Recordings are run-length encoded as `u16` entries: the low 10 bits are the
+//! held keys, and the top 6 bits are how many frames they were held for, less
+//! one. So a recording takes 2 bytes per change in input, or per 64 frames
+//! without a change.
+
+use crate::keys::Keys;
+
+const KEY_BITS: u16 = 0b11_1111_1111;
+const RUN_SHIFT: u32 = 10;
+const MAX_RUN: u16 = 64;
+
+/// Records key input into a buffer.
+#[derive(Debug)]
+pub struct InputRecorder<'a> {
+    buffer: &'a mut [u16],
+    len: usize,
+    frames: u32,
+}
+impl<'a> InputRecorder<'a> {
+    /// Starts an empty recording in the buffer.
+    #[inline]
+    #[must_use]
+    pub fn new(buffer: &'a mut [u16]) -> Self {
+        Self {
+            buffer,
+            len: 0,
+            frames: 0,
+        }
+    }
+
+    /// Records one frame of input.
+    ///
+    /// Returns `false` if the buffer is full, in which case the frame isn't
+    /// recorded.
+    pub fn record(&mut self, keys: Keys) -> bool {
+        let bits = keys.bits() & KEY_BITS;
+        if let Some(last) = self.len.checked_sub(1).map(|i| &mut self.buffer[i]) {
+            let run = (*last >> RUN_SHIFT) + 1;
+            if *last & KEY_BITS == bits && run < MAX_RUN {
+                *last += 1 << RUN_SHIFT;
+                self.frames += 1;
+                return true;
+            }
+        }
+        let Some(entry) = self.buffer.get_mut(self.len) else {
+            return false;
+        };
+        *entry = bits;
+        self.len += 1;
+        self.frames += 1;
+        true
+    }
+
+    /// The number of frames recorded.
+    #[inline]
+    #[must_use]
+    pub const fn frames(&self) -> u32 {
+        self.frames
+    }
+
+    /// The recording so far, to save or to give to an [`InputPlayback`].
+    #[inline]
+    #[must_use]
+    pub fn recording(&self) -> &[u16] {
+        &self.buffer[..self.len]
+    }
+
+    /// Throws away the recording, to start again.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.len = 0;
+        self.frames = 0;
+    }
+}
+
+/// Plays back a recording made by an [`InputRecorder`], one frame at a time.
+///
+/// Use it in place of reading the keys, falling back to live input when the
+/// recording ends:
+///
+/// ```no_run
+/// # use gba_cell::{keys::Keys, replay::InputPlayback};
+/// # let mut playback = InputPlayback::new(&[]);
+/// let keys = playback.next().unwrap_or_else(Keys::read);
+/// ```
+#[derive(Debug, Clone)]
+pub struct InputPlayback<'a> {
+    recording: &'a [u16],
+    position: usize,
+    used: u16,
+    frame: u32,
+}
+impl<'a> InputPlayback<'a> {
+    /// Starts playing from the beginning of a recording.
+    #[inline]
+    #[must_use]
+    pub const fn new(recording: &'a [u16]) -> Self {
+        Self {
+            recording,
+            position: 0,
+            used: 0,
+            frame: 0,
+        }
+    }
+
+    /// The number of frames played so far.
+    #[inline]
+    #[must_use]
+    pub const fn frame(&self) -> u32 {
+        self.frame
+    }
+
+    /// If every frame has been played.
+    #[inline]
+    #[must_use]
+    pub const fn is_finished(&self) -> bool {
+        self.position >= self.recording.len()
+    }
+}
+impl Iterator for InputPlayback<'_> {
+    type Item = Keys;
+
+    #[inline]
+    fn next(&mut self) -> Option<Keys> {
+        let entry = *self.recording.get(self.position)?;
+        self.used += 1;
+        if self.used > entry >> RUN_SHIFT {
+            self.position += 1;
+            self.used = 0;
+        }
+        self.frame += 1;
+        Some(Keys::from_bits(entry & KEY_BITS))
+    }
+}
+
+/// A frame where the game state didn't match the recording.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Desync {
+    /// The frame number, counting from 0.
+    pub frame: u32,
+    /// The recorded checksum.
+    pub expected: u32,
+    /// The checksum this time.
+    pub actual: u32,
+}
+
+#[derive(Debug)]
+enum Checksums<'a> {
+    Record(&'a mut [u32], usize),
+    Verify(&'a [u32]),
+}
+
+/// Records or checks a checksum of the game state every frame, to find where
+/// a replay stops matching the original run.
+///
+/// The hook is a closure that hashes whatever state the game logic depends
+/// on, such as with [`crc32`](crate::hash::crc32) over the relevant bytes.
+/// Record the checksums alongside an [`InputRecorder`], then verify them while
+/// playing the input back: the first mismatch is the frame where something
+/// other than the input affected the game.
+#[derive(Debug)]
+pub struct StateChecksums<'a, F> {
+    checksums: Checksums<'a>,
+    hash: F,
+    frame: u32,
+    last: Option<u32>,
+    first_desync: Option<Desync>,
+}
+impl<'a, F> StateChecksums<'a, F>
+where
+    F: FnMut() -> u32,
+{
+    /// Records checksums into the buffer.
+    #[inline]
+    #[must_use]
+    pub fn record(buffer: &'a mut [u32], hash: F) -> Self {
+        Self::with(Checksums::Record(buffer, 0), hash)
+    }
+
+    /// Checks checksums against a recording.
+    #[inline]
+    #[must_use]
+    pub fn verify(expected: &'a [u32], hash: F) -> Self {
+        Self::with(Checksums::Verify(expected), hash)
+    }
+
+    #[inline]
+    fn with(checksums: Checksums<'a>, hash: F) -> Self {
+        Self {
+            checksums,
+            hash,
+            frame: 0,
+            last: None,
+            first_desync: None,
+        }
+    }
+
+    /// Runs the hook for the frame that just finished.
+    ///
+    /// When verifying, returns the mismatch if the checksum doesn't match.
+    /// Frames past the end of the buffer or recording aren't recorded or
+    /// checked.
+    pub fn end_frame(&mut self) -> Result<(), Desync> {
+        let actual = (self.hash)();
+        let frame = self.frame;
+        self.frame += 1;
+        self.last = Some(actual);
+        match &mut self.checksums {
+            Checksums::Record(buffer, len) => {
+                if let Some(slot) = buffer.get_mut(*len) {
+                    *slot = actual;
+                    *len += 1;
+                }
+                Ok(())
+            }
+            Checksums::Verify(expected) => match expected.get(frame as usize) {
+                Some(&expected) if expected != actual => {
+                    let desync = Desync {
+                        frame,
+                        expected,
+                        actual,
+                    };
+                    self.first_desync.get_or_insert(desync);
+                    Err(desync)
+                }
+                _ => Ok(()),
+            },
+        }
+    }
+
+    /// The checksum from the last frame, such as to send to a link cable
+    /// partner for comparison.
+    #[inline]
+    #[must_use]
+    pub const fn last(&self) -> Option<u32> {
+        self.last
+    }
+
+    /// The first mismatch found while verifying.
+    #[inline]
+    #[must_use]
+    pub const fn first_desync(&self) -> Option<Desync> {
+        self.first_desync
+    }
+
+    /// The checksums recorded so far. This is empty when verifying.
+    #[inline]
+    #[must_use]
+    pub fn recorded(&self) -> &[u32] {
+        match &self.checksums {
+            Checksums::Record(buffer, len) => &buffer[..*len],
+            Checksums::Verify(_) => &[],
+        }
+    }
+}
@@ -0,0 +1,131 @@
+//! Soft-reset on a key chord, with cleanup hooks.
+//!
+//! Commercial games reset when A+B+Start+Select are held together. A
+//! [`ResetChord`] watches for that chord (or another one) when
+//! [`check`](ResetChord::check) is called, usually from the vblank handler,
+//! and runs the registered cleanup hooks (such as flushing pending save
+//! writes) before calling [`soft_reset`].
+
+use crate::{keys::Keys, GbaCell};
+
+/// Resets the game using the BIOS `SoftReset` function.
+///
+/// This restarts from the ROM entry point (or EWRAM for multiboot programs)
+/// after clearing the top of IWRAM, without clearing the rest of memory.
+///
+/// Interrupts are disabled (`IME` and `IE` cleared) and any pending ones
+/// acknowledged first. `SoftReset` leaves those registers alone, so
+/// otherwise an interrupt could fire into a handler that the restarting game
+/// hasn't set up yet.
+#[inline]
+#[cfg(target_arch = "arm")]
+pub fn soft_reset() -> ! {
+    // SAFETY: `IME`, `IE`, and `IF` are plain registers, and this never
+    // returns to code that expects interrupts to be on.
+    unsafe {
+        crate::mmio::IME.write_volatile(0);
+        crate::mmio::IE.write_volatile(0);
+        crate::mmio::IF.write_volatile(0xFFFF);
+    }
+    // SAFETY: `swi 0x00` is `SoftReset`, which never returns. The comment
+    // field is 0 in both ARM and Thumb encodings, so either state works.
+    unsafe { core::arch::asm!("swi #0x00", options(noreturn)) }
+}
+
+/// Resets the game using the BIOS `SoftReset` function.
+///
+/// There's no BIOS when not running on ARM, so this always panics.
+#[inline]
+#[cfg(not(target_arch = "arm"))]
+#[cfg_attr(feature = "track_caller", track_caller)]
+pub fn soft_reset() -> ! {
+    panic!("SoftReset is only available on the GBA.")
+}
+
+type HookSlot = GbaCell<Option<fn()>>;
+
+/// Watches for a reset key chord, with up to `N` cleanup hooks.
+///
+/// Every part is a [`GbaCell`], so the chord can be changed and hooks added
+/// from the main loop while [`check`](Self::check) runs in an interrupt.
+#[derive(Debug)]
+pub struct ResetChord<const N: usize> {
+    enabled: GbaCell<bool>,
+    chord: GbaCell<Keys>,
+    hooks: [HookSlot; N],
+}
+impl<const N: usize> ResetChord<N> {
+    /// The standard A+B+Start+Select chord.
+    pub const STANDARD: Keys = Keys::from_bits(0b1111);
+
+    /// Constructs a new watcher for the standard chord, with no hooks.
+    ///
+    /// It starts out disabled.
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            enabled: GbaCell::new(false),
+            chord: GbaCell::new(Self::STANDARD),
+            hooks: [const { GbaCell::new(None) }; N],
+        }
+    }
+
+    /// Turns the watcher on or off.
+    ///
+    /// Disable it during save writes or other moments a reset must not
+    /// interrupt.
+    #[inline]
+    pub fn set_enabled(&self, enabled: bool) {
+        self.enabled.write(enabled);
+    }
+
+    /// Sets the chord that triggers the reset.
+    #[inline]
+    pub fn set_chord(&self, chord: Keys) {
+        self.chord.write(chord);
+    }
+
+    /// Adds a cleanup hook. Hooks run in the order they were added.
+    ///
+    /// Returns `false` if all `N` hook slots are already used.
+    pub fn add_cleanup(&self, hook: fn()) -> bool {
+        match self.hooks.iter().find(|h| h.read().is_none()) {
+            Some(slot) => {
+                slot.write(Some(hook));
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Removes every cleanup hook.
+    #[inline]
+    pub fn clear_cleanups(&self) {
+        self.hooks.iter().for_each(|h| h.write(None));
+    }
+
+    /// If the watcher is enabled and `keys` holds the chord, runs the cleanup
+    /// hooks and resets.
+    ///
+    /// Interrupts are turned off and acknowledged just before the reset (see
+    /// [`soft_reset`]), so this is safe to call from an interrupt handler.
+    #[cfg_attr(feature = "track_caller", track_caller)]
+    pub fn check(&self, keys: Keys) {
+        let chord = self.chord.read();
+        if self.enabled.read() && !chord.is_empty() && keys.contains(chord) {
+            self.hooks
+                .iter()
+                .filter_map(GbaCell::read)
+                .for_each(|h| h());
+            soft_reset();
+        }
+    }
+}
+
+impl<const N: usize> Default for ResetChord<N> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
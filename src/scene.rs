@@ -0,0 +1,131 @@
+//! A fixed-capacity stack of game scenes.
+//!
+//! The stack doesn't care what a scene is. Usually it's an `enum` with one
+//! variant per screen (title, gameplay, pause, ...) so that dispatch is a
+//! `match`, but a `&'static mut dyn Trait` works just as well.
+
+/// A change to make to a [`SceneStack`], usually returned by a scene's update.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Transition<S> {
+    /// Keep the current scene.
+    #[default]
+    Stay,
+    /// Pause the current scene and start a new one on top of it.
+    Push(S),
+    /// End the current scene and resume the one below it.
+    Pop,
+    /// End the current scene and start a new one in its place.
+    Replace(S),
+}
+
+/// Holds up to `N` scenes, where only the top scene is active.
+#[derive(Debug, Clone)]
+pub struct SceneStack<S, const N: usize> {
+    scenes: [Option<S>; N],
+    len: usize,
+}
+impl<S, const N: usize> SceneStack<S, N> {
+    /// Makes an empty stack.
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            scenes: [const { None }; N],
+            len: 0,
+        }
+    }
+
+    /// The number of scenes in the stack.
+    #[inline]
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// If the stack has no scenes.
+    #[inline]
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The active scene.
+    #[inline]
+    #[must_use]
+    pub fn top(&self) -> Option<&S> {
+        self.len
+            .checked_sub(1)
+            .and_then(|i| self.scenes[i].as_ref())
+    }
+
+    /// The active scene, mutably.
+    #[inline]
+    #[must_use]
+    pub fn top_mut(&mut self) -> Option<&mut S> {
+        self.len
+            .checked_sub(1)
+            .and_then(|i| self.scenes[i].as_mut())
+    }
+
+    /// Iterates the scenes from the bottom of the stack to the top.
+    ///
+    /// This is handy for drawing paused scenes underneath an overlay.
+    #[inline]
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &S> {
+        self.scenes[..self.len].iter().flatten()
+    }
+
+    /// Puts a new scene on top of the stack.
+    ///
+    /// If the stack is full, the scene is given back as an error.
+    #[inline]
+    pub fn push(&mut self, scene: S) -> Result<(), S> {
+        match self.scenes.get_mut(self.len) {
+            Some(slot) => {
+                *slot = Some(scene);
+                self.len += 1;
+                Ok(())
+            }
+            None => Err(scene),
+        }
+    }
+
+    /// Removes the top scene.
+    #[inline]
+    pub fn pop(&mut self) -> Option<S> {
+        self.len = self.len.checked_sub(1)?;
+        self.scenes[self.len].take()
+    }
+
+    /// Swaps the top scene for a new one, giving back the old scene.
+    ///
+    /// On an empty stack this is the same as [`push`](Self::push).
+    #[inline]
+    pub fn replace(&mut self, scene: S) -> Result<Option<S>, S> {
+        let old = self.pop();
+        self.push(scene).map(|()| old)
+    }
+
+    /// Applies a transition to the stack.
+    ///
+    /// Any scene that was removed is dropped. If a push doesn't fit, the new
+    /// scene is given back as an error and the stack is unchanged.
+    #[inline]
+    pub fn apply(&mut self, transition: Transition<S>) -> Result<(), S> {
+        match transition {
+            Transition::Stay => Ok(()),
+            Transition::Push(scene) => self.push(scene),
+            Transition::Pop => {
+                self.pop();
+                Ok(())
+            }
+            Transition::Replace(scene) => self.replace(scene).map(drop),
+        }
+    }
+}
+impl<S, const N: usize> Default for SceneStack<S, N> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,196 @@
+//! Frame counting and work deferred by a number of frames.
+//!
+//! A [`FrameCounter`] is ticked once per vblank, and a [`Scheduler`] uses its
+//! value to run actions some number of frames in the future, so that things
+//! like "end the screen shake in 20 frames" don't each need their own
+//! countdown.
+
+use crate::GbaCell;
+
+/// Counts frames. Usually ticked by the vblank interrupt handler.
+///
+/// The count wraps after `u32::MAX` frames (a little over 2 years).
+pub struct FrameCounter(GbaCell<u32>);
+impl FrameCounter {
+    /// Constructs a new counter at 0.
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self(GbaCell::new(0))
+    }
+
+    /// Advances the count by one frame.
+    #[inline]
+    pub fn tick(&self) {
+        self.0.write(self.0.read().wrapping_add(1));
+    }
+
+    /// The current frame number.
+    #[inline]
+    #[must_use]
+    pub fn get(&self) -> u32 {
+        self.0.read()
+    }
+}
+
+impl Default for FrameCounter {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Something a [`Scheduler`] can do when a task comes due.
+#[derive(Debug, Clone, Copy)]
+pub enum Action {
+    /// Call the function.
+    Call(fn()),
+    /// Set the flag to `true`.
+    Set(&'static GbaCell<bool>),
+}
+impl Action {
+    #[inline]
+    pub(crate) fn run(self) {
+        match self {
+            Self::Call(f) => f(),
+            Self::Set(flag) => flag.write(true),
+        }
+    }
+}
+
+/// Identifies a task in a [`Scheduler`], so that it can be cancelled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TaskId {
+    slot: u16,
+    generation: u16,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Task {
+    due: u32,
+    action: Action,
+}
+
+/// Runs up to `N` pending actions at given frames.
+///
+/// The scheduler belongs to the main loop, which calls [`run`](Self::run) once
+/// per frame with the current frame number. Checking all `N` slots each frame
+/// is cheaper than fancier timer structures at the sizes a GBA game needs.
+#[derive(Debug, Clone)]
+pub struct Scheduler<const N: usize> {
+    tasks: [Option<Task>; N],
+    generations: [u16; N],
+    now: u32,
+}
+impl<const N: usize> Scheduler<N> {
+    /// Makes a scheduler with no tasks, starting at frame 0.
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            tasks: [None; N],
+            generations: [0; N],
+            now: 0,
+        }
+    }
+
+    /// The frame number given to the last [`run`](Self::run).
+    #[inline]
+    #[must_use]
+    pub const fn now(&self) -> u32 {
+        self.now
+    }
+
+    /// Schedules `action` for `frames` frames after [`now`](Self::now).
+    ///
+    /// An action scheduled for 0 frames runs on the next call to
+    /// [`run`](Self::run). If every slot is in use, the action is given back as
+    /// an error.
+    pub fn after_frames(&mut self, frames: u32, action: Action) -> Result<TaskId, Action> {
+        let Some(slot) = self.tasks.iter().position(Option::is_none) else {
+            return Err(action);
+        };
+        self.tasks[slot] = Some(Task {
+            due: self.now.wrapping_add(frames),
+            action,
+        });
+        Ok(TaskId {
+            slot: slot as u16,
+            generation: self.generations[slot],
+        })
+    }
+
+    /// If the task hasn't run or been cancelled yet.
+    #[inline]
+    #[must_use]
+    pub fn is_pending(&self, id: TaskId) -> bool {
+        let slot = usize::from(id.slot);
+        self.generations.get(slot) == Some(&id.generation) && self.tasks[slot].is_some()
+    }
+
+    /// Cancels a task. Returns if the task was still pending.
+    #[inline]
+    pub fn cancel(&mut self, id: TaskId) -> bool {
+        let pending = self.is_pending(id);
+        if pending {
+            self.free(usize::from(id.slot));
+        }
+        pending
+    }
+
+    /// Cancels every pending task.
+    #[inline]
+    pub fn cancel_all(&mut self) {
+        for slot in 0..N {
+            if self.tasks[slot].is_some() {
+                self.free(slot);
+            }
+        }
+    }
+
+    /// The number of frames after [`now`](Self::now) until the next task is
+    /// due, or `None` if there are no tasks.
+    ///
+    /// A task that's already due (which only happens between scheduling it for
+    /// 0 frames and the next [`run`](Self::run)) counts as 0.
+    #[inline]
+    #[must_use]
+    pub fn next_due(&self) -> Option<u32> {
+        self.tasks
+            .iter()
+            .flatten()
+            .map(|task| (task.due.wrapping_sub(self.now) as i32).max(0) as u32)
+            .min()
+    }
+
+    #[inline]
+    fn free(&mut self, slot: usize) {
+        self.tasks[slot] = None;
+        self.generations[slot] = self.generations[slot].wrapping_add(1);
+    }
+
+    /// Sets the current frame, then runs every task that's due.
+    ///
+    /// Tasks that come due on a frame that was skipped still run, late.
+    pub fn run(&mut self, now: u32) {
+        self.now = now;
+        for slot in 0..N {
+            let Some(task) = self.tasks[slot] else {
+                continue;
+            };
+            // The difference is taken as signed so that this keeps working
+            // when the frame count wraps around.
+            if now.wrapping_sub(task.due) as i32 >= 0 {
+                self.free(slot);
+                task.action.run();
+            }
+        }
+    }
+}
+
+impl<const N: usize> Default for Scheduler<N> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
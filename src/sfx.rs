@@ -0,0 +1,125 @@
+//! Sound effect voice management.
+//!
+//! The [`VoiceAllocator`] decides *which* hardware or mixer voice a sound
+//! effect should play on. It doesn't touch any sound hardware itself: the
+//! caller starts the sound on the voice it gets back, and reports back with
+//! [`VoiceAllocator::release`] when that sound finishes.
+
+/// Identifies a sound effect, usually an index into the game's SFX table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[repr(transparent)]
+pub struct SfxId(pub u16);
+
+// SAFETY: `SfxId` is a `repr(transparent)` newtype over `u16`.
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Zeroable for SfxId {}
+// SAFETY: `SfxId` is a `repr(transparent)` newtype over `u16`.
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Pod for SfxId {}
+
+/// The result of successfully asking for a voice.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Allocation {
+    /// The given voice was idle.
+    Free(usize),
+    /// The given voice was playing the given effect, which must be stopped
+    /// before the new effect is started.
+    Stolen(usize, SfxId),
+}
+impl Allocation {
+    /// The voice index the new effect should be played on.
+    #[inline]
+    #[must_use]
+    pub const fn voice(self) -> usize {
+        match self {
+            Self::Free(v) | Self::Stolen(v, _) => v,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Playing {
+    sfx: SfxId,
+    priority: u8,
+    started: u32,
+}
+
+/// Assigns sound effects to a fixed set of `N` voices by priority.
+///
+/// * When a voice is idle, it's used.
+/// * Otherwise the lowest priority voice is stolen, as long as its priority
+///   isn't higher than the new effect's. Among equal priorities the oldest
+///   effect is stolen.
+/// * An effect that was started less than `retrigger_frames` frames ago won't
+///   be started again, which stops the same sound stacking up when it's
+///   triggered on several consecutive frames.
+///
+/// Time is given as a frame count (usually the vblank counter) passed to
+/// [`play`](Self::play), and is allowed to wrap.
+#[derive(Debug, Clone)]
+pub struct VoiceAllocator<const N: usize> {
+    voices: [Option<Playing>; N],
+    retrigger_frames: u32,
+}
+impl<const N: usize> VoiceAllocator<N> {
+    /// Makes an allocator with all voices idle.
+    #[inline]
+    #[must_use]
+    pub const fn new(retrigger_frames: u32) -> Self {
+        Self {
+            voices: [None; N],
+            retrigger_frames,
+        }
+    }
+
+    /// Picks a voice for `sfx`, or `None` if the effect shouldn't play.
+    pub fn play(&mut self, sfx: SfxId, priority: u8, now: u32) -> Option<Allocation> {
+        let recently_started = self
+            .voices
+            .iter()
+            .flatten()
+            .any(|p| p.sfx == sfx && now.wrapping_sub(p.started) < self.retrigger_frames);
+        if recently_started {
+            return None;
+        }
+        let new = Playing {
+            sfx,
+            priority,
+            started: now,
+        };
+        if let Some(idx) = self.voices.iter().position(Option::is_none) {
+            self.voices[idx] = Some(new);
+            return Some(Allocation::Free(idx));
+        }
+        let (idx, victim) = self
+            .voices
+            .iter()
+            .enumerate()
+            .filter_map(|(i, p)| p.map(|p| (i, p)))
+            .filter(|(_, p)| p.priority <= priority)
+            .min_by_key(|(_, p)| (p.priority, u32::MAX - now.wrapping_sub(p.started)))?;
+        self.voices[idx] = Some(new);
+        Some(Allocation::Stolen(idx, victim.sfx))
+    }
+
+    /// Marks a voice as idle, because its effect finished or was stopped.
+    #[inline]
+    pub fn release(&mut self, voice: usize) {
+        if let Some(v) = self.voices.get_mut(voice) {
+            *v = None;
+        }
+    }
+
+    /// Marks every voice as idle.
+    #[inline]
+    pub fn release_all(&mut self) {
+        self.voices = [None; N];
+    }
+
+    /// The effect currently assigned to a voice, if any.
+    #[inline]
+    #[must_use]
+    pub fn playing(&self, voice: usize) -> Option<SfxId> {
+        self.voices.get(voice).copied().flatten().map(|p| p.sfx)
+    }
+}
@@ -0,0 +1,207 @@
+//! Saving and restoring chosen `static` game state, for quick-save and
+//! quick-resume.
+//!
+//! The [`regions!`](crate::regions) macro builds a list of [`Region`]s from
+//! `static mut` items, and [`save`]/[`restore`] copy their bytes to and from a
+//! buffer (such as one in EWRAM). The `_with` versions take closures for
+//! each byte instead, which suits SRAM since it must be accessed one byte at a
+//! time with volatile operations.
+//!
+//! ```no_run
+//! # use gba_cell::{regions, snapshot::{self, Region}};
+//! static mut PLAYER_XY: [i32; 2] = [0; 2];
+//! static mut LEVEL: u16 = 0;
+//! // SAFETY: both statics can hold any bit pattern.
+//! static STATE: [Region; 2] = unsafe { regions![PLAYER_XY, LEVEL] };
+//!
+//! let mut buffer = [0_u8; 32];
+//! let used = snapshot::save(&STATE, &mut buffer).unwrap();
+//! snapshot::restore(&STATE, &buffer[..used]).unwrap();
+//! ```
+//!
+//! Snapshots start with a 12 byte header: the magic bytes `GSNP`, the
+//! length of the data, and a [`crc32`](crate::hash::crc32) of the data. A
+//! snapshot is only restored if the length matches the regions and the
+//! checksum is correct, so a blank or corrupted save is rejected rather than
+//! loaded.
+
+use crate::hash::Crc32;
+
+const MAGIC: [u8; 4] = *b"GSNP";
+const HEADER_LEN: usize = 12;
+
+/// A span of memory that's part of a snapshot.
+#[derive(Debug, Clone, Copy)]
+pub struct Region {
+    ptr: *mut u8,
+    len: usize,
+}
+
+// SAFETY: A region is just an address. The requirements of `Region::new`
+// cover using it.
+unsafe impl Sync for Region {}
+
+impl Region {
+    /// Describes the `len` bytes starting at `ptr`.
+    ///
+    /// ## Safety
+    /// * The memory must stay valid for reads and writes for as long as the
+    ///   region is used.
+    /// * Any bit pattern must be valid for the memory. Snapshots are
+    ///   checksummed, but a snapshot saved by a different build of the game
+    ///   can still pass the check with a different meaning.
+    /// * The memory must not be accessed by other code (including interrupt
+    ///   handlers) during [`save`] or [`restore`].
+    #[inline]
+    #[must_use]
+    pub const unsafe fn new(ptr: *mut u8, len: usize) -> Self {
+        Self { ptr, len }
+    }
+
+    /// Describes all of the value that `ptr` points to.
+    ///
+    /// ## Safety
+    /// * The same as for [`Region::new`].
+    #[inline]
+    #[must_use]
+    pub const unsafe fn of<T>(ptr: *mut T) -> Self {
+        Self {
+            ptr: ptr.cast(),
+            len: core::mem::size_of::<T>(),
+        }
+    }
+
+    /// The size of the region in bytes.
+    #[inline]
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// If the region has no bytes.
+    #[inline]
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+/// Makes an array of [`Region`]s covering the given `static mut` items.
+///
+/// This calls [`Region::of`], so it must be used in an `unsafe` block, and
+/// the same safety rules apply to each item.
+#[macro_export]
+macro_rules! regions {
+    ($($item:path),* $(,)?) => {
+        [$($crate::snapshot::Region::of(::core::ptr::addr_of_mut!($item))),*]
+    };
+}
+
+/// The ways that saving or restoring a snapshot can fail.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SnapshotError {
+    /// The buffer is too small to hold the snapshot.
+    BufferTooSmall,
+    /// There's no snapshot header.
+    BadHeader,
+    /// The snapshot's size doesn't match the regions.
+    SizeMismatch,
+    /// The snapshot data doesn't match its checksum.
+    BadChecksum,
+}
+
+/// The number of bytes a snapshot of the regions takes, including the header.
+#[inline]
+#[must_use]
+pub fn snapshot_len(regions: &[Region]) -> usize {
+    HEADER_LEN + regions.iter().map(Region::len).sum::<usize>()
+}
+
+#[inline]
+fn region_bytes(regions: &[Region]) -> impl Iterator<Item = *mut u8> + '_ {
+    regions
+        .iter()
+        .flat_map(|r| (0..r.len).map(move |i| r.ptr.wrapping_add(i)))
+}
+
+/// Saves the regions, calling `write(offset, byte)` for each byte.
+///
+/// Returns the number of bytes written, which is always
+/// [`snapshot_len`]`(regions)`.
+pub fn save_with<W>(regions: &[Region], mut write: W) -> usize
+where
+    W: FnMut(usize, u8),
+{
+    let mut crc = Crc32::new();
+    let mut len = 0_usize;
+    for p in region_bytes(regions) {
+        // SAFETY: `Region::new` requires the memory to be readable.
+        let b = unsafe { p.read_volatile() };
+        crc.update(&[b]);
+        write(HEADER_LEN + len, b);
+        len += 1;
+    }
+    let header = MAGIC
+        .into_iter()
+        .chain((len as u32).to_le_bytes())
+        .chain(crc.finish().to_le_bytes());
+    for (i, b) in header.enumerate() {
+        write(i, b);
+    }
+    HEADER_LEN + len
+}
+
+/// Saves the regions into `buffer`, returning the number of bytes used.
+pub fn save(regions: &[Region], buffer: &mut [u8]) -> Result<usize, SnapshotError> {
+    if buffer.len() < snapshot_len(regions) {
+        return Err(SnapshotError::BufferTooSmall);
+    }
+    Ok(save_with(regions, |i, b| buffer[i] = b))
+}
+
+/// Restores the regions, calling `read(offset)` to get each byte.
+///
+/// The snapshot is checked completely before any region is changed, so on
+/// error the regions are untouched.
+pub fn restore_with<R>(regions: &[Region], mut read: R) -> Result<(), SnapshotError>
+where
+    R: FnMut(usize) -> u8,
+{
+    let mut header = [0_u8; HEADER_LEN];
+    for (i, b) in header.iter_mut().enumerate() {
+        *b = read(i);
+    }
+    let word =
+        |i: usize| u32::from_le_bytes([header[i], header[i + 1], header[i + 2], header[i + 3]]);
+    if header[..4] != MAGIC {
+        return Err(SnapshotError::BadHeader);
+    }
+    let len = word(4) as usize;
+    if len != snapshot_len(regions) - HEADER_LEN {
+        return Err(SnapshotError::SizeMismatch);
+    }
+    let mut crc = Crc32::new();
+    for i in 0..len {
+        crc.update(&[read(HEADER_LEN + i)]);
+    }
+    if crc.finish() != word(8) {
+        return Err(SnapshotError::BadChecksum);
+    }
+    for (i, p) in region_bytes(regions).enumerate() {
+        // SAFETY: `Region::new` requires the memory to be writable with any
+        // byte values.
+        unsafe { p.write_volatile(read(HEADER_LEN + i)) };
+    }
+    Ok(())
+}
+
+/// Restores the regions from a snapshot in `buffer`.
+pub fn restore(regions: &[Region], buffer: &[u8]) -> Result<(), SnapshotError> {
+    if buffer.len() < HEADER_LEN {
+        return Err(SnapshotError::BadHeader);
+    }
+    if buffer.len() < snapshot_len(regions) {
+        return Err(SnapshotError::SizeMismatch);
+    }
+    restore_with(regions, |i| buffer[i])
+}
@@ -0,0 +1,237 @@
+//! Loading assets a little at a time, so level transitions don't hitch.
+//!
+//! An [`AssetLoader`] holds a queue of [`Load`]s, and the main loop calls
+//! [`run`](AssetLoader::run) once per frame with however many scanlines it
+//! can spare (such as from [`IdleMeter`](crate::power::IdleMeter)). Plain
+//! copies go through DMA 3, and LZ77 data is unpacked with an
+//! [`Lz77Stream`], which writes VRAM safely. Both stop at the end of the
+//! budget and carry on next frame. When a load finishes, its
+//! [`Action`] runs, the same as a [`Scheduler`](crate::schedule::Scheduler)
+//! task coming due.
+//!
+//! ```no_run
+//! # use gba_cell::schedule::Action;
+//! # use gba_cell::streaming::{AssetLoader, Load};
+//! # use gba_cell::GbaCell;
+//! # fn wait_for_vblank() {}
+//! # static LEVEL_2_TILES: [u8; 4] = [0x10, 0, 0, 0];
+//! static TILES_READY: GbaCell<bool> = GbaCell::new(false);
+//!
+//! // SAFETY: nothing else uses this part of VRAM until `TILES_READY` is set.
+//! let vram = unsafe { core::slice::from_raw_parts_mut(0x0600_8000 as *mut u16, 0x2000) };
+//! let mut loader = AssetLoader::<4>::new();
+//! loader
+//!     .push(Load::Lz77 { src: &LEVEL_2_TILES, dst: vram }, Some(Action::Set(&TILES_READY)))
+//!     .unwrap();
+//! loop {
+//!     wait_for_vblank();
+//!     // Game logic here, then spend 20 scanlines on loading.
+//!     loader.run(20).unwrap();
+//! }
+//! ```
+
+use crate::display::{self, LINES_PER_FRAME};
+use crate::lz77::{Lz77Error, Lz77Stream};
+use crate::mmio;
+use crate::schedule::Action;
+
+/// The most words copied between budget checks.
+const COPY_CHUNK: usize = 256;
+
+/// The most bytes decompressed between budget checks.
+const LZ77_CHUNK: usize = 128;
+
+/// Something for an [`AssetLoader`] to do.
+#[derive(Debug)]
+pub enum Load {
+    /// Copies words with DMA 3, such as uncompressed tiles from ROM. Copies
+    /// as many words as the shorter of the two slices has.
+    Raw {
+        /// The words to copy.
+        src: &'static [u32],
+        /// Where to copy them.
+        dst: &'static mut [u32],
+    },
+    /// Decompresses LZ77 data, using [`Lz77Stream::decompress_u16`] so that
+    /// `dst` can be in VRAM.
+    Lz77 {
+        /// The LZ77 data, header included.
+        src: &'static [u8],
+        /// Where to decompress it, which must be big enough for all of it.
+        dst: &'static mut [u16],
+    },
+}
+
+/// A load that has been started.
+#[derive(Debug)]
+enum Work {
+    Raw {
+        src: &'static [u32],
+        dst: &'static mut [u32],
+        done: usize,
+    },
+    Lz77 {
+        stream: Lz77Stream<'static>,
+        dst: &'static mut [u16],
+    },
+}
+impl Work {
+    #[inline]
+    fn start(load: Load) -> Result<Self, Lz77Error> {
+        Ok(match load {
+            Load::Raw { src, dst } => Self::Raw { src, dst, done: 0 },
+            Load::Lz77 { src, dst } => Self::Lz77 {
+                stream: Lz77Stream::new(src)?,
+                dst,
+            },
+        })
+    }
+
+    /// Does about one chunk of the load, returning `true` once it's all done.
+    fn step(&mut self) -> Result<bool, Lz77Error> {
+        match self {
+            Self::Raw { src, dst, done } => {
+                let total = src.len().min(dst.len());
+                let count = COPY_CHUNK.min(total - *done);
+                if count > 0 {
+                    let from = &src[*done..*done + count];
+                    let to = &mut dst[*done..*done + count];
+                    // SAFETY: both slices hold `count` words and are word
+                    // aligned. The transfer is started immediately, with 32
+                    // bit units and both addresses incrementing, and this
+                    // waits for it to finish before `to` is used again.
+                    unsafe {
+                        mmio::DMA3SAD.write_volatile(from.as_ptr() as u32);
+                        mmio::DMA3DAD.write_volatile(to.as_mut_ptr() as u32);
+                        mmio::DMA3CNT_L.write_volatile(count as u16);
+                        mmio::DMA3CNT_H.write_volatile(1 << 15 | 1 << 10);
+                        while mmio::DMA3CNT_H.read_volatile() & (1 << 15) != 0 {}
+                    }
+                    *done += count;
+                }
+                Ok(*done == total)
+            }
+            Self::Lz77 { stream, dst } => {
+                stream.decompress_u16(dst, LZ77_CHUNK)?;
+                Ok(stream.is_done())
+            }
+        }
+    }
+}
+
+/// Runs up to `N` queued [`Load`]s in order, a piece each frame.
+#[derive(Debug)]
+pub struct AssetLoader<const N: usize> {
+    current: Option<(Work, Option<Action>)>,
+    queue: [Option<(Load, Option<Action>)>; N],
+    len: usize,
+}
+impl<const N: usize> AssetLoader<N> {
+    /// Makes a loader with nothing queued.
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            current: None,
+            queue: [const { None }; N],
+            len: 0,
+        }
+    }
+
+    /// The number of loads waiting, including one that's partly done.
+    #[inline]
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.len + self.current.is_some() as usize
+    }
+
+    /// If every load has finished.
+    #[inline]
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Queues a load, with an action to run when it finishes.
+    ///
+    /// If the queue is full, the load is given back as an error.
+    #[inline]
+    pub fn push(&mut self, load: Load, done: Option<Action>) -> Result<(), Load> {
+        match self.queue.get_mut(self.len) {
+            Some(slot) => {
+                *slot = Some((load, done));
+                self.len += 1;
+                Ok(())
+            }
+            None => Err(load),
+        }
+    }
+
+    /// Forgets every load, including one that's partly done, without running
+    /// their actions.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.current = None;
+        self.queue = [const { None }; N];
+        self.len = 0;
+    }
+
+    /// Works through the queue until `lines` scanlines have passed, or it's
+    /// empty.
+    ///
+    /// Time is measured with `VCOUNT`, and checked after each piece of work
+    /// (256 words of copying or 128 bytes of decompression), so this can run
+    /// a little over. A budget of 228 or more scanlines is cut to 227, since
+    /// a whole frame can't be told apart from no time at all.
+    ///
+    /// If a load's LZ77 data turns out to be bad, that load is dropped
+    /// without running its action, and the error is returned. The rest of
+    /// the queue is kept.
+    pub fn run(&mut self, lines: u16) -> Result<(), Lz77Error> {
+        let budget = lines.min(LINES_PER_FRAME - 1);
+        let start = display::vcount();
+        let elapsed = || (display::vcount() + LINES_PER_FRAME - start) % LINES_PER_FRAME;
+        while elapsed() < budget {
+            if self.current.is_none() {
+                let Some((load, done)) = self.pop_front() else {
+                    break;
+                };
+                self.current = Some((Work::start(load)?, done));
+            }
+            let Some((work, _)) = self.current.as_mut() else {
+                break;
+            };
+            match work.step() {
+                Ok(false) => (),
+                Ok(true) => {
+                    if let Some((_, Some(action))) = self.current.take() {
+                        action.run();
+                    }
+                }
+                Err(e) => {
+                    self.current = None;
+                    return Err(e);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Takes the next load off the queue.
+    fn pop_front(&mut self) -> Option<(Load, Option<Action>)> {
+        if self.len == 0 {
+            return None;
+        }
+        let front = self.queue[0].take();
+        self.queue[..self.len].rotate_left(1);
+        self.len -= 1;
+        front
+    }
+}
+
+impl<const N: usize> Default for AssetLoader<N> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
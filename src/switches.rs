@@ -0,0 +1,193 @@
+//! Named runtime "debug switches".
+//!
+//! Each [`Switch`] is a named [`GbaCell<u8>`] that code can check cheaply, and
+//! a [`SwitchMenu`] lets the switches be flipped on hardware with the keys,
+//! so features can be turned on and off without rebuilding.
+//!
+//! ```
+//! # use gba_cell::switches::Switch;
+//! static SHOW_HITBOXES: Switch = Switch::flag("show hitboxes");
+//! static SPAWN_RATE: Switch = Switch::level("spawn rate", 3);
+//! static SWITCHES: [&Switch; 2] = [&SHOW_HITBOXES, &SPAWN_RATE];
+//! ```
+
+use crate::{keys::Keys, GbaCell};
+
+/// A named value from `0` to some maximum, stored in a [`GbaCell`].
+#[derive(Debug)]
+pub struct Switch {
+    name: &'static str,
+    max: u8,
+    value: GbaCell<u8>,
+}
+impl Switch {
+    /// Constructs an on/off switch, starting off.
+    #[inline]
+    #[must_use]
+    pub const fn flag(name: &'static str) -> Self {
+        Self::level(name, 1)
+    }
+
+    /// Constructs a switch with values `0..=max`, starting at 0.
+    #[inline]
+    #[must_use]
+    pub const fn level(name: &'static str, max: u8) -> Self {
+        Self {
+            name,
+            max,
+            value: GbaCell::new(0),
+        }
+    }
+
+    /// The switch's name.
+    #[inline]
+    #[must_use]
+    pub const fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// The largest value the switch can hold.
+    #[inline]
+    #[must_use]
+    pub const fn max(&self) -> u8 {
+        self.max
+    }
+
+    /// The current value.
+    #[inline]
+    #[must_use]
+    pub fn get(&self) -> u8 {
+        self.value.read()
+    }
+
+    /// If the current value isn't 0.
+    #[inline]
+    #[must_use]
+    pub fn is_on(&self) -> bool {
+        self.get() != 0
+    }
+
+    /// Sets the value, clamped to the maximum.
+    #[inline]
+    pub fn set(&self, value: u8) {
+        self.value.write(value.min(self.max));
+    }
+
+    /// Moves to the next value, wrapping from the maximum back to 0.
+    #[inline]
+    pub fn cycle_up(&self) {
+        let v = self.get();
+        self.value.write(if v >= self.max { 0 } else { v + 1 });
+    }
+
+    /// Moves to the previous value, wrapping from 0 to the maximum.
+    #[inline]
+    pub fn cycle_down(&self) {
+        let v = self.get();
+        self.value.write(if v == 0 || v > self.max {
+            self.max
+        } else {
+            v - 1
+        });
+    }
+}
+
+/// Key-driven navigation over a list of switches.
+///
+/// The menu only tracks state; drawing it is up to the game, using
+/// [`entries`](Self::entries). Call [`update`](Self::update) once per frame
+/// with the held keys:
+///
+/// * The open chord (L+R+Select by default) opens and closes the menu.
+/// * While open, Up and Down move the cursor, and Right/A and Left/B change
+///   the selected switch.
+#[derive(Debug)]
+pub struct SwitchMenu<'a> {
+    switches: &'a [&'a Switch],
+    open_chord: Keys,
+    cursor: usize,
+    open: bool,
+    previous: Keys,
+}
+impl<'a> SwitchMenu<'a> {
+    /// Makes a closed menu over the given switches.
+    #[inline]
+    #[must_use]
+    pub const fn new(switches: &'a [&'a Switch]) -> Self {
+        Self {
+            switches,
+            open_chord: Keys::from_bits(0b11_0000_0100),
+            cursor: 0,
+            open: false,
+            previous: Keys::NONE,
+        }
+    }
+
+    /// Sets the key chord that opens and closes the menu.
+    #[inline]
+    #[must_use]
+    pub const fn with_open_chord(mut self, chord: Keys) -> Self {
+        self.open_chord = chord;
+        self
+    }
+
+    /// If the menu is open. Games will usually pause while it is.
+    #[inline]
+    #[must_use]
+    pub const fn is_open(&self) -> bool {
+        self.open
+    }
+
+    /// The index of the selected switch.
+    #[inline]
+    #[must_use]
+    pub const fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    /// Handles one frame of input.
+    pub fn update(&mut self, keys: Keys) {
+        let pressed = keys.newly_pressed(self.previous);
+        let chord_completed =
+            keys.contains(self.open_chord) && !self.previous.contains(self.open_chord);
+        self.previous = keys;
+        if chord_completed {
+            self.open = !self.open;
+            return;
+        }
+        if !self.open || self.switches.is_empty() {
+            return;
+        }
+        let last = self.switches.len() - 1;
+        if pressed.up() {
+            self.cursor = if self.cursor == 0 {
+                last
+            } else {
+                self.cursor - 1
+            };
+        }
+        if pressed.down() {
+            self.cursor = if self.cursor >= last {
+                0
+            } else {
+                self.cursor + 1
+            };
+        }
+        let selected = self.switches[self.cursor.min(last)];
+        if pressed.right() || pressed.a() {
+            selected.cycle_up();
+        }
+        if pressed.left() || pressed.b() {
+            selected.cycle_down();
+        }
+    }
+
+    /// Iterates the switches for drawing, with whether each one is selected.
+    #[inline]
+    pub fn entries(&self) -> impl Iterator<Item = (&'a Switch, bool)> + '_ {
+        self.switches
+            .iter()
+            .enumerate()
+            .map(|(i, s)| (*s, i == self.cursor))
+    }
+}
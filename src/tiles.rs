@@ -0,0 +1,206 @@
+//! Tile flipping and deduplication, for asset pipelines and generated maps.
+//!
+//! A tile is given as its `u32` words in VRAM order: `[u32; 8]` for a 4bpp
+//! tile (one word per row), or `[u32; 16]` for an 8bpp tile (two words per
+//! row). Everything here is a `const fn`, so it can run at compile time on
+//! data from `include_bytes!`, or at runtime on procedurally built tiles.
+//!
+//! Deduplication produces the unique tiles plus a screenblock entry for each
+//! input tile, using the entry's flip bits when a tile matches a mirrored copy
+//! of an earlier one. Backgrounds can flip tiles for free, so this often saves
+//! a lot of VRAM on symmetric art.
+
+/// The horizontal flip bit of a screenblock entry.
+pub const HFLIP: u16 = 1 << 10;
+/// The vertical flip bit of a screenblock entry.
+pub const VFLIP: u16 = 1 << 11;
+
+/// The words per row for a tile of `N` words.
+#[inline]
+#[cfg_attr(feature = "track_caller", track_caller)]
+const fn row_words<const N: usize>() -> usize {
+    assert!(
+        N == 8 || N == 16,
+        "Tiles must be 8 (4bpp) or 16 (8bpp) words."
+    );
+    N / 8
+}
+
+/// Mirrors a tile left to right.
+///
+/// ## Panics
+/// * If `N` isn't 8 or 16.
+#[must_use]
+#[cfg_attr(feature = "track_caller", track_caller)]
+pub const fn flip_h<const N: usize>(tile: &[u32; N]) -> [u32; N] {
+    let mut out = [0; N];
+    let mut i = 0;
+    if row_words::<N>() == 1 {
+        // Reverse the eight nibbles of each row.
+        while i < N {
+            let w = tile[i];
+            let w = w.rotate_left(16);
+            let w = ((w >> 8) & 0x00FF_00FF) | ((w << 8) & 0xFF00_FF00);
+            out[i] = ((w >> 4) & 0x0F0F_0F0F) | ((w << 4) & 0xF0F0_F0F0);
+            i += 1;
+        }
+    } else {
+        // Reverse the eight bytes of each row, which swaps its two words.
+        while i < N {
+            out[i] = tile[i + 1].swap_bytes();
+            out[i + 1] = tile[i].swap_bytes();
+            i += 2;
+        }
+    }
+    out
+}
+
+/// Mirrors a tile top to bottom.
+///
+/// ## Panics
+/// * If `N` isn't 8 or 16.
+#[must_use]
+#[cfg_attr(feature = "track_caller", track_caller)]
+pub const fn flip_v<const N: usize>(tile: &[u32; N]) -> [u32; N] {
+    let per_row = row_words::<N>();
+    let mut out = [0; N];
+    let mut row = 0;
+    while row < 8 {
+        let mut w = 0;
+        while w < per_row {
+            out[(7 - row) * per_row + w] = tile[row * per_row + w];
+            w += 1;
+        }
+        row += 1;
+    }
+    out
+}
+
+#[inline]
+const fn same<const N: usize>(a: &[u32; N], b: &[u32; N]) -> bool {
+    let mut i = 0;
+    while i < N {
+        if a[i] != b[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// Finds `tile` in `unique`, possibly flipped, returning the screenblock
+/// entry (tile index and flip bits) that draws it.
+#[must_use]
+#[cfg_attr(feature = "track_caller", track_caller)]
+pub const fn find<const N: usize>(unique: &[[u32; N]], tile: &[u32; N]) -> Option<u16> {
+    let h = flip_h(tile);
+    let v = flip_v(tile);
+    let hv = flip_v(&h);
+    let mut i = 0;
+    while i < unique.len() && i < 1024 {
+        let u = &unique[i];
+        let flips = if same(u, tile) {
+            Some(0)
+        } else if same(u, &h) {
+            Some(HFLIP)
+        } else if same(u, &v) {
+            Some(VFLIP)
+        } else if same(u, &hv) {
+            Some(HFLIP | VFLIP)
+        } else {
+            None
+        };
+        if let Some(flips) = flips {
+            return Some(i as u16 | flips);
+        }
+        i += 1;
+    }
+    None
+}
+
+/// Deduplicates `tiles` into `unique`, writing a screenblock entry for each
+/// tile into `map`, and returns how many unique tiles there are.
+///
+/// The entries use tile indexes from 0. Add the charblock offset of where the
+/// unique tiles are loaded (and any palette bits) before using them.
+///
+/// Returns `None` if `unique` fills up, if there are more than 1024 unique
+/// tiles (which screenblock entries can't index), or if `map` is shorter than
+/// `tiles`.
+#[must_use]
+#[cfg_attr(feature = "track_caller", track_caller)]
+pub const fn dedup_into<const N: usize>(
+    tiles: &[[u32; N]],
+    unique: &mut [[u32; N]],
+    map: &mut [u16],
+) -> Option<usize> {
+    if map.len() < tiles.len() {
+        return None;
+    }
+    let mut count = 0;
+    let mut i = 0;
+    while i < tiles.len() {
+        let (found, _) = unique.split_at(count);
+        map[i] = match find(found, &tiles[i]) {
+            Some(entry) => entry,
+            None => {
+                if count == unique.len() || count == 1024 {
+                    return None;
+                }
+                unique[count] = tiles[i];
+                count += 1;
+                (count - 1) as u16
+            }
+        };
+        i += 1;
+    }
+    Some(count)
+}
+
+/// The result of [`dedup`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Deduped<const N: usize, const COUNT: usize> {
+    /// The unique tiles, followed by unused zeroed tiles.
+    pub tiles: [[u32; N]; COUNT],
+    /// How many of `tiles` are unique tiles.
+    pub unique: usize,
+    /// The screenblock entry for each input tile.
+    pub map: [u16; COUNT],
+}
+impl<const N: usize, const COUNT: usize> Deduped<N, COUNT> {
+    /// Just the unique tiles.
+    #[inline]
+    #[must_use]
+    pub fn unique_tiles(&self) -> &[[u32; N]] {
+        &self.tiles[..self.unique]
+    }
+}
+
+/// Deduplicates an array of tiles, usually in a `const`.
+///
+/// ```
+/// # use gba_cell::tiles::{dedup, flip_h, Deduped, HFLIP};
+/// const A: [u32; 8] = [0x1234_5678; 8];
+/// const TILES: Deduped<8, 3> = dedup(&[A, flip_h(&A), A]);
+/// assert_eq!(TILES.unique, 1);
+/// assert_eq!(TILES.map, [0, HFLIP, 0]);
+/// ```
+///
+/// ## Panics
+/// * If there are more than 1024 unique tiles.
+#[must_use]
+#[cfg_attr(feature = "track_caller", track_caller)]
+pub const fn dedup<const N: usize, const COUNT: usize>(
+    tiles: &[[u32; N]; COUNT],
+) -> Deduped<N, COUNT> {
+    let mut out = Deduped {
+        tiles: [[0; N]; COUNT],
+        unique: 0,
+        map: [0; COUNT],
+    };
+    match dedup_into(tiles, &mut out.tiles, &mut out.map) {
+        Some(unique) => out.unique = unique,
+        None => panic!("More than 1024 unique tiles."),
+    }
+    out
+}
@@ -0,0 +1,160 @@
+//! Frame-counting timers for game logic.
+//!
+//! A [`Cooldown`] counts down to ready and a [`Stopwatch`] counts up. Both are
+//! a single `u32` of 8.8 fixed-point frames, so they fit in a [`GbaCell`]
+//! (read, tick, write back), and can be ticked by fractions of a frame. That
+//! lets timings tuned at one rate run at another: ticking by `213` (50/60 of
+//! a frame) each frame makes 50 Hz timings take the same time at 60 Hz.
+//!
+//! The GBA actually refreshes at about 59.73 Hz, which the millisecond
+//! conversions account for.
+//!
+//! [`GbaCell`]: crate::GbaCell
+
+use crate::GbaCellSafe;
+
+/// One frame in 8.8 fixed-point.
+const FRAME: u32 = 256;
+
+/// Converts milliseconds to whole frames, rounded to nearest.
+#[inline]
+const fn millis_to_frames(ms: u32) -> u32 {
+    // 59.7275 frames per second, as 16.16 frames per millisecond.
+    ((ms as u64 * 3914 + 0x8000) >> 16) as u32
+}
+
+/// A countdown that becomes ready after some number of frames, such as the
+/// delay between shots.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[repr(transparent)]
+pub struct Cooldown(u32);
+impl Cooldown {
+    /// A cooldown that's already ready.
+    pub const READY: Self = Self(0);
+
+    /// Makes a cooldown that's ready after `frames` ticks.
+    #[inline]
+    #[must_use]
+    pub const fn new(frames: u16) -> Self {
+        Self(frames as u32 * FRAME)
+    }
+
+    /// Makes a cooldown that's ready after about `ms` milliseconds.
+    #[inline]
+    #[must_use]
+    pub const fn from_millis(ms: u16) -> Self {
+        Self(millis_to_frames(ms as u32) * FRAME)
+    }
+
+    /// Counts down one frame, returning if the cooldown is ready.
+    #[inline]
+    pub fn tick(&mut self) -> bool {
+        self.tick_by(FRAME as u16)
+    }
+
+    /// Counts down by an 8.8 fixed-point number of frames, returning if the
+    /// cooldown is ready.
+    #[inline]
+    pub fn tick_by(&mut self, step: u16) -> bool {
+        self.0 = self.0.saturating_sub(step as u32);
+        self.ready()
+    }
+
+    /// If the countdown has finished.
+    #[inline]
+    #[must_use]
+    pub const fn ready(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Starts counting down again from `frames`.
+    #[inline]
+    pub fn reset(&mut self, frames: u16) {
+        *self = Self::new(frames);
+    }
+
+    /// The whole frames left, rounded up.
+    #[inline]
+    #[must_use]
+    pub const fn remaining(self) -> u16 {
+        self.0.div_ceil(FRAME) as u16
+    }
+}
+
+/// A count of frames since it was started, such as the time spent on a level.
+///
+/// It stops counting after about 78 hours, rather than wrapping.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+#[repr(transparent)]
+pub struct Stopwatch(u32);
+impl Stopwatch {
+    /// Makes a stopwatch at zero.
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self(0)
+    }
+
+    /// Counts one frame.
+    #[inline]
+    pub fn tick(&mut self) {
+        self.tick_by(FRAME as u16);
+    }
+
+    /// Counts an 8.8 fixed-point number of frames.
+    #[inline]
+    pub fn tick_by(&mut self, step: u16) {
+        self.0 = self.0.saturating_add(step as u32);
+    }
+
+    /// Sets the count back to zero.
+    #[inline]
+    pub fn reset(&mut self) {
+        self.0 = 0;
+    }
+
+    /// The whole frames counted, rounded down.
+    #[inline]
+    #[must_use]
+    pub const fn frames(self) -> u32 {
+        self.0 / FRAME
+    }
+
+    /// The time counted in milliseconds, rounded down.
+    #[inline]
+    #[must_use]
+    pub const fn millis(self) -> u32 {
+        // 16.7427 milliseconds per frame, as 16.16 with the 8.8 frames.
+        ((self.0 as u64 * 1_097_250) >> 24) as u32
+    }
+
+    /// If at least `frames` frames have been counted.
+    #[inline]
+    #[must_use]
+    pub const fn has_elapsed(self, frames: u32) -> bool {
+        self.frames() >= frames
+    }
+}
+
+// SAFETY: `Cooldown` is a `repr(transparent)` newtype over `u32`.
+unsafe impl GbaCellSafe for Cooldown {}
+// SAFETY: `Stopwatch` is a `repr(transparent)` newtype over `u32`.
+unsafe impl GbaCellSafe for Stopwatch {}
+
+// SAFETY: `Cooldown` is a `repr(transparent)` newtype over `u32`, every bit
+// pattern is a valid time left, and zero is `Cooldown::READY`.
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Zeroable for Cooldown {}
+
+// SAFETY: as above.
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Pod for Cooldown {}
+
+// SAFETY: `Stopwatch` is a `repr(transparent)` newtype over `u32`, every bit
+// pattern is a valid elapsed time, and zero is a fresh stopwatch.
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Zeroable for Stopwatch {}
+
+// SAFETY: as above.
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Pod for Stopwatch {}
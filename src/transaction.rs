@@ -0,0 +1,80 @@
+//! Provides [`transaction`], for updating several cells at once.
+
+use crate::{mmio, GbaCell};
+use core::marker::PhantomData;
+use core::sync::atomic::{compiler_fence, Ordering};
+
+/// If the [`IrqToken`] has been taken.
+static TOKEN_TAKEN: GbaCell<bool> = GbaCell::new(false);
+
+/// Permission to run a [`transaction`].
+///
+/// There's only one token, and `transaction` borrows it mutably for as long
+/// as it runs. The closure can't use it too, so transactions can't be nested,
+/// which is checked at compile time. The token can't be sent to another
+/// thread either, which keeps it out of IRQ handlers.
+///
+/// Take it once at startup, and pass it by `&mut` to whatever needs to run
+/// transactions.
+#[derive(Debug)]
+pub struct IrqToken(PhantomData<*const ()>);
+impl IrqToken {
+    /// Takes the token, or returns `None` if it's already been taken.
+    #[must_use]
+    pub fn take() -> Option<Self> {
+        // SAFETY: `IME` is a plain read/write register. Interrupts are held
+        // off so that an IRQ handler can't take the token in between the read
+        // and the write.
+        let ime = unsafe { mmio::IME.read_volatile() };
+        unsafe { mmio::IME.write_volatile(0) };
+        let taken = TOKEN_TAKEN.read();
+        TOKEN_TAKEN.write(true);
+        unsafe { mmio::IME.write_volatile(ime) };
+        (!taken).then_some(Self(PhantomData))
+    }
+}
+
+/// Proof that interrupts are masked, given to the closure of a
+/// [`transaction`].
+///
+/// A function that must only run with interrupts masked can take a
+/// `&Masked` parameter, so callers can't forget the transaction. It can't be
+/// made any other way, sent to another thread, or kept after the transaction.
+#[derive(Debug)]
+pub struct Masked<'a>(PhantomData<*const &'a ()>);
+
+/// Runs `f` with interrupts masked (`IME` off), so no IRQ handler sees the
+/// [`GbaCell`](crate::GbaCell)s it writes half updated.
+///
+/// This is for values that go together but can't be packed into one cell,
+/// such as an x and y scroll. Keep the closure short, since interrupts that
+/// happen meanwhile are delayed until it ends.
+///
+/// ```no_run
+/// # use gba_cell::{transaction, transaction::IrqToken, GbaCell};
+/// static SCROLL_X: GbaCell<u16> = GbaCell::new(0);
+/// static SCROLL_Y: GbaCell<u16> = GbaCell::new(0);
+///
+/// let mut token = IrqToken::take().unwrap();
+/// transaction(&mut token, |_| {
+///     SCROLL_X.write(12);
+///     SCROLL_Y.write(34);
+/// });
+/// ```
+///
+/// Transactions can't be nested, since the closure can't use the
+/// [`IrqToken`]. `IME` is put back the way it was afterwards, rather than
+/// turned on, so a transaction started with interrupts already off doesn't
+/// unmask them.
+#[inline]
+pub fn transaction<R>(_token: &mut IrqToken, f: impl FnOnce(&Masked<'_>) -> R) -> R {
+    // SAFETY: `IME` is a plain read/write register.
+    let ime = unsafe { mmio::IME.read_volatile() };
+    unsafe { mmio::IME.write_volatile(0) };
+    // Keep the closure's memory accesses between the two `IME` writes.
+    compiler_fence(Ordering::SeqCst);
+    let result = f(&Masked(PhantomData));
+    compiler_fence(Ordering::SeqCst);
+    unsafe { mmio::IME.write_volatile(ime) };
+    result
+}
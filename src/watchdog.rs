@@ -0,0 +1,194 @@
+//! A software watchdog for catching hangs on hardware.
+//!
+//! The main loop [`feed`](Watchdog::feed)s the watchdog once per pass, and a
+//! periodic interrupt calls [`tick`](Watchdog::tick). If enough ticks go by
+//! without the watchdog being fed, the main loop is assumed to be stuck and
+//! the timeout handler runs (or the watchdog panics, if there's no handler).
+//!
+//! Any regular interrupt can drive the ticks. Using the vblank interrupt makes
+//! one tick one frame, but vblank is often busy with other work. A pair of
+//! cascaded timers works well too: for example timer 2 counting at 1/1024
+//! CPU cycles with a reload of `0x10000 - 274` (about one frame per overflow),
+//! cascading into timer 3 that fires its IRQ every `n` overflows.
+//! [`Watchdog::with_timers`] sets up such a pair whenever it's armed.
+
+use crate::{mmio, GbaCell};
+
+/// Timer cycles per frame at the 1/1024 prescaler (280,896 CPU cycles).
+const TIMER_CYCLES_PER_FRAME: u16 = 274;
+
+/// The state of a software watchdog.
+///
+/// The watchdog starts out disarmed, and does nothing on [`tick`](Self::tick)
+/// until it's [`arm`](Self::arm)ed.
+pub struct Watchdog {
+    limit: GbaCell<u16>,
+    remaining: GbaCell<u16>,
+    on_timeout: GbaCell<Option<fn()>>,
+    timers: Option<(usize, u16)>,
+}
+impl Watchdog {
+    /// Constructs a new, disarmed watchdog with no timeout handler.
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            limit: GbaCell::new(0),
+            remaining: GbaCell::new(0),
+            on_timeout: GbaCell::new(None),
+            timers: None,
+        }
+    }
+
+    /// Constructs a new, disarmed watchdog that drives itself with timers
+    /// `timer` and `timer + 1`.
+    ///
+    /// While the watchdog is armed, timer `timer` counts at 1/1024 CPU cycles
+    /// and overflows about once a frame, cascading into timer `timer + 1`,
+    /// which raises its interrupt every `frames_per_tick` overflows. The IRQ
+    /// handler must call [`tick`](Self::tick) when that timer's bit (bit
+    /// `4 + timer`) is set in `IF`, and `IME` must be on. [`arm`](Self::arm)
+    /// starts both timers and sets the bit in `IE`, and
+    /// [`disarm`](Self::disarm) stops them.
+    ///
+    /// ## Panics
+    /// * If `timer` is more than 2, since there'd be no timer to cascade into.
+    /// * If `frames_per_tick` is 0.
+    #[inline]
+    #[must_use]
+    #[cfg_attr(feature = "track_caller", track_caller)]
+    pub const fn with_timers(timer: usize, frames_per_tick: u16) -> Self {
+        assert!(
+            timer < 3,
+            "The watchdog needs timers N and N + 1, so N must be 0 to 2."
+        );
+        assert!(
+            frames_per_tick != 0,
+            "The watchdog can't tick every 0 frames."
+        );
+        Self {
+            timers: Some((timer, frames_per_tick)),
+            ..Self::new()
+        }
+    }
+
+    /// Starts the watchdog, allowing `ticks` ticks between feedings.
+    ///
+    /// An argument of 0 disarms the watchdog.
+    #[inline]
+    pub fn arm(&self, ticks: u16) {
+        if ticks == 0 {
+            self.disarm();
+            return;
+        }
+        self.limit.write(ticks);
+        self.remaining.write(ticks);
+        self.start_timers();
+    }
+
+    /// Stops the watchdog, such as before a long blocking operation.
+    #[inline]
+    pub fn disarm(&self) {
+        self.limit.write(0);
+        self.stop_timers();
+    }
+
+    /// Starts the timers given to [`with_timers`](Self::with_timers), if any.
+    fn start_timers(&self) {
+        let Some((timer, frames_per_tick)) = self.timers else {
+            return;
+        };
+        let (first, second) = (timer * 2, timer * 2 + 2);
+        // SAFETY: the timer registers, `IME`, and `IE` are plain read/write
+        // registers, and `timer + 1` is at most 3. Interrupts are held off
+        // while `IE` is changed, so an IRQ handler changing it at the same
+        // time can't be lost.
+        unsafe {
+            mmio::TM0CNT_H.wrapping_add(first).write_volatile(0);
+            mmio::TM0CNT_H.wrapping_add(second).write_volatile(0);
+            mmio::TM0CNT_L
+                .wrapping_add(first)
+                .write_volatile(0_u16.wrapping_sub(TIMER_CYCLES_PER_FRAME));
+            mmio::TM0CNT_L
+                .wrapping_add(second)
+                .write_volatile(0_u16.wrapping_sub(frames_per_tick));
+            let ime = mmio::IME.read_volatile();
+            mmio::IME.write_volatile(0);
+            mmio::IE.write_volatile(mmio::IE.read_volatile() | (1 << (4 + timer)));
+            mmio::IME.write_volatile(ime);
+            // Cascade, IRQ on overflow, enabled. The second timer is started
+            // first so it doesn't miss the first overflow.
+            mmio::TM0CNT_H
+                .wrapping_add(second)
+                .write_volatile((1 << 2) | (1 << 6) | (1 << 7));
+            // 1/1024 prescaler, enabled.
+            mmio::TM0CNT_H
+                .wrapping_add(first)
+                .write_volatile(0b11 | (1 << 7));
+        }
+    }
+
+    /// Stops the timers given to [`with_timers`](Self::with_timers), if any.
+    fn stop_timers(&self) {
+        if let Some((timer, _)) = self.timers {
+            // SAFETY: as in `start_timers`.
+            unsafe {
+                mmio::TM0CNT_H.wrapping_add(timer * 2).write_volatile(0);
+                mmio::TM0CNT_H.wrapping_add(timer * 2 + 2).write_volatile(0);
+            }
+        }
+    }
+
+    /// If the watchdog is currently armed.
+    #[inline]
+    #[must_use]
+    pub fn is_armed(&self) -> bool {
+        self.limit.read() != 0
+    }
+
+    /// Sets the function to call on timeout, instead of panicking.
+    ///
+    /// The handler runs inside whatever interrupt called
+    /// [`tick`](Self::tick). A common choice is a function that logs the
+    /// problem and then performs a `SoftReset`.
+    #[inline]
+    pub fn set_timeout_handler(&self, handler: Option<fn()>) {
+        self.on_timeout.write(handler);
+    }
+
+    /// Tells the watchdog that the main loop is still running.
+    #[inline]
+    pub fn feed(&self) {
+        self.remaining.write(self.limit.read());
+    }
+
+    /// Counts down the watchdog. Call this from a periodic interrupt.
+    ///
+    /// When the count runs out, the watchdog disarms itself and then calls
+    /// the timeout handler.
+    ///
+    /// ## Panics
+    /// * If the count runs out and there's no timeout handler set.
+    #[cfg_attr(feature = "track_caller", track_caller)]
+    pub fn tick(&self) {
+        if !self.is_armed() {
+            return;
+        }
+        let remaining = self.remaining.read().saturating_sub(1);
+        self.remaining.write(remaining);
+        if remaining == 0 {
+            self.disarm();
+            match self.on_timeout.read() {
+                Some(handler) => handler(),
+                None => panic!("Watchdog timed out: the main loop stopped feeding it."),
+            }
+        }
+    }
+}
+
+impl Default for Watchdog {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
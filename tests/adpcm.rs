@@ -0,0 +1,103 @@
+use gba_cell::adpcm::AdpcmDecoder;
+
+#[test]
+fn decode_first_nibbles() {
+    let mut decoder = AdpcmDecoder::new(0, 0);
+    // A step of 7: 7 >> 3, plus 7, 7 >> 1, and 7 >> 2.
+    assert_eq!(decoder.decode_nibble(0x7), 11);
+    assert_eq!(decoder.index(), 8);
+    // A step of 16: 16 >> 3, plus 16 >> 1, going down.
+    assert_eq!(decoder.decode_nibble(0xA), 1);
+    assert_eq!(decoder.index(), 7);
+    // The upper bits are ignored.
+    let mut other = AdpcmDecoder::new(0, 0);
+    assert_eq!(other.decode_nibble(0xF7), 11);
+}
+
+#[test]
+fn decode_clamps() {
+    let mut decoder = AdpcmDecoder::new(i16::MAX - 10, 88);
+    assert_eq!(decoder.decode_nibble(0x7), i16::MAX);
+    assert_eq!(decoder.index(), 88);
+    let mut decoder = AdpcmDecoder::new(i16::MIN + 10, 0);
+    assert_eq!(decoder.decode_nibble(0xF), i16::MIN);
+    assert_eq!(decoder.index(), 8);
+}
+
+#[test]
+fn decode_stops_when_out_is_full() {
+    let input = [0x77, 0x77, 0x77, 0x77];
+    let mut out = [0_i16; 5];
+    let mut decoder = AdpcmDecoder::new(0, 0);
+    // Only whole bytes are decoded, so the odd sample slot is left alone.
+    assert_eq!(decoder.decode_i16(&input, &mut out), 2);
+    assert_ne!(out[3], 0);
+    assert_eq!(out[4], 0);
+
+    let mut out8 = [0_i8; 5];
+    let mut decoder8 = AdpcmDecoder::new(0, 0);
+    assert_eq!(decoder8.decode(&input, &mut out8), 2);
+    assert_eq!(out8[4], 0);
+    // The stream carries on from the same place either way.
+    assert_eq!(decoder8, decoder);
+    for (&wide, &narrow) in out[..4].iter().zip(&out8[..4]) {
+        assert_eq!((wide >> 8) as i8, narrow);
+    }
+}
+
+#[test]
+fn decode_stops_when_input_runs_out() {
+    let mut out = [0_i16; 8];
+    let mut decoder = AdpcmDecoder::new(0, 0);
+    assert_eq!(decoder.decode_i16(&[0x77], &mut out), 1);
+    assert_eq!(&out[2..], &[0; 6]);
+}
+
+#[cfg(feature = "std")]
+mod round_trip {
+    use gba_cell::adpcm::{encode, AdpcmDecoder};
+
+    fn decode_all(bytes: &[u8]) -> Vec<i16> {
+        let mut out = vec![0; bytes.len() * 2];
+        assert_eq!(
+            AdpcmDecoder::new(0, 0).decode_i16(bytes, &mut out),
+            bytes.len()
+        );
+        out
+    }
+
+    #[test]
+    fn sine_wave_within_bounds() {
+        let samples: Vec<i16> = (0..2000)
+            .map(|i| ((f64::from(i) * 0.05).sin() * 12000.0) as i16)
+            .collect();
+        let bytes = encode(&samples);
+        assert_eq!(bytes.len(), 1000);
+        let decoded = decode_all(&bytes);
+        // The step size needs a few samples to adapt to the wave, and after
+        // that each sample is within a step or so of the original.
+        for (i, (&a, &b)) in samples.iter().zip(&decoded).enumerate().skip(32) {
+            let error = (i32::from(a) - i32::from(b)).abs();
+            assert!(error < 100, "sample {i}: {a} decoded as {b}");
+        }
+    }
+
+    #[test]
+    fn silence_stays_near_zero() {
+        let decoded = decode_all(&encode(&[0; 64]));
+        assert!(decoded.iter().all(|s| s.abs() <= 8), "{decoded:?}");
+    }
+
+    #[test]
+    fn odd_length_is_padded() {
+        let samples = [0, 1000, 2000];
+        let bytes = encode(&samples);
+        assert_eq!(bytes.len(), 2);
+        let decoded = decode_all(&bytes);
+        // The padding repeats the last sample, so it also tracks toward 2000
+        // rather than heading back to 0.
+        assert!(decoded[3] >= decoded[2], "{decoded:?}");
+        assert_eq!(encode(&[5]).len(), 1);
+        assert!(encode(&[]).is_empty());
+    }
+}
@@ -0,0 +1,53 @@
+#![cfg(feature = "std")]
+
+use gba_cell::assets::{build, name_hash, AssetDir, AssetDirError};
+
+#[test]
+fn build_and_look_up() {
+    let tiles: Vec<u8> = (0..200).map(|i| (i % 7) as u8).collect();
+    let bytes = build([
+        ("sprites/hero", &b"hero!"[..], false),
+        ("tiles/grass", &tiles[..], true),
+        ("empty", &[][..], false),
+    ])
+    .unwrap();
+
+    // Copy into words so the directory itself starts 4-aligned.
+    let mut words = vec![0u32; bytes.len() / 4];
+    for (word, chunk) in words.iter_mut().zip(bytes.chunks_exact(4)) {
+        *word = u32::from_ne_bytes(chunk.try_into().unwrap());
+    }
+    // SAFETY: the words are initialized and cover `bytes.len()` bytes.
+    let aligned = unsafe { std::slice::from_raw_parts(words.as_ptr().cast(), bytes.len()) };
+    assert_eq!(aligned, &bytes[..]);
+
+    let dir = AssetDir::new(aligned).unwrap();
+    assert_eq!(dir.len(), 3);
+
+    let hero = dir.get_hash(name_hash("sprites/hero")).unwrap();
+    assert_eq!(hero.data(), b"hero!");
+    assert!(!hero.is_compressed());
+    assert_eq!(hero.data().as_ptr() as usize % 4, 0);
+
+    let grass = dir.get_hash(name_hash("tiles/grass")).unwrap();
+    assert!(grass.is_compressed());
+    assert_eq!(grass.data().as_ptr() as usize % 4, 0);
+    assert_eq!(grass.loaded_len(), Ok(200));
+    let mut out = [0; 200];
+    assert_eq!(grass.load(&mut out), Ok(200));
+    assert_eq!(&out[..], &tiles[..]);
+
+    let empty = dir.get("empty").unwrap();
+    assert_eq!(empty.data(), b"");
+    assert_eq!(empty.data().as_ptr() as usize % 4, 0);
+
+    assert_eq!(dir.get("sprites/villain"), None);
+}
+
+#[test]
+fn build_rejects_duplicate_names() {
+    assert_eq!(
+        build([("a", &b"1"[..], false), ("a", &b"2"[..], false)]),
+        Err(AssetDirError::Unsorted)
+    );
+}
@@ -0,0 +1,87 @@
+#![cfg(feature = "on_gba")]
+
+use gba_cell::cart::{size_from, MAX_ROM_SIZE};
+
+/// Reads from a fake ROM of `len` bytes, where each halfword is a
+/// pseudo-random value, then either open bus or a mirror past the end.
+fn fake_rom(len: u32, mirror: bool) -> impl Fn(u32) -> u16 {
+    move |offset| {
+        let offset = if mirror { offset % len } else { offset };
+        if offset < len {
+            ((offset / 2).wrapping_mul(0x9E37_79B9) >> 16) as u16
+        } else {
+            (offset >> 1) as u16
+        }
+    }
+}
+
+#[test]
+fn detects_open_bus_end() {
+    assert_eq!(size_from(fake_rom(256 << 10, false)), 256 << 10);
+    assert_eq!(size_from(fake_rom(300 << 10, false)), 512 << 10);
+    assert_eq!(size_from(fake_rom(4 << 10, false)), 64 << 10);
+}
+
+#[test]
+fn detects_mirrors() {
+    assert_eq!(size_from(fake_rom(1 << 20, true)), 1 << 20);
+    assert_eq!(size_from(fake_rom(8 << 20, true)), 8 << 20);
+}
+
+#[test]
+fn full_size_rom() {
+    assert_eq!(size_from(fake_rom(MAX_ROM_SIZE, false)), MAX_ROM_SIZE);
+}
+
+mod reader {
+    use gba_cell::cart::Reader;
+
+    /// The fake ROM's byte at an offset.
+    fn byte_at(offset: u32) -> u8 {
+        (offset.wrapping_mul(0x9E37_79B9) >> 24) as u8
+    }
+
+    /// Fills words from the fake ROM, checking the reads are aligned.
+    fn fill(offset: u32, words: &mut [u32]) {
+        assert_eq!(offset % 4, 0);
+        for (i, word) in words.iter_mut().enumerate() {
+            let at = offset + i as u32 * 4;
+            *word = u32::from_le_bytes([0, 1, 2, 3].map(|b| byte_at(at + b)));
+        }
+    }
+
+    fn expected(offset: u32, len: u32) -> Vec<u8> {
+        (offset..offset + len).map(byte_at).collect()
+    }
+
+    #[test]
+    fn bytes_match_rom() {
+        for (offset, len) in [(0, 64), (0x100, 13), (0x101, 13), (0x203, 40), (7, 1)] {
+            let reader = Reader::<4, _>::with_fill(offset, len, fill);
+            assert_eq!(reader.len(), len as usize);
+            assert_eq!(reader.collect::<Vec<_>>(), expected(offset, len));
+        }
+    }
+
+    #[test]
+    fn read_across_refills() {
+        let mut reader = Reader::<2, _>::with_fill(0x41, 30, fill);
+        let mut out = [0; 20];
+        assert_eq!(reader.read(&mut out[..3]), 3);
+        assert_eq!(reader.next_byte(), Some(byte_at(0x44)));
+        assert_eq!(reader.read(&mut out), 20);
+        assert_eq!(&out[..], &expected(0x45, 20)[..]);
+        assert_eq!(reader.remaining(), 6);
+        assert_eq!(reader.read(&mut out), 6);
+        assert!(reader.is_empty());
+        assert_eq!(reader.next_byte(), None);
+    }
+
+    #[test]
+    fn empty_unaligned_range() {
+        let mut reader = Reader::<1, _>::with_fill(0x13, 0, fill);
+        assert!(reader.is_empty());
+        assert_eq!(reader.next_byte(), None);
+        assert_eq!(reader.read(&mut [0; 4]), 0);
+    }
+}
@@ -0,0 +1,29 @@
+#![cfg(feature = "on_gba")]
+
+use gba_cell::GbaCell;
+
+static SPEED: GbaCell<f32> = GbaCell::new(1.5);
+
+#[test]
+fn f32_static_read_write() {
+    assert_eq!(SPEED.read(), 1.5);
+    SPEED.write(-0.25);
+    assert_eq!(SPEED.read(), -0.25);
+}
+
+#[test]
+fn f32_keeps_exact_bits() {
+    let cell = GbaCell::new(0.0_f32);
+    for bits in [0x8000_0000, 0x7F80_0000, 0x7FC0_1234, 0x0000_0001] {
+        cell.write(f32::from_bits(bits));
+        assert_eq!(cell.read().to_bits(), bits);
+    }
+}
+
+#[test]
+fn f32_default_and_clone() {
+    let cell: GbaCell<f32> = GbaCell::default();
+    assert_eq!(cell.read(), 0.0);
+    cell.write(3.0);
+    assert_eq!(cell.clone().read(), 3.0);
+}
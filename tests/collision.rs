@@ -0,0 +1,73 @@
+use gba_cell::collision::{Aabb, SpatialGrid};
+
+#[test]
+fn overlap_and_containment() {
+    let a = Aabb::new(0, 0, 10, 10);
+    assert!(a.overlaps(&Aabb::new(9, 9, 5, 5)));
+    // Touching edges don't overlap.
+    assert!(!a.overlaps(&Aabb::new(10, 0, 5, 5)));
+    assert!(!a.overlaps(&Aabb::new(0, -5, 5, 5)));
+    assert!(a.contains(0, 9));
+    assert!(!a.contains(10, 5));
+    assert_eq!(
+        a.intersection(&Aabb::new(5, -3, 10, 6)),
+        Some(Aabb::new(5, 0, 5, 3))
+    );
+    assert_eq!(a.intersection(&Aabb::new(10, 10, 1, 1)), None);
+    assert_eq!(a.translated(-3, 4), Aabb::new(-3, 4, 10, 10));
+}
+
+#[test]
+fn sweep_stops_at_walls_and_slides() {
+    // 8 pixel tiles, with a wall at tile column 4 and a floor at row 3.
+    let solid = |tx: i32, ty: i32| tx == 4 || ty == 3;
+    let player = Aabb::new(16, 8, 8, 8);
+
+    let moved = player.sweep_tiles(12, 0, 3, solid);
+    assert!(moved.hit_x && !moved.hit_y);
+    assert_eq!(moved.aabb.x, 24);
+
+    let moved = player.sweep_tiles(-6, 10, 3, solid);
+    assert!(!moved.hit_x && moved.hit_y);
+    assert_eq!((moved.aabb.x, moved.aabb.y), (10, 16));
+
+    let moved = player.sweep_tiles(-3, -4, 3, solid);
+    assert!(!moved.hit_x && !moved.hit_y);
+    assert_eq!((moved.aabb.x, moved.aabb.y), (13, 4));
+}
+
+#[test]
+fn grid_finds_overlapping_entries() {
+    // 4 x 4 cells of 16 units, with a spare row to check rows are counted.
+    let mut grid = SpatialGrid::<20, 8>::new(4, 4);
+    let boxes = [
+        Aabb::new(0, 0, 8, 8),
+        Aabb::new(12, 12, 16, 16),
+        Aabb::new(40, 40, 8, 8),
+        Aabb::new(60, 70, 4, 4),
+        // Off the grid, so clamped into the edge cells.
+        Aabb::new(-30, 100, 4, 4),
+    ];
+    for (id, aabb) in boxes.iter().enumerate() {
+        assert!(grid.insert(id as u16, aabb));
+    }
+    assert!(!grid.insert(8, &boxes[0]));
+
+    let overlapping = |area: Aabb| {
+        let mut ids: Vec<u16> = grid
+            .query(&area)
+            .filter(|&id| boxes[usize::from(id)].overlaps(&area))
+            .collect();
+        ids.sort_unstable();
+        ids
+    };
+    // Entry 1 starts in the cell up and to the left of the area.
+    assert_eq!(overlapping(Aabb::new(20, 20, 4, 4)), [1]);
+    assert_eq!(overlapping(Aabb::new(0, 0, 64, 64)), [0, 1, 2]);
+    assert_eq!(overlapping(Aabb::new(58, 68, 4, 4)), [3]);
+    assert_eq!(overlapping(Aabb::new(-32, 98, 8, 8)), [4]);
+    assert_eq!(grid.query(&Aabb::new(30, 50, 2, 2)).count(), 0);
+
+    grid.clear();
+    assert_eq!(grid.query(&Aabb::new(0, 0, 64, 64)).count(), 0);
+}
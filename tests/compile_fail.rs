@@ -0,0 +1,10 @@
+//! Checks that misuse is rejected at compile time: `GbaCell` types it can't
+//! access in a single instruction (with an error that names the type), queues
+//! that can't be shared, callbacks with the wrong ABI, missing assets,
+//! nested transactions, and dirty rectangle sets with no room.
+
+#[test]
+fn ui() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/*.rs");
+}
@@ -0,0 +1,74 @@
+use gba_cell::digits::{bcd_add, from_bcd, to_bcd, DigitTiles};
+
+/// Digits at tile 0x10 in palette 1, so the blank is tile 0 in palette 1.
+const TILES: DigitTiles = DigitTiles::new(0x1010);
+
+/// The entries `TILES` uses for a string of digits, `' '`, and `'.'`.
+fn entries(s: &str) -> Vec<u16> {
+    s.bytes()
+        .map(|b| match b {
+            b' ' => 0x1000,
+            b'.' => 0x101A,
+            _ => TILES.digit(b - b'0'),
+        })
+        .collect()
+}
+
+#[test]
+fn zero_and_max() {
+    let mut out = [0; 4];
+    TILES.write(0, &mut out);
+    assert_eq!(out[..], entries("   0"));
+    TILES.write_zero_padded(0, &mut out);
+    assert_eq!(out[..], entries("0000"));
+
+    let mut out = [0; 10];
+    TILES.write(u32::MAX, &mut out);
+    assert_eq!(out[..], entries("4294967295"));
+    let mut out = [0; 12];
+    TILES.write(u32::MAX, &mut out);
+    assert_eq!(out[..], entries("  4294967295"));
+}
+
+#[test]
+fn too_long_saturates_to_nines() {
+    let mut out = [0; 3];
+    TILES.write(999, &mut out);
+    assert_eq!(out[..], entries("999"));
+    TILES.write(1000, &mut out);
+    assert_eq!(out[..], entries("999"));
+    TILES.write_zero_padded(u32::MAX, &mut out);
+    assert_eq!(out[..], entries("999"));
+}
+
+#[test]
+fn fixed_point() {
+    let mut out = [0; 5];
+    // 3.25 with 8 fractional bits.
+    TILES.write_fixed(0x340, 8, 2, &mut out);
+    assert_eq!(out[..], entries(" 3.25"));
+    // 0.999... is truncated, not rounded.
+    TILES.write_fixed(0xFF, 8, 2, &mut out);
+    assert_eq!(out[..], entries(" 0.99"));
+    TILES.write_fixed(u32::MAX, 4, 1, &mut out);
+    assert_eq!(out[..], entries("999.9"));
+}
+
+#[test]
+fn bcd() {
+    assert_eq!(to_bcd(0), 0);
+    assert_eq!(to_bcd(12_345_678), 0x1234_5678);
+    assert_eq!(to_bcd(99_999_999), 0x9999_9999);
+    // Only the lowest 8 digits fit.
+    assert_eq!(to_bcd(u32::MAX), 0x9496_7295);
+    assert_eq!(from_bcd(to_bcd(u32::MAX)), 94_967_295);
+    for n in [0, 1, 9, 10, 99, 100, 65_535, 99_999_999] {
+        assert_eq!(from_bcd(to_bcd(n)), n);
+    }
+    assert_eq!(bcd_add(0x0999, 0x0001), 0x1000);
+    assert_eq!(bcd_add(0x9999_9990, 0x10), 0x9999_9999);
+
+    let mut out = [0; 4];
+    TILES.write_bcd(0x0012_3456, &mut out);
+    assert_eq!(out[..], entries("3456"));
+}
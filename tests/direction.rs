@@ -0,0 +1,178 @@
+use gba_cell::direction::{atan2_approx, dist_approx, Angle, Dir16, Dir8};
+use std::f64::consts::TAU;
+
+/// A point on a circle of radius 10000 at `degrees`, clockwise from right.
+fn at(degrees: f64) -> (i32, i32) {
+    let r = degrees.to_radians();
+    (
+        (r.cos() * 10000.0).round() as i32,
+        (r.sin() * 10000.0).round() as i32,
+    )
+}
+
+/// Checks every tenth of a degree against the nearest of `steps` directions,
+/// skipping angles within half a degree of a boundary between two.
+fn sweep(steps: u32, from_delta: impl Fn(i32, i32) -> Option<u8>) {
+    let width = 360.0 / f64::from(steps);
+    for tenth in 0..3600 {
+        let degrees = f64::from(tenth) / 10.0;
+        let nearest = degrees / width;
+        if (nearest.fract() - 0.5).abs() < 0.5 / width {
+            continue;
+        }
+        let expected = (nearest.round() as u32 % steps) as u8;
+        let (dx, dy) = at(degrees);
+        assert_eq!(from_delta(dx, dy), Some(expected), "{degrees}°");
+    }
+}
+
+#[test]
+fn dir8_sweep() {
+    sweep(8, |dx, dy| Dir8::from_delta(dx, dy).map(Dir8::index));
+}
+
+#[test]
+fn dir16_sweep() {
+    sweep(16, |dx, dy| Dir16::from_delta(dx, dy).map(Dir16::index));
+}
+
+#[test]
+fn dir8_tangent_boundaries() {
+    // tan(22.5°) is 0.41421.
+    assert_eq!(Dir8::from_delta(1000, 414), Some(Dir8::Right));
+    assert_eq!(Dir8::from_delta(1000, 415), Some(Dir8::DownRight));
+    assert_eq!(Dir8::from_delta(-1000, -414), Some(Dir8::Left));
+    assert_eq!(Dir8::from_delta(-1000, -415), Some(Dir8::UpLeft));
+    // tan(67.5°) is 2.41421.
+    assert_eq!(Dir8::from_delta(1000, 2414), Some(Dir8::DownRight));
+    assert_eq!(Dir8::from_delta(1000, 2415), Some(Dir8::Down));
+    assert_eq!(Dir8::from_delta(1000, -2415), Some(Dir8::Up));
+    assert_eq!(Dir8::from_delta(-1000, 2415), Some(Dir8::Down));
+}
+
+#[test]
+fn dir16_tangent_boundaries() {
+    // The thresholds are tan(11.25°) and tan(78.75°) rounded to 8.8
+    // fixed-point, 51 / 256 = 0.19922 and 1287 / 256 = 5.02734.
+    assert_eq!(Dir16::from_delta(10000, 1992), Some(Dir16::from_index(0)));
+    assert_eq!(Dir16::from_delta(10000, 1993), Some(Dir16::from_index(1)));
+    assert_eq!(Dir16::from_delta(10000, -1993), Some(Dir16::from_index(15)));
+    assert_eq!(Dir16::from_delta(1000, 5027), Some(Dir16::from_index(3)));
+    assert_eq!(Dir16::from_delta(1000, 5028), Some(Dir16::from_index(4)));
+    assert_eq!(Dir16::from_delta(-1000, 5028), Some(Dir16::from_index(4)));
+}
+
+#[test]
+fn axes_and_diagonals() {
+    assert_eq!(Dir8::from_delta(0, 0), None);
+    assert_eq!(Dir16::from_delta(0, 0), None);
+    for dir in Dir8::ALL {
+        let (dx, dy) = dir.step();
+        assert_eq!(Dir8::from_delta(dx, dy), Some(dir));
+        assert_eq!(Dir16::from_delta(dx, dy), Some(dir.into()));
+        assert_eq!(Dir16::from(dir).to_dir8(), dir);
+    }
+}
+
+/// The angle in 65536ths of a turn, and the error of `atan2_approx` there.
+fn atan2_error(dy: i32, dx: i32) -> i32 {
+    let exact = (f64::from(dy).atan2(f64::from(dx)) / TAU * 65536.0).round() as i64;
+    let approx = atan2_approx(dy, dx).unwrap().bits();
+    i32::from(approx.wrapping_sub(exact as u16) as i16)
+}
+
+#[test]
+fn atan2_sweep() {
+    for tenth in 0..3600 {
+        let (dx, dy) = at(f64::from(tenth) / 10.0);
+        assert!(atan2_error(dy, dx).abs() <= 6, "{tenth}");
+    }
+}
+
+#[test]
+fn atan2_extremes() {
+    assert_eq!(atan2_approx(0, 0), None);
+    for (dy, dx) in [
+        (0, 1),
+        (1, 1),
+        (1, 0),
+        (0, -1),
+        (-1, 0),
+        (1, 1000),
+        (i32::MAX, i32::MAX),
+        (i32::MIN, 0),
+        (0, i32::MIN),
+        (i32::MIN, i32::MIN),
+        (i32::MAX, 1),
+        (-1, i32::MAX),
+    ] {
+        assert!(atan2_error(dy, dx).abs() <= 6, "({dy}, {dx})");
+    }
+}
+
+#[test]
+fn angle_to_directions() {
+    assert_eq!(Angle::from_bits(0x4000).to_dir8(), Dir8::Down);
+    assert_eq!(Angle::from_bits(0x0FFF).to_dir8(), Dir8::Right);
+    assert_eq!(Angle::from_bits(0x1000).to_dir8(), Dir8::DownRight);
+    assert_eq!(Angle::from_bits(0xF800).to_dir16(), Dir16::RIGHT);
+    assert_eq!(Angle::from_bits(0xF7FF).to_dir16(), Dir16::from_index(15));
+    for dir in Dir8::ALL {
+        assert_eq!(Angle::from(dir).to_dir8(), dir);
+    }
+}
+
+/// The square root of `n`, rounded down.
+fn isqrt(n: u64) -> u64 {
+    let mut root = (n as f64).sqrt() as u64;
+    while root * root > n {
+        root -= 1;
+    }
+    while (root + 1) * (root + 1) <= n {
+        root += 1;
+    }
+    root
+}
+
+/// Checks the distance is within 4% every tenth of a degree around circles
+/// of several sizes, allowing 2 more for rounding (of both the estimate and
+/// the square root).
+#[test]
+fn dist_sweep() {
+    for radius in [100, 1000, 30000, 1 << 20] {
+        for tenth in 0..3600 {
+            let (dx, dy) = {
+                let r = (f64::from(tenth) / 10.0).to_radians();
+                let scale = f64::from(radius);
+                (
+                    (r.cos() * scale).round() as i32,
+                    (r.sin() * scale).round() as i32,
+                )
+            };
+            let exact =
+                isqrt(u64::from(dx.unsigned_abs()).pow(2) + u64::from(dy.unsigned_abs()).pow(2));
+            let approx = u64::from(dist_approx(dx, dy));
+            assert!(
+                approx.abs_diff(exact) * 100 <= exact * 4 + 200,
+                "({dx}, {dy}): {approx} vs {exact}"
+            );
+        }
+    }
+}
+
+#[test]
+fn dist_extremes() {
+    assert_eq!(dist_approx(0, 0), 0);
+    assert_eq!(dist_approx(1, 0), 1);
+    for (dx, dy) in [
+        (0, -1000),
+        (i32::MAX, 0),
+        (i32::MIN, i32::MIN),
+        (i32::MAX, i32::MIN),
+    ] {
+        let exact =
+            isqrt(u64::from(dx.unsigned_abs()).pow(2) + u64::from(dy.unsigned_abs()).pow(2));
+        let approx = u64::from(dist_approx(dx, dy));
+        assert!(approx.abs_diff(exact) * 100 <= exact * 4, "({dx}, {dy})");
+    }
+}
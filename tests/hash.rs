@@ -0,0 +1,41 @@
+use gba_cell::hash::{adler32, crc32, Adler32, Crc32};
+
+#[test]
+fn crc32_check_value() {
+    assert_eq!(crc32(b""), 0);
+    assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+}
+
+#[test]
+fn adler32_known_values() {
+    assert_eq!(adler32(b""), 1);
+    assert_eq!(adler32(b"Wikipedia"), 0x11E6_0398);
+}
+
+#[test]
+fn adler32_long_input() {
+    // Long enough that the sums have to be reduced partway through.
+    let bytes = [0xFF; 20_000];
+    let (mut a, mut b) = (1_u32, 0_u32);
+    for &byte in &bytes {
+        a = (a + u32::from(byte)) % 65521;
+        b = (b + a) % 65521;
+    }
+    assert_eq!(adler32(&bytes), (b << 16) | a);
+}
+
+#[test]
+fn incremental_matches_one_shot() {
+    let bytes: Vec<u8> = (0..10_000_u32).map(|i| (i * 7 + i / 3) as u8).collect();
+    for split in [0, 1, 9, 4096, 5552, 9999, 10_000] {
+        let (head, tail) = bytes.split_at(split);
+        let mut crc = Crc32::new();
+        crc.update(head);
+        crc.update(tail);
+        assert_eq!(crc.finish(), crc32(&bytes), "crc32 split at {split}");
+        let mut adler = Adler32::new();
+        adler.update(head);
+        adler.update(tail);
+        assert_eq!(adler.finish(), adler32(&bytes), "adler32 split at {split}");
+    }
+}
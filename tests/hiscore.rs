@@ -0,0 +1,50 @@
+use gba_cell::hiscore::{HiScores, InitialsEntry, Score};
+use gba_cell::keys::{Key, Keys};
+
+#[test]
+fn insert_keeps_order_and_drops_lowest() {
+    let mut table = HiScores::<3>::new();
+    assert_eq!(table.insert(Score::new(100, *b"AAA")), Ok(0));
+    assert_eq!(table.insert(Score::new(300, *b"BBB")), Ok(0));
+    assert_eq!(table.insert(Score::new(100, *b"CCC")), Ok(2));
+    assert_eq!(table.qualifies(50), None);
+    assert_eq!(table.insert(Score::new(200, *b"DDD")), Ok(1));
+    let initials: Vec<_> = table.scores().iter().map(|s| &s.initials).collect();
+    assert_eq!(initials, [b"BBB", b"DDD", b"AAA"]);
+    assert_eq!(
+        table.insert(Score::new(100, *b"EEE")),
+        Err(Score::new(100, *b"EEE"))
+    );
+}
+
+#[test]
+fn save_round_trips_and_rejects_corruption() {
+    let mut table = HiScores::<4>::new();
+    table.insert(Score::new(1234, *b"XYZ")).unwrap();
+    table.insert(Score::new(99, *b"ABC")).unwrap();
+    let mut bytes = [0xFF; HiScores::<4>::SAVE_LEN];
+    assert_eq!(table.save(&mut bytes), Some(HiScores::<4>::SAVE_LEN));
+    assert_eq!(HiScores::<4>::load(&bytes), Some(table.clone()));
+
+    bytes[5] ^= 1;
+    assert_eq!(HiScores::<4>::load(&bytes), None);
+    assert_eq!(HiScores::<4>::load(&[0xFF; 40]), None);
+    assert_eq!(table.save(&mut [0; 8]), None);
+}
+
+#[test]
+fn initials_entry() {
+    let mut entry = InitialsEntry::new();
+    let press = |k: Key| Keys::from(k);
+    assert_eq!(entry.update(press(Key::Down)), None);
+    assert_eq!(entry.initials(), *b" AA");
+    assert_eq!(entry.update(press(Key::A)), None);
+    entry.update(press(Key::Up));
+    entry.update(press(Key::Up));
+    entry.update(press(Key::Right));
+    assert_eq!(entry.cursor(), 2);
+    entry.update(press(Key::B));
+    assert_eq!(entry.cursor(), 1);
+    entry.update(press(Key::A));
+    assert_eq!(entry.update(press(Key::A)), Some(*b" CA"));
+}
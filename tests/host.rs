@@ -0,0 +1,134 @@
+//! Runs the crate's `unsafe` code paths on the host, so they can be checked
+//! for Undefined Behavior with `cargo +nightly miri test --test host`.
+#![cfg(feature = "on_gba")]
+
+use gba_cell::arena::ArenaString;
+use gba_cell::collision::Aabb;
+use gba_cell::diagnostics::test_words;
+use gba_cell::dirty::DirtyRects;
+use gba_cell::patch::{Patch, PatchList, Width};
+use gba_cell::schedule::{Action, Scheduler};
+use gba_cell::{GbaCell, GbaFnCell, GbaQueue};
+use std::fmt::Write;
+use std::num::NonZeroU16;
+use std::ptr::NonNull;
+
+#[test]
+fn cell_read_write_each_size() {
+    let byte = GbaCell::new(1_u8);
+    byte.write(2);
+    assert_eq!(byte.read(), 2);
+
+    let half = GbaCell::new(NonZeroU16::new(3));
+    half.write(None);
+    assert_eq!(half.read(), None);
+
+    let mut target = 5_u32;
+    let ptr = GbaCell::new(NonNull::from(&mut target));
+    // SAFETY: `target` is still alive and not otherwise borrowed.
+    unsafe { *ptr.read().as_ptr() += 1 };
+    assert_eq!(target, 6);
+}
+
+#[test]
+fn cell_shared_between_threads() {
+    static COUNT: GbaCell<u32> = GbaCell::new(0);
+    std::thread::spawn(|| COUNT.write(7)).join().unwrap();
+    assert_eq!(COUNT.read(), 7);
+}
+
+#[test]
+fn queue_wraps_around() {
+    let queue = GbaQueue::<u16, 4>::new();
+    // SAFETY: everything happens on this one thread.
+    unsafe {
+        for round in 0..10 {
+            for i in 0..3 {
+                queue.push(round * 3 + i).unwrap();
+            }
+            for i in 0..3 {
+                assert_eq!(queue.pop(), Some(round * 3 + i));
+            }
+        }
+        assert_eq!(queue.pop(), None);
+        for i in 0..4 {
+            queue.push(i).unwrap();
+        }
+        assert_eq!(queue.push(9), Err(9));
+    }
+}
+
+#[test]
+fn fn_cell_calls() {
+    extern "C" fn double(x: u32) -> u32 {
+        x * 2
+    }
+    let cell = GbaFnCell::<(u32,), u32>::new();
+    assert_eq!(cell.call(4), None);
+    cell.set(Some(double));
+    assert_eq!(cell.call(4), Some(8));
+}
+
+#[test]
+fn arena_string_stays_utf8() {
+    let mut buffer = [0_u8; 8];
+    let mut s = ArenaString::new(&mut buffer);
+    write!(s, "é{}", 12).unwrap();
+    assert!(s.push_str("ünï").is_err());
+    assert_eq!(s.into_str(), "é12");
+}
+
+#[test]
+fn patches_write_each_width() {
+    let mut target = [0_u32; 3];
+    let base = target.as_mut_ptr();
+    let list = PatchList::<3>::new();
+    // SAFETY: each address is in `target`, aligned for its width, and
+    // nothing else accesses `target` until the list is done with.
+    unsafe {
+        list.add(Patch::new(base as usize, 0xAB, Width::U8))
+            .unwrap();
+        list.add(Patch::new(base.add(1) as usize, 0xABCD, Width::U16))
+            .unwrap();
+        list.add(Patch::new(base.add(2) as usize, 0x1234_5678, Width::U32))
+            .unwrap();
+    }
+    list.apply();
+    assert_eq!(target, [0xAB, 0xABCD, 0x1234_5678]);
+}
+
+#[test]
+fn dirty_rects_copy() {
+    let from = vec![0x1111_u16; 120 * 160];
+    let mut to = vec![0_u16; 120 * 160];
+    let mut dirty = DirtyRects::<4>::new();
+    dirty.add(Aabb::new(230, 150, 20, 20));
+    dirty.copy(&from, &mut to);
+    assert_eq!(to.iter().filter(|&&u| u != 0).count(), 5 * 10);
+    assert_eq!(to[120 * 160 - 1], 0x1111);
+}
+
+#[test]
+fn scheduler_next_due() {
+    static FLAG: GbaCell<bool> = GbaCell::new(false);
+    let mut scheduler = Scheduler::<2>::new();
+    assert_eq!(scheduler.next_due(), None);
+    scheduler.run(u32::MAX - 1);
+    scheduler.after_frames(5, Action::Set(&FLAG)).unwrap();
+    let soon = scheduler.after_frames(2, Action::Set(&FLAG)).unwrap();
+    assert_eq!(scheduler.next_due(), Some(2));
+    scheduler.cancel(soon);
+    scheduler.run(2);
+    assert_eq!(scheduler.next_due(), Some(1));
+    assert!(!FLAG.read());
+    scheduler.run(3);
+    assert!(FLAG.read());
+    assert_eq!(scheduler.next_due(), None);
+}
+
+#[test]
+fn memory_test_passes_and_clears() {
+    let mut mem = vec![0x1234_5678_u32; 256];
+    assert_eq!(test_words(&mut mem), Ok(()));
+    assert!(mem.iter().all(|&w| w == 0));
+}
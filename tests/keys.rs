@@ -0,0 +1,80 @@
+use gba_cell::keys::{Key, KeyMap, Keys};
+
+#[test]
+fn keyinput_polarity() {
+    // Nothing pressed reads as all ones.
+    assert_eq!(Keys::from_keyinput(0x03FF), Keys::NONE);
+    // A and Start held, with the unused upper bits set as the hardware does.
+    let keys = Keys::from_keyinput(!(Key::A.bit() | Key::Start.bit()));
+    assert_eq!(keys, Key::A | Key::Start);
+    assert!(keys.a() && keys.start());
+    assert!(!keys.b() && !keys.select());
+    assert_eq!(keys.to_keyinput(), 0x03FF & !0b1001);
+    assert_eq!(Keys::from_keyinput(keys.to_keyinput()), keys);
+    assert_eq!(Keys::from_bits(0xFFFF).bits(), 0x03FF);
+}
+
+#[test]
+fn edges_and_combos() {
+    let before = Key::A | Key::Left;
+    let after = Key::A | Key::Right;
+    assert_eq!(after.newly_pressed(before), Keys::from(Key::Right));
+    assert_eq!(after.newly_released(before), Keys::from(Key::Left));
+    assert!(after.contains(Keys::from(Key::A)));
+    assert!(!after.contains(Key::A | Key::B));
+    assert_eq!(!Keys::NONE, Keys::from_bits(0x03FF));
+    assert_eq!(after & before, Keys::from(Key::A));
+    assert_eq!(after.iter().collect::<Vec<_>>(), [Key::A, Key::Right]);
+    assert_eq!(format!("{after:?}"), "Keys[A, Right]");
+}
+
+#[test]
+fn keymap_identity_and_swap() {
+    assert_eq!(KeyMap::default(), KeyMap::IDENTITY);
+    for key in Key::ALL {
+        assert_eq!(KeyMap::IDENTITY.get(key), key);
+    }
+    let keys = Key::A | Key::Up;
+    assert_eq!(KeyMap::IDENTITY.apply(keys), keys);
+
+    assert_eq!(KeyMap::SWAP_AB.get(Key::A), Key::B);
+    assert_eq!(KeyMap::SWAP_AB.get(Key::B), Key::A);
+    assert_eq!(KeyMap::SWAP_AB.apply(keys), Key::B | Key::Up);
+    assert_eq!(KeyMap::SWAP_AB.apply(Key::A | Key::B), Key::A | Key::B);
+    assert_eq!(KeyMap::SWAP_AB.swapped(Key::A, Key::B), KeyMap::IDENTITY);
+}
+
+#[test]
+fn keymap_remapping() {
+    let mut map = KeyMap::IDENTITY;
+    // Both shoulders jump, so nothing produces L or R.
+    map.set(Key::R, Key::A);
+    map.set(Key::L, Key::A);
+    assert_eq!(map.get(Key::L), Key::A);
+    assert_eq!(map.apply(Keys::from(Key::R)), Keys::from(Key::A));
+    assert_eq!(map.apply(Key::A | Key::L | Key::R), Keys::from(Key::A));
+    assert_eq!(map.apply(Key::L | Key::Down), Key::A | Key::Down);
+    assert_eq!(map.apply(!Keys::NONE), !Keys::NONE & !(Key::L | Key::R));
+
+    let map = map.swapped(Key::Start, Key::R);
+    assert_eq!(map.get(Key::Start), Key::A);
+    assert_eq!(map.get(Key::R), Key::Start);
+}
+
+#[test]
+fn keymap_bytes() {
+    let map = KeyMap::SWAP_AB.swapped(Key::Up, Key::Down);
+    let bytes = map.to_bytes();
+    assert_eq!(bytes, [1, 0, 2, 3, 4, 5, 7, 6, 8, 9]);
+    assert_eq!(KeyMap::from_bytes(bytes), Some(map));
+    assert_eq!(
+        KeyMap::from_bytes([0; 10]).map(|m| m.get(Key::L)),
+        Some(Key::A)
+    );
+
+    // Blank save data, and a single bad entry.
+    assert_eq!(KeyMap::from_bytes([0xFF; 10]), None);
+    let mut bad = bytes;
+    bad[9] = 10;
+    assert_eq!(KeyMap::from_bytes(bad), None);
+}
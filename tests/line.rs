@@ -0,0 +1,63 @@
+use gba_cell::line::Line;
+
+/// Checks the points run from `start` to `end` in single 8-way steps, with
+/// one point per step along the longer axis.
+fn check(start: (i32, i32), end: (i32, i32)) -> Vec<(i32, i32)> {
+    let points: Vec<_> = Line::new(start, end).collect();
+    assert_eq!(points.first(), Some(&start));
+    assert_eq!(points.last(), Some(&end));
+    let steps = (end.0 - start.0).abs().max((end.1 - start.1).abs());
+    assert_eq!(points.len(), steps as usize + 1);
+    for pair in points.windows(2) {
+        let (dx, dy) = (pair[1].0 - pair[0].0, pair[1].1 - pair[0].1);
+        assert!(dx.abs() <= 1 && dy.abs() <= 1 && (dx, dy) != (0, 0));
+    }
+    points
+}
+
+#[test]
+fn single_point() {
+    assert_eq!(check((3, -4), (3, -4)), [(3, -4)]);
+}
+
+#[test]
+fn axis_lines() {
+    assert_eq!(check((0, 0), (3, 0)), [(0, 0), (1, 0), (2, 0), (3, 0)]);
+    assert_eq!(check((0, 2), (0, -1)), [(0, 2), (0, 1), (0, 0), (0, -1)]);
+}
+
+// Halfway points (such as `(1, 0.5)`) round away from the start.
+#[test]
+fn shallow_and_steep_lines() {
+    assert_eq!(
+        check((0, 0), (4, 2)),
+        [(0, 0), (1, 1), (2, 1), (3, 2), (4, 2)]
+    );
+    assert_eq!(
+        check((0, 0), (2, 4)),
+        [(0, 0), (1, 1), (1, 2), (2, 3), (2, 4)]
+    );
+}
+
+#[test]
+fn negative_slopes() {
+    assert_eq!(
+        check((0, 0), (4, -2)),
+        [(0, 0), (1, -1), (2, -1), (3, -2), (4, -2)]
+    );
+    assert_eq!(
+        check((0, 0), (-2, 4)),
+        [(0, 0), (-1, 1), (-1, 2), (-2, 3), (-2, 4)]
+    );
+    check((10, 10), (-7, 3));
+    check((-5, 8), (2, -30));
+}
+
+#[test]
+fn reversed_lines_cover_the_same_length() {
+    for end in [(5, 1), (1, 5), (-5, 3), (-3, -5), (7, -7)] {
+        let forward = check((0, 0), end);
+        let backward = check(end, (0, 0));
+        assert_eq!(forward.len(), backward.len());
+    }
+}
@@ -0,0 +1,142 @@
+use gba_cell::lz77::{decompress, decompressed_len, Lz77Error, Lz77Stream};
+
+/// `ABCABCABCABC`: three literals, then one back-reference 3 bytes back for
+/// the other 9 bytes, padded to 4 bytes.
+const ABC_VECTOR: [u8; 12] = [
+    0x10, 0x0C, 0x00, 0x00, 0x10, b'A', b'B', b'C', 0x60, 0x02, 0x00, 0x00,
+];
+
+#[test]
+fn decompress_known_vector() {
+    let mut out = [0; 12];
+    assert_eq!(decompressed_len(&ABC_VECTOR), Ok(12));
+    assert_eq!(decompress(&ABC_VECTOR, &mut out), Ok(12));
+    assert_eq!(&out, b"ABCABCABCABC");
+}
+
+#[test]
+fn decompress_rejects_bad_input() {
+    let mut out = [0; 16];
+    assert_eq!(
+        decompress(&[0x11, 1, 0, 0, 0, 0], &mut out),
+        Err(Lz77Error::BadHeader)
+    );
+    assert_eq!(decompress(&[0x10], &mut out), Err(Lz77Error::BadHeader));
+    assert_eq!(
+        decompress(&ABC_VECTOR, &mut out[..11]),
+        Err(Lz77Error::OutputTooSmall)
+    );
+    assert_eq!(
+        decompress(&ABC_VECTOR[..9], &mut out),
+        Err(Lz77Error::Truncated)
+    );
+    // A back-reference as the very first entry has nothing to copy from.
+    let bad = [0x10, 3, 0, 0, 0x80, 0x00, 0x00, 0x00];
+    assert_eq!(decompress(&bad, &mut out), Err(Lz77Error::BadDistance));
+}
+
+#[test]
+fn stream_resumes_and_writes_u16() {
+    // Every split point, including the middle of the back-reference.
+    for limit in 1..=12 {
+        let mut stream = Lz77Stream::new(&ABC_VECTOR).unwrap();
+        let mut out = [0; 12];
+        while !stream.is_done() {
+            let before = stream.written();
+            let n = stream.decompress(&mut out, limit).unwrap();
+            assert_eq!(n, limit.min(12 - before));
+        }
+        assert_eq!(&out, b"ABCABCABCABC");
+        assert_eq!(stream.decompress(&mut out, limit), Ok(0));
+    }
+
+    let mut stream = Lz77Stream::new(&ABC_VECTOR).unwrap();
+    let mut halves = [0xFFFF; 7];
+    assert_eq!(stream.decompress_u16(&mut halves, 3), Ok(3));
+    assert_eq!(halves[..2], [u16::from_le_bytes(*b"AB"), 0xFF43]);
+    assert_eq!(stream.decompress_u16(&mut halves, 100), Ok(9));
+    assert_eq!(halves[5], u16::from_le_bytes(*b"BC"));
+    // Bytes past the end aren't touched.
+    assert_eq!(halves[6], 0xFFFF);
+    assert_eq!(
+        stream.decompress_u16(&mut halves[..5], 1),
+        Err(Lz77Error::OutputTooSmall)
+    );
+}
+
+#[cfg(feature = "std")]
+mod round_trip {
+    use gba_cell::lz77::{compress, decompress, Lz77Stream};
+
+    fn round_trip(data: &[u8]) -> Vec<u8> {
+        let packed = compress(data);
+        assert_eq!(packed.len() % 4, 0);
+        let mut out = vec![0; data.len()];
+        assert_eq!(decompress(&packed, &mut out), Ok(data.len()));
+        assert_eq!(out, data);
+        packed
+    }
+
+    /// Bytes from a simple LCG, which don't compress.
+    fn noise(len: usize) -> Vec<u8> {
+        let mut state = 12345_u32;
+        (0..len)
+            .map(|_| {
+                state = state.wrapping_mul(1_103_515_245).wrapping_add(12345);
+                (state >> 16) as u8
+            })
+            .collect()
+    }
+
+    #[test]
+    fn matches_known_vector() {
+        assert_eq!(round_trip(b"ABCABCABCABC"), super::ABC_VECTOR);
+    }
+
+    #[test]
+    fn empty() {
+        assert_eq!(round_trip(&[]), [0x10, 0, 0, 0]);
+    }
+
+    #[test]
+    fn runs() {
+        let packed = round_trip(&[0x55; 1000]);
+        assert!(packed.len() < 200);
+        // No back-reference has a distance of 1, for the BIOS VRAM function.
+        assert_eq!(packed[5..9], [0x55, 0x55, 0xF0, 0x01]);
+    }
+
+    #[test]
+    fn repeats_further_back_than_the_window() {
+        let mut data = noise(5000);
+        data.extend_from_within(..100);
+        round_trip(&data);
+    }
+
+    #[test]
+    fn stream_in_pieces() {
+        let mut data = noise(3000);
+        data.extend_from_within(1000..2000);
+        let packed = compress(&data);
+        for limit in [1, 7, 64, 1000] {
+            let mut stream = Lz77Stream::new(&packed).unwrap();
+            let mut bytes = vec![0; data.len()];
+            let mut halves = vec![0; data.len() / 2];
+            let mut copy = stream.clone();
+            while !stream.is_done() {
+                stream.decompress(&mut bytes, limit).unwrap();
+                copy.decompress_u16(&mut halves, limit).unwrap();
+            }
+            assert_eq!(bytes, data);
+            let halves: Vec<u8> = halves.iter().flat_map(|h| h.to_le_bytes()).collect();
+            assert_eq!(halves, data);
+        }
+    }
+
+    #[test]
+    fn unaligned_lengths() {
+        for len in [1, 2, 3, 5, 7, 13, 4097] {
+            round_trip(&noise(len));
+        }
+    }
+}
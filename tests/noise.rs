@@ -0,0 +1,52 @@
+use gba_cell::noise::{below, hash32, noise2d, shuffle, shuffle_seeded};
+
+#[test]
+fn hash_and_noise_are_deterministic() {
+    assert_eq!(hash32(0), 0);
+    assert_ne!(hash32(1), hash32(2));
+    assert_eq!(noise2d(3, -4, 99), noise2d(3, -4, 99));
+    assert_ne!(noise2d(3, -4, 99), noise2d(-4, 3, 99));
+    assert_ne!(noise2d(3, -4, 99), noise2d(3, -4, 100));
+
+    // Every output bit should flip about half the time over a grid.
+    let mut ones = [0_u32; 32];
+    for y in 0..32 {
+        for x in 0..32 {
+            let n = noise2d(x, y, 7);
+            for (bit, count) in ones.iter_mut().enumerate() {
+                *count += (n >> bit) & 1;
+            }
+        }
+    }
+    assert!(ones.iter().all(|&c| (400..624).contains(&c)), "{ones:?}");
+}
+
+#[test]
+fn below_stays_in_range() {
+    assert_eq!(below(0, 10), 0);
+    assert_eq!(below(u32::MAX, 10), 9);
+    assert_eq!(below(u32::MAX, 1), 0);
+    assert_eq!(below(0x8000_0000, 6), 3);
+    assert_eq!(below(12345, 0), 0);
+}
+
+#[test]
+fn shuffles_are_permutations() {
+    let mut items: Vec<u32> = (0..50).collect();
+    shuffle_seeded(&mut items, 1);
+    let mut again: Vec<u32> = (0..50).collect();
+    shuffle_seeded(&mut again, 1);
+    assert_eq!(items, again);
+    assert_ne!(items, (0..50).collect::<Vec<_>>());
+    items.sort_unstable();
+    assert_eq!(items, (0..50).collect::<Vec<_>>());
+
+    // `next` is called once per item, less one.
+    let mut calls = 0;
+    shuffle(&mut [0; 8], || {
+        calls += 1;
+        0
+    });
+    assert_eq!(calls, 7);
+    shuffle(&mut [0_u8; 0], || unreachable!());
+}
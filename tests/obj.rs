@@ -0,0 +1,51 @@
+use gba_cell::obj::{ObjX, ObjY};
+
+#[test]
+fn x_wraps_into_nine_bits() {
+    assert_eq!(ObjX::wrapping(-8).bits(), 504);
+    assert_eq!(ObjX::wrapping(-8).to_screen(), -8);
+    assert_eq!(ObjX::wrapping(255).to_screen(), 255);
+    assert_eq!(ObjX::wrapping(256).to_screen(), -256);
+    assert_eq!(ObjX::wrapping(512 + 3).to_screen(), 3);
+    assert_eq!(ObjX::from_bits(0xFFFF).bits(), 0x1FF);
+    assert_eq!(ObjX::from_bits(0x1FF).to_screen(), -1);
+    for x in -256..256 {
+        assert_eq!(ObjX::wrapping(x).to_screen(), x);
+        assert_eq!(ObjX::from_bits(ObjX::wrapping(x).bits()), ObjX::wrapping(x));
+    }
+}
+
+#[test]
+fn x_offscreen() {
+    assert_eq!(ObjX::from_screen(-16, 16), ObjX::OFFSCREEN);
+    assert_eq!(ObjX::from_screen(-15, 16).to_screen(), -15);
+    assert_eq!(ObjX::from_screen(239, 16).to_screen(), 239);
+    assert_eq!(ObjX::from_screen(240, 16), ObjX::OFFSCREEN);
+    assert_eq!(ObjX::from_screen(1000, 8), ObjX::OFFSCREEN);
+    assert_eq!(ObjX::from_screen(-1000, 64), ObjX::OFFSCREEN);
+    assert_eq!(ObjX::OFFSCREEN.to_screen(), 240);
+}
+
+#[test]
+fn y_wraps_by_height() {
+    assert_eq!(ObjY::wrapping(-8).bits(), 248);
+    // An 8 pixel sprite at 248 fits below the screen without wrapping, but a
+    // 16 pixel one crosses 256 and is drawn from the top.
+    assert_eq!(ObjY::wrapping(-8).to_screen(8), 248);
+    assert_eq!(ObjY::wrapping(-8).to_screen(16), -8);
+    assert_eq!(ObjY::wrapping(256 + 5).to_screen(8), 5);
+    for height in [8, 16, 32, 64] {
+        for y in -(i32::from(height)) + 1..160 {
+            assert_eq!(ObjY::from_screen(y, height).to_screen(height), y);
+        }
+    }
+}
+
+#[test]
+fn y_offscreen() {
+    assert_eq!(ObjY::from_screen(-64, 64), ObjY::OFFSCREEN);
+    assert_eq!(ObjY::from_screen(160, 8), ObjY::OFFSCREEN);
+    assert_eq!(ObjY::from_screen(300, 8), ObjY::OFFSCREEN);
+    // Even a 64 pixel sprite at the offscreen spot can't wrap into view.
+    assert_eq!(ObjY::OFFSCREEN.to_screen(64), 160);
+}
@@ -0,0 +1,149 @@
+use gba_cell::path::{OpenEntry, PathNode, Pathfinder, Search};
+
+/// A map drawn as text: `#` can't be entered, and digits are tile costs.
+struct Map(&'static [&'static str]);
+impl Map {
+    fn width(&self) -> u16 {
+        self.0[0].len() as u16
+    }
+    fn height(&self) -> u16 {
+        self.0.len() as u16
+    }
+    fn cost(&self, x: u16, y: u16) -> Option<u8> {
+        match self.0[usize::from(y)].as_bytes()[usize::from(x)] {
+            b'#' => None,
+            digit => Some(digit - b'0'),
+        }
+    }
+    /// The cost of following a path without diagonals.
+    fn path_cost(&self, path: &[(u16, u16)]) -> u32 {
+        path[1..]
+            .iter()
+            .map(|&(x, y)| 2 * u32::from(self.cost(x, y).unwrap()))
+            .sum()
+    }
+}
+
+/// Runs a whole search, returning the status and any path found.
+fn search(
+    map: &Map,
+    dijkstra: bool,
+    from: (u16, u16),
+    to: (u16, u16),
+) -> (Search, Vec<(u16, u16)>) {
+    let mut nodes = [PathNode::EMPTY; 64];
+    let mut open = [OpenEntry::EMPTY; 32];
+    let mut finder = Pathfinder::new(map.width(), map.height(), &mut nodes, &mut open);
+    if dijkstra {
+        finder = finder.dijkstra();
+    }
+    finder.start(from, to);
+    let status = finder.step(usize::MAX, |x, y| map.cost(x, y));
+    let mut out = [(0, 0); 64];
+    let path = finder.path(&mut out).map(<[_]>::to_vec).unwrap_or_default();
+    (status, path)
+}
+
+const WALLED: Map = Map(&[
+    "11111", //
+    "1###1", //
+    "11#11", //
+    "11#11", //
+]);
+
+#[test]
+fn shortest_path_around_a_wall() {
+    for dijkstra in [false, true] {
+        let (status, path) = search(&WALLED, dijkstra, (1, 3), (3, 3));
+        assert_eq!(status, Search::Found);
+        assert_eq!(path.first(), Some(&(1, 3)));
+        assert_eq!(path.last(), Some(&(3, 3)));
+        // Up the left side, across the top, and down the right side.
+        assert_eq!(path.len(), 13);
+        assert_eq!(WALLED.path_cost(&path), 24);
+    }
+}
+
+#[test]
+fn no_path() {
+    let map = Map(&[
+        "111#1", //
+        "111#1", //
+        "111#1", //
+    ]);
+    for dijkstra in [false, true] {
+        assert_eq!(
+            search(&map, dijkstra, (0, 0), (4, 2)),
+            (Search::NoPath, vec![])
+        );
+    }
+    // A goal that can't be entered is never found either.
+    assert_eq!(search(&map, false, (0, 0), (3, 1)).0, Search::NoPath);
+}
+
+#[test]
+fn budget_spreads_the_search() {
+    let mut nodes = [PathNode::EMPTY; 64];
+    let mut open = [OpenEntry::EMPTY; 32];
+    let mut finder = Pathfinder::new(WALLED.width(), WALLED.height(), &mut nodes, &mut open);
+    assert_eq!(finder.status(), Search::Idle);
+    finder.start((1, 3), (3, 3));
+    let mut calls = 0;
+    while finder.step(1, |x, y| WALLED.cost(x, y)) == Search::Searching {
+        calls += 1;
+    }
+    assert!(calls > 5);
+    assert_eq!(finder.status(), Search::Found);
+    let mut out = [(0, 0); 64];
+    assert_eq!(finder.path(&mut out).map(<[_]>::len), Some(13));
+    assert_eq!(finder.path(&mut out[..12]), None);
+}
+
+#[test]
+fn open_list_limit() {
+    let mut nodes = [PathNode::EMPTY; 64];
+    let mut open = [OpenEntry::EMPTY; 1];
+    let mut finder = Pathfinder::new(WALLED.width(), WALLED.height(), &mut nodes, &mut open);
+    finder.start((1, 3), (3, 3));
+    assert_eq!(
+        finder.step(100, |x, y| WALLED.cost(x, y)),
+        Search::OutOfMemory
+    );
+}
+
+#[test]
+fn dijkstra_takes_free_tiles() {
+    // The top row is free, so going around it beats the direct route.
+    let map = Map(&[
+        "00000", //
+        "11111", //
+    ]);
+    let (status, path) = search(&map, true, (0, 1), (4, 1));
+    assert_eq!(status, Search::Found);
+    assert_eq!(
+        path,
+        [(0, 1), (0, 0), (1, 0), (2, 0), (3, 0), (4, 0), (4, 1)]
+    );
+    assert_eq!(map.path_cost(&path), 2);
+    // A* still finds a path, just not necessarily the cheapest one.
+    let (status, path) = search(&map, false, (0, 1), (4, 1));
+    assert_eq!(status, Search::Found);
+    assert!(map.path_cost(&path) >= 2);
+}
+
+#[test]
+fn long_thin_map() {
+    // The estimate from one end to the other is more than `u16::MAX`.
+    const LEN: u16 = 40_000;
+    let mut nodes = vec![PathNode::EMPTY; usize::from(LEN)];
+    let mut open = [OpenEntry::EMPTY; 4];
+    for diagonals in [false, true] {
+        let mut finder = Pathfinder::new(LEN, 1, &mut nodes, &mut open).with_diagonals(diagonals);
+        finder.start((0, 0), (LEN - 1, 0));
+        assert_eq!(finder.step(usize::MAX, |_, _| Some(1)), Search::Found);
+        let mut out = vec![(0, 0); usize::from(LEN)];
+        let path = finder.path(&mut out).unwrap();
+        assert_eq!(path.len(), usize::from(LEN));
+        assert_eq!(path.last(), Some(&(LEN - 1, 0)));
+    }
+}
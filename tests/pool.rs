@@ -0,0 +1,65 @@
+use gba_cell::pool::Pool;
+
+#[test]
+fn spawn_until_exhausted_then_reuse() {
+    let mut pool = Pool::<u8, 3>::new();
+    let handles: Vec<_> = (0..3).map(|i| pool.spawn(i).unwrap()).collect();
+    assert!(pool.is_full());
+    assert_eq!(pool.spawn(9), Err(9));
+    assert_eq!(
+        handles.iter().map(|h| h.index()).collect::<Vec<_>>(),
+        [0, 1, 2]
+    );
+
+    assert_eq!(pool.despawn(handles[1]), Some(1));
+    assert_eq!(pool.len(), 2);
+    // The lowest free slot is reused, but the old handle stays invalid.
+    let reused = pool.spawn(7).unwrap();
+    assert_eq!(reused.index(), 1);
+    assert_ne!(reused, handles[1]);
+    assert_eq!(pool.get(handles[1]), None);
+    assert_eq!(pool.despawn(handles[1]), None);
+    assert_eq!(pool.get(reused), Some(&7));
+    assert_eq!(pool.spawn(9), Err(9));
+
+    pool.clear();
+    assert!(pool.is_empty());
+    assert!(handles.iter().all(|&h| !pool.contains(h)));
+    assert!(!pool.contains(reused));
+}
+
+#[test]
+fn deferred_despawn() {
+    let mut pool = Pool::<u32, 4>::new();
+    for i in 0..4 {
+        pool.spawn(i * 10).unwrap();
+    }
+    let doomed: Vec<_> = pool
+        .iter()
+        .filter(|(_, &v)| v >= 20)
+        .map(|(h, _)| h)
+        .collect();
+    for &h in &doomed {
+        assert!(pool.despawn_later(h));
+        assert!(pool.is_despawning(h));
+    }
+    // Marked objects are still there until the flush.
+    assert_eq!(pool.iter().count(), 4);
+    assert_eq!(pool.flush(), 2);
+    assert_eq!(pool.flush(), 0);
+    assert_eq!(pool.iter().map(|(_, &v)| v).collect::<Vec<_>>(), [0, 10]);
+    assert!(!pool.despawn_later(doomed[0]));
+}
+
+#[test]
+fn retain_and_iter_mut() {
+    let mut pool = Pool::<i32, 5>::new();
+    for i in 0..5 {
+        pool.spawn(i).unwrap();
+    }
+    pool.iter_mut().for_each(|(_, v)| *v *= 3);
+    pool.retain(|_, v| *v % 2 == 0);
+    assert_eq!(pool.len(), 3);
+    let left: Vec<_> = pool.iter().map(|(h, &v)| (h.index(), v)).collect();
+    assert_eq!(left, [(0, 0), (2, 6), (4, 12)]);
+}
@@ -0,0 +1,52 @@
+use gba_cell::ratio::{Blend16, Coeff31, Volume7};
+
+#[test]
+fn percent_round_trip() {
+    for value in 0..=Blend16::MAX {
+        let ratio = Blend16::new(value);
+        assert_eq!(Blend16::from_percent(ratio.to_percent()), ratio);
+    }
+    for value in 0..=Volume7::MAX {
+        let ratio = Volume7::new(value);
+        assert_eq!(Volume7::from_percent(ratio.to_percent()), ratio);
+    }
+    for value in 0..=Coeff31::MAX {
+        let ratio = Coeff31::new(value);
+        assert_eq!(Coeff31::from_percent(ratio.to_percent()), ratio);
+    }
+}
+
+#[test]
+fn percent_rounds_to_nearest() {
+    assert_eq!(Blend16::from_percent(50).get(), 8);
+    assert_eq!(Blend16::from_percent(3).get(), 0);
+    assert_eq!(Blend16::from_percent(4).get(), 1);
+    assert_eq!(Blend16::new(1).to_percent(), 6);
+    assert_eq!(Volume7::from_percent(50).get(), 4);
+    assert_eq!(Volume7::new(4).to_percent(), 57);
+}
+
+#[test]
+fn clamps() {
+    assert_eq!(Blend16::from_percent(101), Blend16::FULL);
+    assert_eq!(Blend16::from_percent(u8::MAX), Blend16::FULL);
+    assert_eq!(Blend16::new(17), Blend16::FULL);
+    assert_eq!(Blend16::from_fraction(u16::MAX), Blend16::FULL);
+    assert_eq!(Volume7::new(u8::MAX).get(), 7);
+    assert_eq!(Coeff31::from_percent(200).to_percent(), 100);
+}
+
+#[test]
+fn fractions() {
+    assert_eq!(Blend16::from_fraction(128).get(), 8);
+    assert_eq!(Blend16::new(8).to_fraction(), 128);
+    assert_eq!(Blend16::FULL.to_fraction(), 256);
+    assert_eq!(Coeff31::from_fraction(0), Coeff31::ZERO);
+}
+
+#[test]
+fn complement() {
+    assert_eq!(Blend16::new(4).complement().get(), 12);
+    assert_eq!(Blend16::ZERO.complement(), Blend16::FULL);
+    assert_eq!(Volume7::FULL.complement(), Volume7::ZERO);
+}
@@ -0,0 +1,58 @@
+use gba_cell::scene::{SceneStack, Transition};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Scene {
+    Title,
+    Play,
+    Pause,
+    Options,
+}
+
+#[test]
+fn push_pop_and_capacity() {
+    let mut stack = SceneStack::<Scene, 2>::new();
+    assert!(stack.is_empty());
+    assert_eq!(stack.top(), None);
+    assert_eq!(stack.pop(), None);
+
+    assert_eq!(stack.push(Scene::Play), Ok(()));
+    assert_eq!(stack.push(Scene::Pause), Ok(()));
+    assert_eq!(stack.push(Scene::Options), Err(Scene::Options));
+    assert_eq!(stack.len(), 2);
+    assert_eq!(stack.top(), Some(&Scene::Pause));
+    assert_eq!(
+        stack.iter().copied().collect::<Vec<_>>(),
+        [Scene::Play, Scene::Pause]
+    );
+
+    // A full stack can still have its top replaced.
+    assert_eq!(stack.replace(Scene::Options), Ok(Some(Scene::Pause)));
+    assert_eq!(stack.pop(), Some(Scene::Options));
+    assert_eq!(stack.pop(), Some(Scene::Play));
+    assert!(stack.is_empty());
+    assert_eq!(stack.replace(Scene::Title), Ok(None));
+    assert_eq!(stack.top(), Some(&Scene::Title));
+}
+
+#[test]
+fn apply_transitions() {
+    let mut stack = SceneStack::<Scene, 2>::new();
+    stack.apply(Transition::Push(Scene::Title)).unwrap();
+    stack.apply(Transition::Replace(Scene::Play)).unwrap();
+    stack.apply(Transition::Stay).unwrap();
+    stack.apply(Transition::Push(Scene::Pause)).unwrap();
+    assert_eq!(
+        stack.apply(Transition::Push(Scene::Options)),
+        Err(Scene::Options)
+    );
+    assert_eq!(stack.len(), 2);
+
+    if let Some(top) = stack.top_mut() {
+        *top = Scene::Options;
+    }
+    stack.apply(Transition::Pop).unwrap();
+    assert_eq!(stack.top(), Some(&Scene::Play));
+    stack.apply(Transition::Pop).unwrap();
+    stack.apply(Transition::Pop).unwrap();
+    assert!(stack.is_empty());
+}
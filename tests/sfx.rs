@@ -0,0 +1,61 @@
+use gba_cell::sfx::{Allocation, SfxId, VoiceAllocator};
+
+#[test]
+fn idle_voices_are_used_first() {
+    let mut voices = VoiceAllocator::<2>::new(0);
+    assert_eq!(voices.play(SfxId(1), 0, 0), Some(Allocation::Free(0)));
+    assert_eq!(voices.play(SfxId(2), 0, 0), Some(Allocation::Free(1)));
+    assert_eq!(voices.playing(0), Some(SfxId(1)));
+    assert_eq!(voices.playing(1), Some(SfxId(2)));
+    assert_eq!(voices.playing(2), None);
+
+    voices.release(0);
+    assert_eq!(voices.playing(0), None);
+    assert_eq!(voices.play(SfxId(3), 0, 1), Some(Allocation::Free(0)));
+}
+
+#[test]
+fn steals_lowest_priority_then_oldest() {
+    let mut voices = VoiceAllocator::<3>::new(0);
+    voices.play(SfxId(1), 5, 10);
+    voices.play(SfxId(2), 1, 20);
+    voices.play(SfxId(3), 1, 30);
+
+    // Both priority 1 voices are candidates, and the older one goes.
+    assert_eq!(
+        voices.play(SfxId(4), 1, 40),
+        Some(Allocation::Stolen(1, SfxId(2)))
+    );
+    assert_eq!(
+        voices.play(SfxId(5), 3, 50),
+        Some(Allocation::Stolen(2, SfxId(3)))
+    );
+    // Everything left outranks a priority 0 effect.
+    assert_eq!(voices.play(SfxId(6), 0, 60), None);
+    assert_eq!(voices.play(SfxId(6), 9, 60).map(Allocation::voice), Some(1));
+}
+
+#[test]
+fn oldest_is_found_across_wrapping_time() {
+    let mut voices = VoiceAllocator::<2>::new(0);
+    voices.play(SfxId(1), 0, u32::MAX - 1);
+    voices.play(SfxId(2), 0, 1);
+    assert_eq!(
+        voices.play(SfxId(3), 0, 2),
+        Some(Allocation::Stolen(0, SfxId(1)))
+    );
+}
+
+#[test]
+fn retrigger_window() {
+    let mut voices = VoiceAllocator::<4>::new(3);
+    assert!(voices.play(SfxId(7), 0, u32::MAX).is_some());
+    assert_eq!(voices.play(SfxId(7), 0, 0), None);
+    assert_eq!(voices.play(SfxId(7), 0, 1), None);
+    assert_eq!(voices.play(SfxId(7), 0, 2), Some(Allocation::Free(1)));
+    // Other effects aren't held back.
+    assert_eq!(voices.play(SfxId(8), 0, 2), Some(Allocation::Free(2)));
+
+    voices.release_all();
+    assert_eq!(voices.play(SfxId(7), 0, 3), Some(Allocation::Free(0)));
+}
@@ -0,0 +1,41 @@
+use gba_cell::snapshot::{self, Region, SnapshotError};
+
+#[test]
+fn round_trip_and_corruption() {
+    let mut state = [1_u8, 2, 3, 4, 5];
+    // SAFETY: `state` outlives the region, and any bytes are valid.
+    let regions = [unsafe { Region::new(state.as_mut_ptr(), state.len()) }];
+    let mut buffer = [0_u8; 32];
+    let used = snapshot::save(&regions, &mut buffer).unwrap();
+    assert_eq!(used, snapshot::snapshot_len(&regions));
+    assert_eq!(used, 12 + 5);
+
+    state = [0; 5];
+    assert_eq!(snapshot::restore(&regions, &buffer[..used]), Ok(()));
+    assert_eq!(state, [1, 2, 3, 4, 5]);
+
+    buffer[14] ^= 0x40;
+    state = [9; 5];
+    assert_eq!(
+        snapshot::restore(&regions, &buffer[..used]),
+        Err(SnapshotError::BadChecksum)
+    );
+    assert_eq!(state, [9; 5]);
+    assert_eq!(
+        snapshot::save(&regions, &mut [0; 16]),
+        Err(SnapshotError::BufferTooSmall)
+    );
+}
+
+#[test]
+fn huge_header_length_is_a_mismatch() {
+    let mut state = [7_u8; 4];
+    // SAFETY: as above.
+    let regions = [unsafe { Region::new(state.as_mut_ptr(), state.len()) }];
+    let mut header = [0_u8; 12];
+    header[..4].copy_from_slice(b"GSNP");
+    header[4..8].copy_from_slice(&u32::MAX.to_le_bytes());
+    let result = snapshot::restore_with(&regions, |i| header.get(i).copied().unwrap_or(0));
+    assert_eq!(result, Err(SnapshotError::SizeMismatch));
+    assert_eq!(state, [7; 4]);
+}
@@ -0,0 +1,67 @@
+use gba_cell::timer::{Cooldown, Stopwatch};
+
+#[test]
+fn cooldown_counts_down() {
+    let mut cooldown = Cooldown::new(3);
+    assert!(!cooldown.ready());
+    assert!(!cooldown.tick());
+    assert!(!cooldown.tick());
+    assert!(cooldown.tick());
+    // It stays ready rather than wrapping.
+    assert!(cooldown.tick());
+    assert_eq!(cooldown, Cooldown::READY);
+
+    cooldown.reset(1);
+    assert_eq!(cooldown.remaining(), 1);
+}
+
+#[test]
+fn cooldown_remaining_rounds_up() {
+    let mut cooldown = Cooldown::new(2);
+    assert_eq!(cooldown.remaining(), 2);
+    assert!(!cooldown.tick_by(1));
+    assert_eq!(cooldown.remaining(), 2);
+    assert!(!cooldown.tick_by(255));
+    assert_eq!(cooldown.remaining(), 1);
+    assert!(!cooldown.tick_by(255));
+    assert_eq!(cooldown.remaining(), 1);
+    assert!(cooldown.tick_by(1));
+    assert_eq!(cooldown.remaining(), 0);
+}
+
+#[test]
+fn cooldown_from_millis() {
+    assert_eq!(Cooldown::from_millis(0), Cooldown::READY);
+    assert_eq!(Cooldown::from_millis(1000).remaining(), 60);
+    assert_eq!(Cooldown::from_millis(100).remaining(), 6);
+    assert_eq!(Cooldown::from_millis(17).remaining(), 1);
+}
+
+#[test]
+fn stopwatch_frames_round_down() {
+    let mut watch = Stopwatch::new();
+    watch.tick_by(255);
+    assert_eq!(watch.frames(), 0);
+    assert!(!watch.has_elapsed(1));
+    watch.tick_by(1);
+    assert_eq!(watch.frames(), 1);
+    assert!(watch.has_elapsed(1));
+
+    watch.reset();
+    assert_eq!(watch, Stopwatch::new());
+}
+
+#[test]
+fn stopwatch_millis_rounds_down() {
+    let mut watch = Stopwatch::new();
+    assert_eq!(watch.millis(), 0);
+    watch.tick_by(128);
+    assert_eq!(watch.millis(), 8);
+    watch.tick_by(128);
+    assert_eq!(watch.millis(), 16);
+    for _ in 1..60 {
+        watch.tick();
+    }
+    assert_eq!(watch.frames(), 60);
+    assert_eq!(watch.millis(), 1004);
+}
@@ -0,0 +1,13 @@
+use gba_cell::{GbaCell, GbaCellSafe};
+
+#[derive(Clone, Copy)]
+struct Pair(u16, u16);
+
+// Wrong: `Pair` is 4 bytes but only 2-byte aligned.
+unsafe impl GbaCellSafe for Pair {}
+
+static PAIR: GbaCell<Pair> = GbaCell::new(Pair(0, 0));
+
+fn main() {
+    let _ = &PAIR;
+}
@@ -0,0 +1,8 @@
+use gba_cell::dirty::DirtyRects;
+
+// A set with no room would silently drop every region.
+static DIRTY: DirtyRects<0> = DirtyRects::new();
+
+fn main() {
+    let _ = &DIRTY;
+}
@@ -0,0 +1,11 @@
+use gba_cell::fn_cell::FnArgs;
+
+struct MyArgs;
+
+// Only argument tuples can pick a `GbaFnCell` pointer type.
+impl FnArgs<()> for MyArgs {
+    type Ptr = Option<extern "C" fn()>;
+    const NONE: Self::Ptr = None;
+}
+
+fn main() {}
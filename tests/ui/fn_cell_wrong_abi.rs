@@ -0,0 +1,10 @@
+use gba_cell::GbaFnCell;
+
+static CALLBACK: GbaFnCell<(), ()> = GbaFnCell::new();
+
+fn rust_abi() {}
+
+fn main() {
+    // Only `extern "C"` functions can be stored.
+    CALLBACK.set(Some(rust_abi));
+}
@@ -0,0 +1,16 @@
+use gba_cell::{
+    asset,
+    assets::{Asset, AssetDir},
+};
+
+const ASSETS: AssetDir<'static> = match AssetDir::new(b"ADIR\0\0\0\0") {
+    Ok(dir) => dir,
+    Err(_) => panic!(),
+};
+
+// A misspelled asset name is caught at compile time.
+const HERO: Asset<'static> = asset!(ASSETS, "sprites/hreo");
+
+fn main() {
+    let _ = HERO;
+}
@@ -0,0 +1,9 @@
+use gba_cell::transaction::{transaction, IrqToken};
+
+fn main() {
+    let mut token = IrqToken::take().unwrap();
+    transaction(&mut token, |_| {
+        // Wrong: the outer transaction is already using the token.
+        transaction(&mut token, |_| {});
+    });
+}
@@ -0,0 +1,7 @@
+use gba_cell::GbaCell;
+
+static THREE_BYTES: GbaCell<[u8; 3]> = GbaCell::new([0; 3]);
+
+fn main() {
+    let _ = &THREE_BYTES;
+}
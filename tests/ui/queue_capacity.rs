@@ -0,0 +1,8 @@
+use gba_cell::GbaQueue;
+
+// Queue capacities must be a power of two.
+static QUEUE: GbaQueue<u8, 3> = GbaQueue::new();
+
+fn main() {
+    let _ = &QUEUE;
+}
@@ -0,0 +1,8 @@
+use gba_cell::GbaQueue;
+
+// Values are read out by copying, so they must be `Copy`.
+static QUEUE: GbaQueue<String, 4> = GbaQueue::new();
+
+fn main() {
+    let _ = &QUEUE;
+}
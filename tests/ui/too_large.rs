@@ -0,0 +1,5 @@
+use gba_cell::GbaCell;
+
+fn main() {
+    let _ = GbaCell::new(0_u128);
+}
@@ -0,0 +1,7 @@
+use gba_cell::GbaCell;
+
+fn main() {
+    // Four bytes, but only 2-byte aligned.
+    let cell = GbaCell::new([0_u16; 2]);
+    let _ = cell.read();
+}
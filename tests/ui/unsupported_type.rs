@@ -0,0 +1,6 @@
+use gba_cell::GbaCell;
+
+fn main() {
+    // `String` is neither small enough nor `Copy`.
+    let _ = GbaCell::new(String::new());
+}
@@ -0,0 +1,14 @@
+use gba_cell::{GbaCell, GbaCellSafe};
+
+#[derive(Clone, Copy)]
+#[repr(transparent)]
+struct Wide(u64);
+
+// Wrong: `Wide` is 8 bytes, even on hosts where that's the size of a pointer.
+unsafe impl GbaCellSafe for Wide {}
+
+static WIDE: GbaCell<Wide> = GbaCell::new(Wide(0));
+
+fn main() {
+    let _ = &WIDE;
+}